@@ -16,6 +16,8 @@ module MethodRay
         Commands.check(args)
       when 'watch'
         Commands.watch(args)
+      when 'explain'
+        Commands.explain(args)
       when 'clear-cache'
         Commands.clear_cache(args)
       else
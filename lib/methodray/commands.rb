@@ -12,12 +12,15 @@ module MethodRay
           Usage:
             methodray help                    # Show this help
             methodray version                 # Show version
-            methodray check [FILE] [OPTIONS]  # Type check a Ruby file
+            methodray check [FILE] [OPTIONS]  # Type check a Ruby file (or the whole project)
             methodray watch FILE              # Watch file for changes and auto-check
+            methodray explain [CODE]          # Explain a diagnostic code (e.g. MR001)
             methodray clear-cache             # Clear RBS method cache
 
           Examples:
             methodray check app/models/user.rb
+            methodray check --generate-baseline
+            methodray check --severity MR003=off
             methodray watch app/models/user.rb
         HELP
       end
@@ -34,6 +37,10 @@ module MethodRay
         exec_rust_cli('watch', args)
       end
 
+      def explain(args)
+        exec_rust_cli('explain', args)
+      end
+
       def clear_cache(args)
         exec_rust_cli('clear-cache', args)
       end
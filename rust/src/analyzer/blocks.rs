@@ -4,9 +4,10 @@
 //! - Processing BlockNode (e.g., `{ |x| x.to_s }` or `do |x| x.to_s end`)
 //! - Registering block parameters as local variables
 //! - Managing block scope
+//! - Yielding to the block of a user-defined method (`yield`, `block.call`)
 
 use crate::env::{GlobalEnv, LocalEnv, ScopeKind};
-use crate::graph::VertexId;
+use crate::graph::{ChangeSet, VertexId};
 
 use super::parameters::install_required_parameter;
 
@@ -33,11 +34,36 @@ pub fn exit_block_scope(genv: &mut GlobalEnv) {
 /// ```ruby
 /// [1, 2, 3].each { |x| x.to_s }  # 'x' is a block parameter
 /// ```
-pub fn install_block_parameter(genv: &mut GlobalEnv, lenv: &mut LocalEnv, name: String) -> VertexId {
+pub fn install_block_parameter(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    name: String,
+) -> VertexId {
     // Reuse required parameter logic (Bot type)
     install_required_parameter(genv, lenv, name)
 }
 
+/// Install `yield args` (or `block.call(args)`) inside a method body
+///
+/// Each argument flows into the method's yielded value vertex at the same
+/// position, which callers connect to their block parameters.
+/// Returns the value of the yield (the block's result, unknown here).
+pub fn install_yield(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    yield_vtxs: &mut Vec<VertexId>,
+    arg_vtxs: Vec<VertexId>,
+) -> VertexId {
+    for (i, arg_vtx) in arg_vtxs.into_iter().enumerate() {
+        if i == yield_vtxs.len() {
+            yield_vtxs.push(genv.new_vertex());
+        }
+        changes.add_edge(arg_vtx, yield_vtxs[i]);
+    }
+
+    genv.new_vertex()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,4 +117,31 @@ mod tests {
 
         exit_block_scope(&mut genv);
     }
+
+    #[test]
+    fn test_install_yield_collects_by_position() {
+        let mut genv = GlobalEnv::new();
+        let mut changes = ChangeSet::new();
+        let mut yield_vtxs = Vec::new();
+
+        // yield "a", 1
+        let str_src = genv.new_source(crate::types::Type::string());
+        let int_src = genv.new_source(crate::types::Type::integer());
+        install_yield(
+            &mut genv,
+            &mut changes,
+            &mut yield_vtxs,
+            vec![str_src, int_src],
+        );
+
+        // yield "b"
+        let other_src = genv.new_source(crate::types::Type::string());
+        install_yield(&mut genv, &mut changes, &mut yield_vtxs, vec![other_src]);
+
+        genv.apply_changes(changes);
+
+        assert_eq!(yield_vtxs.len(), 2);
+        assert_eq!(genv.get_vertex(yield_vtxs[0]).unwrap().show(), "String");
+        assert_eq!(genv.get_vertex(yield_vtxs[1]).unwrap().show(), "Integer");
+    }
 }
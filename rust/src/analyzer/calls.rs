@@ -1,32 +1,179 @@
 //! Method Call Handlers - Processing Ruby method calls
 //!
 //! This module is responsible for:
-//! - Creating MethodCallBox for method invocations (x.upcase)
+//! - Creating MethodCallBox for method invocations (x.upcase, x&.upcase)
+//! - Creating IndexAccessBox for element access (opts[:name])
+//! - Creating SuperCallBox for `super` calls
+//! - Connecting element writes (opts[:name] = value, list << value) to the container
 //! - Managing return value vertices
 //! - Attaching source location for error reporting
 
 use crate::env::GlobalEnv;
-use crate::graph::{MethodCallBox, VertexId};
+use crate::graph::{
+    ChangeSet, ElementMutationBox, IndexAccessBox, IndexKey, MethodCallBox, SuperCallBox, VertexId,
+};
 use crate::source_map::SourceLocation;
 
 /// Install method call and return the return value's VertexId
+///
+/// `arg_vtxs` are the positional arguments; `self_receiver` is true for an
+/// explicit `self` receiver (`self.foo`).
 pub fn install_method_call(
     genv: &mut GlobalEnv,
     recv_vtx: VertexId,
     method_name: String,
+    arg_vtxs: Vec<VertexId>,
     location: Option<SourceLocation>,
+    self_receiver: bool,
 ) -> VertexId {
     // Create Vertex for return value
     let ret_vtx = genv.new_vertex();
 
-    // Create MethodCallBox with location
+    // Create MethodCallBox with location and the calling class (for visibility checks)
     let box_id = genv.alloc_box_id();
-    let call_box = MethodCallBox::new(box_id, recv_vtx, method_name, ret_vtx, location);
+    let caller_class = genv.scope_manager.current_class_name();
+    let call_box = MethodCallBox::new(box_id, recv_vtx, method_name, ret_vtx, location)
+        .with_arguments(arg_vtxs)
+        .called_from(caller_class, self_receiver);
     genv.register_box(box_id, Box::new(call_box));
 
     ret_vtx
 }
 
+/// Record an in-place mutation of the receiver's elements (list << value,
+/// opts.merge!(other))
+///
+/// Whether the call mutates elements depends on the receiver: it is decided
+/// by an ElementMutationBox once the receiver's type is known.
+pub fn install_element_mutation(
+    genv: &mut GlobalEnv,
+    recv_vtx: VertexId,
+    method_name: &str,
+    arg_vtxs: &[VertexId],
+) {
+    if !ElementMutationBox::is_mutation(method_name) {
+        return;
+    }
+
+    let box_id = genv.alloc_box_id();
+    let mutation_box =
+        ElementMutationBox::new(box_id, recv_vtx, method_name.to_string(), arg_vtxs.to_vec());
+    genv.register_box(box_id, Box::new(mutation_box));
+}
+
+/// Install safe navigation call (x&.upcase) and return the return value's VertexId
+///
+/// Nil receivers are skipped and contribute nil to the return value.
+pub fn install_safe_navigation_call(
+    genv: &mut GlobalEnv,
+    recv_vtx: VertexId,
+    method_name: String,
+    arg_vtxs: Vec<VertexId>,
+    location: Option<SourceLocation>,
+    self_receiver: bool,
+) -> VertexId {
+    let ret_vtx = genv.new_vertex();
+
+    let box_id = genv.alloc_box_id();
+    let caller_class = genv.scope_manager.current_class_name();
+    let call_box = MethodCallBox::new(box_id, recv_vtx, method_name, ret_vtx, location)
+        .with_safe_navigation()
+        .with_arguments(arg_vtxs)
+        .called_from(caller_class, self_receiver);
+    genv.register_box(box_id, Box::new(call_box));
+
+    ret_vtx
+}
+
+/// Install `super` inside a method and return the return value's VertexId
+///
+/// Only methods of a class are resolved; `super` in a module method depends
+/// on where the module is included.
+pub fn install_super_call(
+    genv: &mut GlobalEnv,
+    method_name: String,
+    singleton: bool,
+    arg_vtxs: Vec<VertexId>,
+    location: Option<SourceLocation>,
+) -> VertexId {
+    let ret_vtx = genv.new_vertex();
+
+    let Some(class_name) = genv.scope_manager.current_class_name() else {
+        return ret_vtx;
+    };
+
+    let box_id = genv.alloc_box_id();
+    let super_box = SuperCallBox::new(
+        box_id,
+        class_name,
+        singleton,
+        method_name,
+        arg_vtxs,
+        ret_vtx,
+        location,
+    );
+    genv.register_box(box_id, Box::new(super_box));
+
+    ret_vtx
+}
+
+/// Install element access (opts[:name]) and return the return value's VertexId
+///
+/// Values written to the same container with `[]=` are part of the result.
+pub fn install_index_access(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    recv_vtx: VertexId,
+    key: Option<IndexKey>,
+    location: Option<SourceLocation>,
+) -> VertexId {
+    let ret_vtx = genv.new_vertex();
+
+    let elem_vtx = genv.element_vertex(recv_vtx);
+    changes.add_edge(elem_vtx, ret_vtx);
+
+    let box_id = genv.alloc_box_id();
+    let index_box = IndexAccessBox::new(box_id, recv_vtx, key, ret_vtx, location);
+    genv.register_box(box_id, Box::new(index_box));
+    genv.add_element_reader(recv_vtx, box_id);
+
+    ret_vtx
+}
+
+/// Install element write (opts[:name] = value) and return the value's VertexId
+///
+/// The value widens the container's element vertex; `[]=` itself is still
+/// checked as a method call on the receiver.
+pub fn install_index_write(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    recv_vtx: VertexId,
+    key: Option<IndexKey>,
+    value_vtx: VertexId,
+    location: Option<SourceLocation>,
+) -> VertexId {
+    let elem_vtx = genv.element_vertex(recv_vtx);
+    changes.add_edge(value_vtx, elem_vtx);
+
+    let written_key = match key {
+        Some(IndexKey::Symbol(key)) => Some(key),
+        _ => None,
+    };
+    genv.record_element_write(changes, recv_vtx, written_key);
+
+    install_method_call(
+        genv,
+        recv_vtx,
+        "[]=".to_string(),
+        Vec::new(),
+        location,
+        false,
+    );
+
+    // `recv[key] = value` evaluates to value
+    value_vtx
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,7 +184,14 @@ mod tests {
         let mut genv = GlobalEnv::new();
 
         let recv_vtx = genv.new_source(Type::string());
-        let ret_vtx = install_method_call(&mut genv, recv_vtx, "upcase".to_string(), None);
+        let ret_vtx = install_method_call(
+            &mut genv,
+            recv_vtx,
+            "upcase".to_string(),
+            Vec::new(),
+            None,
+            false,
+        );
 
         // Return vertex should exist
         assert!(genv.get_vertex(ret_vtx).is_some());
@@ -48,7 +202,14 @@ mod tests {
         let mut genv = GlobalEnv::new();
 
         let recv_vtx = genv.new_source(Type::string());
-        let _ret_vtx = install_method_call(&mut genv, recv_vtx, "upcase".to_string(), None);
+        let _ret_vtx = install_method_call(
+            &mut genv,
+            recv_vtx,
+            "upcase".to_string(),
+            Vec::new(),
+            None,
+            false,
+        );
 
         // Box should be added
         assert_eq!(genv.box_count(), 1);
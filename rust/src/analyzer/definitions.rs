@@ -4,23 +4,95 @@
 //! - Class definition scope management (class Foo ... end)
 //! - Module definition scope management (module Bar ... end)
 //! - Method definition scope management (def baz ... end)
-//! - Extracting class/module names from AST nodes
+//! - Extracting class/module/constant names from AST nodes
 
+use crate::env::method_registry::Visibility;
 use crate::env::GlobalEnv;
+use crate::graph::VertexId;
+use crate::types::Type;
 
 /// Install class definition
-pub fn install_class(genv: &mut GlobalEnv, class_name: String) {
+///
+/// Registers the class with its superclass so inherited methods and
+/// `super` resolve through the ancestor chain.
+pub fn install_class(genv: &mut GlobalEnv, class_name: String, superclass: Option<String>) {
+    genv.register_class(&class_name, superclass);
     genv.enter_class(class_name);
 }
 
 /// Install module definition
 pub fn install_module(genv: &mut GlobalEnv, module_name: String) {
+    genv.register_module(&module_name);
     genv.enter_module(module_name);
 }
 
-/// Install method definition
-pub fn install_method(genv: &mut GlobalEnv, method_name: String) {
-    genv.enter_method(method_name);
+/// Install method definition (`singleton` for `def self.foo`)
+pub fn install_method(genv: &mut GlobalEnv, method_name: String, singleton: bool) {
+    genv.enter_method(method_name, singleton);
+}
+
+/// Register the method being defined in the method registry
+///
+/// Instance methods belong to the enclosing class or module (Object at the
+/// top level); `def self.foo` defines a singleton method.
+pub fn register_user_method(
+    genv: &mut GlobalEnv,
+    method_name: &str,
+    singleton: bool,
+    return_vtx: VertexId,
+    yield_vtxs: Vec<VertexId>,
+    param_vtxs: Vec<VertexId>,
+    visibility: Visibility,
+) {
+    let recv_ty = method_owner_type(genv, singleton);
+    genv.register_user_method(
+        recv_ty,
+        method_name,
+        Some(return_vtx),
+        yield_vtxs,
+        param_vtxs,
+        visibility,
+    );
+}
+
+/// Change the visibility of an instance method of the enclosing class or
+/// module (`private :foo`)
+pub fn set_method_visibility(genv: &mut GlobalEnv, method_name: &str, visibility: Visibility) {
+    let recv_ty = method_owner_type(genv, false);
+    genv.set_method_visibility(&recv_ty, method_name, visibility);
+}
+
+/// Define `new_name` as an alias of an instance method of the enclosing
+/// class or module (`alias new old`, `alias_method :new, :old`)
+pub fn alias_user_method(genv: &mut GlobalEnv, new_name: &str, old_name: &str) {
+    let recv_ty = method_owner_type(genv, false);
+    genv.alias_method(recv_ty, new_name, old_name);
+}
+
+/// Extract a method name given as a symbol or string literal (`:foo`, `"foo"`)
+pub fn literal_method_name(node: &ruby_prism::Node) -> Option<String> {
+    if let Some(symbol) = node.as_symbol_node() {
+        return Some(String::from_utf8_lossy(symbol.unescaped()).to_string());
+    }
+    if let Some(string) = node.as_string_node() {
+        return Some(String::from_utf8_lossy(string.unescaped()).to_string());
+    }
+    None
+}
+
+/// Receiver type of methods defined in the current scope
+fn method_owner_type(genv: &GlobalEnv, singleton: bool) -> Type {
+    let owner = genv
+        .scope_manager
+        .current_class_name()
+        .or_else(|| genv.scope_manager.current_module_name());
+    match owner {
+        Some(class_name) if singleton => Type::Singleton { class_name },
+        Some(class_name) => Type::Instance { class_name },
+        None => Type::Instance {
+            class_name: "Object".to_string(),
+        },
+    }
 }
 
 /// Exit current scope (class, module, or method)
@@ -28,22 +100,40 @@ pub fn exit_scope(genv: &mut GlobalEnv) {
     genv.exit_scope();
 }
 
-/// Extract class name from ClassNode
+/// Extract class name from ClassNode (`class Admin::User` defines `User`)
 pub fn extract_class_name(class_node: &ruby_prism::ClassNode) -> String {
-    if let Some(constant_read) = class_node.constant_path().as_constant_read_node() {
-        String::from_utf8_lossy(constant_read.name().as_slice()).to_string()
-    } else {
-        "UnknownClass".to_string()
-    }
+    declared_name(&class_node.constant_path()).unwrap_or_else(|| "UnknownClass".to_string())
 }
 
 /// Extract module name from ModuleNode
 pub fn extract_module_name(module_node: &ruby_prism::ModuleNode) -> String {
-    if let Some(constant_read) = module_node.constant_path().as_constant_read_node() {
-        String::from_utf8_lossy(constant_read.name().as_slice()).to_string()
-    } else {
-        "UnknownModule".to_string()
+    declared_name(&module_node.constant_path()).unwrap_or_else(|| "UnknownModule".to_string())
+}
+
+/// Last segment of the constant a class or module declares
+fn declared_name(constant_path: &ruby_prism::Node) -> Option<String> {
+    let name = match constant_path.as_constant_read_node() {
+        Some(constant_read) => constant_read.name(),
+        None => constant_path.as_constant_path_node()?.name()?,
+    };
+    Some(String::from_utf8_lossy(name.as_slice()).to_string())
+}
+
+/// Extract the name of a constant reference (`ArgumentError`, `Net::ReadTimeout`)
+///
+/// Returns None for anything that is not a constant.
+pub fn constant_name(node: &ruby_prism::Node, source: &str) -> Option<String> {
+    if let Some(constant) = node.as_constant_read_node() {
+        return Some(String::from_utf8_lossy(constant.name().as_slice()).to_string());
     }
+
+    if node.as_constant_path_node().is_some() {
+        let location = node.location();
+        let text = source.get(location.start_offset()..location.end_offset())?;
+        return Some(text.trim_start_matches("::").to_string());
+    }
+
+    None
 }
 
 #[cfg(test)]
@@ -54,7 +144,7 @@ mod tests {
     fn test_enter_exit_class_scope() {
         let mut genv = GlobalEnv::new();
 
-        install_class(&mut genv, "User".to_string());
+        install_class(&mut genv, "User".to_string(), None);
         assert_eq!(
             genv.scope_manager.current_class_name(),
             Some("User".to_string())
@@ -82,8 +172,8 @@ mod tests {
     fn test_nested_method_scope() {
         let mut genv = GlobalEnv::new();
 
-        install_class(&mut genv, "User".to_string());
-        install_method(&mut genv, "greet".to_string());
+        install_class(&mut genv, "User".to_string(), None);
+        install_method(&mut genv, "greet".to_string(), false);
 
         // Still in User class context
         assert_eq!(
@@ -102,7 +192,7 @@ mod tests {
         let mut genv = GlobalEnv::new();
 
         install_module(&mut genv, "Helpers".to_string());
-        install_method(&mut genv, "format".to_string());
+        install_method(&mut genv, "format".to_string(), false);
 
         // Should find module context from within method
         assert_eq!(
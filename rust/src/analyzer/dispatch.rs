@@ -4,14 +4,20 @@
 //! and dispatches them to specialized handlers.
 
 use crate::env::{GlobalEnv, LocalEnv};
-use crate::graph::{ChangeSet, VertexId};
+use crate::graph::{ChangeSet, IndexKey, VertexId};
 use crate::source_map::SourceLocation;
+use crate::types::Type;
 use ruby_prism::Node;
 
-use super::calls::install_method_call;
+use super::calls::{
+    install_index_access, install_index_write, install_method_call, install_safe_navigation_call,
+};
+use super::literals::install_boolean;
+use super::multi_write::install_multi_write_targets;
 use super::variables::{
-    install_ivar_read, install_ivar_write, install_local_var_read, install_local_var_write,
-    install_self,
+    install_cvar_read, install_cvar_write, install_global_var_read, install_global_var_write,
+    install_ivar_logical_write, install_ivar_read, install_ivar_write,
+    install_local_var_logical_write, install_local_var_read, install_local_var_write, install_self,
 };
 
 /// Result of dispatching a simple node (no child processing needed)
@@ -22,19 +28,116 @@ pub enum DispatchResult {
     NotHandled,
 }
 
+/// Variable assigned by an operator assignment
+pub enum OpWriteTarget {
+    /// Local variable: x ||= value
+    LocalVar(String),
+    /// Instance variable: @name ||= value
+    Ivar(String),
+    /// Class variable: @@count += value
+    ClassVar(String),
+    /// Global variable: $count += value
+    GlobalVar(String),
+    /// Element, read with `[]` and written with `[]=`: opts[:name] ||= value
+    Index {
+        recv_vtx: VertexId,
+        key: Option<IndexKey>,
+        location: SourceLocation,
+    },
+    /// Attribute, read with `name` and written with `name=`: obj.count += 1
+    Attribute {
+        recv_vtx: VertexId,
+        read_name: String,
+        write_name: String,
+        location: SourceLocation,
+        safe_navigation: bool,
+        self_receiver: bool,
+    },
+}
+
+/// Operator of an operator assignment
+pub enum OpWriteOperator {
+    /// x ||= value
+    Or,
+    /// x &&= value
+    And,
+    /// x += value, x <<= value, ...
+    Binary {
+        operator: String,
+        location: SourceLocation,
+    },
+}
+
 /// Kind of child processing needed
 pub enum NeedsChildKind<'a> {
     /// Instance variable write: need to process value, then call finish_ivar_write
     IvarWrite { ivar_name: String, value: Node<'a> },
     /// Local variable write: need to process value, then call finish_local_var_write
     LocalVarWrite { var_name: String, value: Node<'a> },
+    /// Class variable write: need to process value, then call finish_cvar_write
+    CvarWrite { cvar_name: String, value: Node<'a> },
+    /// Global variable write: need to process value, then call finish_global_var_write
+    GlobalVarWrite { var_name: String, value: Node<'a> },
+    /// Operator assignment: need to process value, then call finish_op_write
+    OpWrite {
+        target: OpWriteTarget,
+        operator: OpWriteOperator,
+        value: Node<'a>,
+    },
+    /// Operator assignment to an element: need to process receiver, index and
+    /// value, then call finish_op_write with OpWriteTarget::Index
+    IndexOpWrite {
+        receiver: Node<'a>,
+        arguments: Vec<Node<'a>>,
+        /// Statically known key (e.g., symbol literal)
+        key: Option<IndexKey>,
+        operator: OpWriteOperator,
+        value: Node<'a>,
+        location: SourceLocation,
+    },
+    /// Operator assignment to an attribute: need to process receiver and
+    /// value, then call finish_op_write with OpWriteTarget::Attribute
+    CallOpWrite {
+        receiver: Node<'a>,
+        read_name: String,
+        write_name: String,
+        operator: OpWriteOperator,
+        value: Node<'a>,
+        location: SourceLocation,
+        safe_navigation: bool,
+    },
+    /// Multiple assignment: need to process value, then call finish_multi_write
+    MultiWrite {
+        write_node: ruby_prism::MultiWriteNode<'a>,
+    },
+    /// Element access: need to process receiver and index, then call finish_index_access
+    IndexAccess {
+        receiver: Node<'a>,
+        index: Node<'a>,
+        /// Statically known key (e.g., symbol literal)
+        key: Option<IndexKey>,
+        location: SourceLocation,
+    },
+    /// Element write: need to process receiver, index and value, then call finish_index_write
+    IndexWrite {
+        receiver: Node<'a>,
+        index: Node<'a>,
+        value: Node<'a>,
+        /// Statically known key (e.g., symbol literal)
+        key: Option<IndexKey>,
+        location: SourceLocation,
+    },
     /// Method call: need to process receiver, then call finish_method_call
     MethodCall {
         receiver: Node<'a>,
         method_name: String,
         location: SourceLocation,
+        /// Arguments passed to the method
+        arguments: Vec<Node<'a>>,
         /// Optional block attached to the method call
         block: Option<Node<'a>>,
+        /// Safe navigation call: x&.upcase
+        safe_navigation: bool,
     },
 }
 
@@ -57,6 +160,13 @@ pub fn dispatch_simple(genv: &mut GlobalEnv, lenv: &mut LocalEnv, node: &Node) -
         return DispatchResult::Vertex(install_self(genv));
     }
 
+    // block_given?
+    if let Some(call_node) = node.as_call_node() {
+        if call_node.receiver().is_none() && call_node.name().as_slice() == b"block_given?" {
+            return DispatchResult::Vertex(install_boolean(genv));
+        }
+    }
+
     // Local variable read: x
     if let Some(read_node) = node.as_local_variable_read_node() {
         let var_name = String::from_utf8_lossy(read_node.name().as_slice()).to_string();
@@ -66,6 +176,21 @@ pub fn dispatch_simple(genv: &mut GlobalEnv, lenv: &mut LocalEnv, node: &Node) -
         };
     }
 
+    // Class variable read: @@count
+    if let Some(cvar_read) = node.as_class_variable_read_node() {
+        let cvar_name = String::from_utf8_lossy(cvar_read.name().as_slice()).to_string();
+        return match install_cvar_read(genv, &cvar_name) {
+            Some(vtx) => DispatchResult::Vertex(vtx),
+            None => DispatchResult::NotHandled,
+        };
+    }
+
+    // Global variable read: $stdout
+    if let Some(global_read) = node.as_global_variable_read_node() {
+        let var_name = String::from_utf8_lossy(global_read.name().as_slice()).to_string();
+        return DispatchResult::Vertex(install_global_var_read(genv, &var_name));
+    }
+
     DispatchResult::NotHandled
 }
 
@@ -80,6 +205,24 @@ pub fn dispatch_needs_child<'a>(node: &Node<'a>, source: &str) -> Option<NeedsCh
         });
     }
 
+    // Class variable write: @@count = value
+    if let Some(cvar_write) = node.as_class_variable_write_node() {
+        let cvar_name = String::from_utf8_lossy(cvar_write.name().as_slice()).to_string();
+        return Some(NeedsChildKind::CvarWrite {
+            cvar_name,
+            value: cvar_write.value(),
+        });
+    }
+
+    // Global variable write: $name = value
+    if let Some(global_write) = node.as_global_variable_write_node() {
+        let var_name = String::from_utf8_lossy(global_write.name().as_slice()).to_string();
+        return Some(NeedsChildKind::GlobalVarWrite {
+            var_name,
+            value: global_write.value(),
+        });
+    }
+
     // Local variable write: x = value
     if let Some(write_node) = node.as_local_variable_write_node() {
         let var_name = String::from_utf8_lossy(write_node.name().as_slice()).to_string();
@@ -89,6 +232,21 @@ pub fn dispatch_needs_child<'a>(node: &Node<'a>, source: &str) -> Option<NeedsCh
         });
     }
 
+    // Operator assignment: x ||= value, @name += value, ...
+    if let Some(op_write) = dispatch_op_write(node, source) {
+        return Some(op_write);
+    }
+
+    // Operator assignment to an element or attribute: h[k] ||= [], obj.count += 1
+    if let Some(op_write) = dispatch_call_op_write(node, source) {
+        return Some(op_write);
+    }
+
+    // Multiple assignment: a, b = value
+    if let Some(write_node) = node.as_multi_write_node() {
+        return Some(NeedsChildKind::MultiWrite { write_node });
+    }
+
     // Method call: x.upcase or x.each { |i| ... }
     if let Some(call_node) = node.as_call_node() {
         if let Some(receiver) = call_node.receiver() {
@@ -99,11 +257,43 @@ pub fn dispatch_needs_child<'a>(node: &Node<'a>, source: &str) -> Option<NeedsCh
             // Get block if present (e.g., `x.each { |i| ... }`)
             let block = call_node.block();
 
+            // Element access: opts[:name] / opts[:name] = value
+            if block.is_none() && !call_node.is_safe_navigation() {
+                let mut arguments = call_arguments(&call_node);
+                match (method_name.as_str(), arguments.len()) {
+                    ("[]", 1) => {
+                        let index = arguments.pop().unwrap();
+                        let key = index_key(&index);
+                        return Some(NeedsChildKind::IndexAccess {
+                            receiver,
+                            index,
+                            key,
+                            location,
+                        });
+                    }
+                    ("[]=", 2) => {
+                        let value = arguments.pop().unwrap();
+                        let index = arguments.pop().unwrap();
+                        let key = index_key(&index);
+                        return Some(NeedsChildKind::IndexWrite {
+                            receiver,
+                            index,
+                            value,
+                            key,
+                            location,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
             return Some(NeedsChildKind::MethodCall {
                 receiver,
                 method_name,
                 location,
+                arguments: call_arguments(&call_node),
                 block,
+                safe_navigation: call_node.is_safe_navigation(),
             });
         }
     }
@@ -111,11 +301,248 @@ pub fn dispatch_needs_child<'a>(node: &Node<'a>, source: &str) -> Option<NeedsCh
     None
 }
 
+/// Get the arguments of a call
+fn call_arguments<'a>(call_node: &ruby_prism::CallNode<'a>) -> Vec<Node<'a>> {
+    call_node
+        .arguments()
+        .map(|args| args.arguments().iter().collect())
+        .unwrap_or_default()
+}
+
+/// Statically known key of an element access
+fn index_key(index: &Node) -> Option<IndexKey> {
+    if let Some(symbol) = index.as_symbol_node() {
+        return Some(IndexKey::Symbol(
+            String::from_utf8_lossy(symbol.unescaped()).to_string(),
+        ));
+    }
+    if let Some(integer) = index.as_integer_node() {
+        let value: i32 = integer.value().try_into().ok()?;
+        return Some(IndexKey::Integer(value.into()));
+    }
+    if index.as_range_node().is_some() {
+        return Some(IndexKey::Range);
+    }
+    None
+}
+
+/// Match `||=`, `&&=` and `op=` on local, instance, class and global variables
+fn dispatch_op_write<'a>(node: &Node<'a>, source: &str) -> Option<NeedsChildKind<'a>> {
+    let name_of = |id: ruby_prism::ConstantId| String::from_utf8_lossy(id.as_slice()).to_string();
+    let binary = |operator: ruby_prism::ConstantId, location: ruby_prism::Location| {
+        // `+=` calls `+`
+        OpWriteOperator::Binary {
+            operator: name_of(operator),
+            location: SourceLocation::from_prism_location_with_source(&location, source),
+        }
+    };
+
+    let (target, operator, value) = if let Some(n) = node.as_local_variable_or_write_node() {
+        (
+            OpWriteTarget::LocalVar(name_of(n.name())),
+            OpWriteOperator::Or,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_local_variable_and_write_node() {
+        (
+            OpWriteTarget::LocalVar(name_of(n.name())),
+            OpWriteOperator::And,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_local_variable_operator_write_node() {
+        (
+            OpWriteTarget::LocalVar(name_of(n.name())),
+            binary(n.binary_operator(), n.binary_operator_loc()),
+            n.value(),
+        )
+    } else if let Some(n) = node.as_instance_variable_or_write_node() {
+        (
+            OpWriteTarget::Ivar(name_of(n.name())),
+            OpWriteOperator::Or,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_instance_variable_and_write_node() {
+        (
+            OpWriteTarget::Ivar(name_of(n.name())),
+            OpWriteOperator::And,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_instance_variable_operator_write_node() {
+        (
+            OpWriteTarget::Ivar(name_of(n.name())),
+            binary(n.binary_operator(), n.binary_operator_loc()),
+            n.value(),
+        )
+    } else if let Some(n) = node.as_class_variable_or_write_node() {
+        (
+            OpWriteTarget::ClassVar(name_of(n.name())),
+            OpWriteOperator::Or,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_class_variable_and_write_node() {
+        (
+            OpWriteTarget::ClassVar(name_of(n.name())),
+            OpWriteOperator::And,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_class_variable_operator_write_node() {
+        (
+            OpWriteTarget::ClassVar(name_of(n.name())),
+            binary(n.binary_operator(), n.binary_operator_loc()),
+            n.value(),
+        )
+    } else if let Some(n) = node.as_global_variable_or_write_node() {
+        (
+            OpWriteTarget::GlobalVar(name_of(n.name())),
+            OpWriteOperator::Or,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_global_variable_and_write_node() {
+        (
+            OpWriteTarget::GlobalVar(name_of(n.name())),
+            OpWriteOperator::And,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_global_variable_operator_write_node() {
+        (
+            OpWriteTarget::GlobalVar(name_of(n.name())),
+            binary(n.binary_operator(), n.binary_operator_loc()),
+            n.value(),
+        )
+    } else {
+        return None;
+    };
+
+    Some(NeedsChildKind::OpWrite {
+        target,
+        operator,
+        value,
+    })
+}
+
+/// Match `||=`, `&&=` and `op=` on elements (`h[k] ||= []`) and attributes
+/// (`obj.count += 1`)
+fn dispatch_call_op_write<'a>(node: &Node<'a>, source: &str) -> Option<NeedsChildKind<'a>> {
+    let name_of = |id: ruby_prism::ConstantId| String::from_utf8_lossy(id.as_slice()).to_string();
+    let binary = |operator: ruby_prism::ConstantId, location: ruby_prism::Location| {
+        OpWriteOperator::Binary {
+            operator: name_of(operator),
+            location: SourceLocation::from_prism_location_with_source(&location, source),
+        }
+    };
+    let arguments_of = |arguments: Option<ruby_prism::ArgumentsNode<'a>>| -> Vec<Node<'a>> {
+        arguments
+            .map(|args| args.arguments().iter().collect())
+            .unwrap_or_default()
+    };
+    let location = SourceLocation::from_prism_location_with_source(&node.location(), source);
+
+    let (receiver, arguments, operator, value) = if let Some(n) = node.as_index_or_write_node() {
+        (
+            n.receiver(),
+            arguments_of(n.arguments()),
+            OpWriteOperator::Or,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_index_and_write_node() {
+        (
+            n.receiver(),
+            arguments_of(n.arguments()),
+            OpWriteOperator::And,
+            n.value(),
+        )
+    } else if let Some(n) = node.as_index_operator_write_node() {
+        (
+            n.receiver(),
+            arguments_of(n.arguments()),
+            binary(n.binary_operator(), n.binary_operator_loc()),
+            n.value(),
+        )
+    } else {
+        let (receiver, read_name, write_name, operator, value, safe_navigation) =
+            if let Some(n) = node.as_call_or_write_node() {
+                (
+                    n.receiver(),
+                    n.read_name(),
+                    n.write_name(),
+                    OpWriteOperator::Or,
+                    n.value(),
+                    n.is_safe_navigation(),
+                )
+            } else if let Some(n) = node.as_call_and_write_node() {
+                (
+                    n.receiver(),
+                    n.read_name(),
+                    n.write_name(),
+                    OpWriteOperator::And,
+                    n.value(),
+                    n.is_safe_navigation(),
+                )
+            } else if let Some(n) = node.as_call_operator_write_node() {
+                (
+                    n.receiver(),
+                    n.read_name(),
+                    n.write_name(),
+                    binary(n.binary_operator(), n.binary_operator_loc()),
+                    n.value(),
+                    n.is_safe_navigation(),
+                )
+            } else {
+                return None;
+            };
+
+        return Some(NeedsChildKind::CallOpWrite {
+            receiver: receiver?,
+            read_name: name_of(read_name),
+            write_name: name_of(write_name),
+            operator,
+            value,
+            location,
+            safe_navigation,
+        });
+    };
+
+    let key = match arguments.as_slice() {
+        [index] => index_key(index),
+        _ => None,
+    };
+    Some(NeedsChildKind::IndexOpWrite {
+        receiver: receiver?,
+        arguments,
+        key,
+        operator,
+        value,
+        location,
+    })
+}
+
 /// Finish instance variable write after child is processed
 pub fn finish_ivar_write(genv: &mut GlobalEnv, ivar_name: String, value_vtx: VertexId) -> VertexId {
     install_ivar_write(genv, ivar_name, value_vtx)
 }
 
+/// Finish class variable write after child is processed
+///
+/// Outside a class or module the value is not stored and is returned as is.
+pub fn finish_cvar_write(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    cvar_name: String,
+    value_vtx: VertexId,
+) -> VertexId {
+    install_cvar_write(genv, changes, &cvar_name, value_vtx).unwrap_or(value_vtx)
+}
+
+/// Finish global variable write after child is processed
+pub fn finish_global_var_write(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    var_name: String,
+    value_vtx: VertexId,
+) -> VertexId {
+    install_global_var_write(genv, changes, &var_name, value_vtx)
+}
+
 /// Finish local variable write after child is processed
 pub fn finish_local_var_write(
     genv: &mut GlobalEnv,
@@ -127,12 +554,223 @@ pub fn finish_local_var_write(
     install_local_var_write(genv, lenv, changes, var_name, value_vtx)
 }
 
+/// Finish operator assignment after value is processed
+///
+/// `||=` / `&&=` union the previous value with the new one. `x op= v` is
+/// installed as `x = x.op(v)`, so the operator goes through a MethodCallBox.
+/// Class and global variables have one vertex, which already holds the
+/// previous value. Elements and attributes are read and written through
+/// their `[]`/`[]=` and `name`/`name=` methods.
+pub fn finish_op_write(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    changes: &mut ChangeSet,
+    target: OpWriteTarget,
+    operator: OpWriteOperator,
+    value_vtx: VertexId,
+) -> VertexId {
+    match operator {
+        OpWriteOperator::Or | OpWriteOperator::And => match target {
+            OpWriteTarget::LocalVar(var_name) => {
+                install_local_var_logical_write(genv, lenv, changes, var_name, value_vtx)
+            }
+            OpWriteTarget::Ivar(ivar_name) => {
+                install_ivar_logical_write(genv, ivar_name, value_vtx)
+            }
+            OpWriteTarget::ClassVar(cvar_name) => {
+                finish_cvar_write(genv, changes, cvar_name, value_vtx)
+            }
+            OpWriteTarget::GlobalVar(var_name) => {
+                finish_global_var_write(genv, changes, var_name, value_vtx)
+            }
+            OpWriteTarget::Index { .. } | OpWriteTarget::Attribute { .. } => {
+                let result_vtx = genv.new_vertex();
+                if let Some(prev_vtx) = read_op_write_target(genv, lenv, changes, &target) {
+                    changes.add_edge(prev_vtx, result_vtx);
+                }
+                changes.add_edge(value_vtx, result_vtx);
+                write_op_write_target(genv, lenv, changes, target, value_vtx);
+                result_vtx
+            }
+        },
+        OpWriteOperator::Binary { operator, location } => {
+            // An unassigned variable reads as nil
+            let recv_vtx = read_op_write_target(genv, lenv, changes, &target)
+                .unwrap_or_else(|| genv.new_source(Type::Nil));
+            let ret_vtx = install_method_call(
+                genv,
+                recv_vtx,
+                operator,
+                vec![value_vtx],
+                Some(location),
+                false,
+            );
+            write_op_write_target(genv, lenv, changes, target, ret_vtx)
+        }
+    }
+}
+
+/// Previous value of an operator assignment's target
+fn read_op_write_target(
+    genv: &mut GlobalEnv,
+    lenv: &LocalEnv,
+    changes: &mut ChangeSet,
+    target: &OpWriteTarget,
+) -> Option<VertexId> {
+    match target {
+        OpWriteTarget::LocalVar(var_name) => install_local_var_read(lenv, var_name),
+        OpWriteTarget::Ivar(ivar_name) => install_ivar_read(genv, ivar_name),
+        OpWriteTarget::ClassVar(cvar_name) => install_cvar_read(genv, cvar_name),
+        OpWriteTarget::GlobalVar(var_name) => Some(install_global_var_read(genv, var_name)),
+        OpWriteTarget::Index {
+            recv_vtx,
+            key,
+            location,
+        } => Some(install_index_access(
+            genv,
+            changes,
+            *recv_vtx,
+            key.clone(),
+            Some(location.clone()),
+        )),
+        OpWriteTarget::Attribute {
+            recv_vtx,
+            read_name,
+            location,
+            safe_navigation,
+            self_receiver,
+            ..
+        } => Some(finish_method_call(
+            genv,
+            *recv_vtx,
+            read_name.clone(),
+            Vec::new(),
+            location.clone(),
+            *safe_navigation,
+            *self_receiver,
+        )),
+    }
+}
+
+/// Assign the new value of an operator assignment; returns the value
+fn write_op_write_target(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    changes: &mut ChangeSet,
+    target: OpWriteTarget,
+    value_vtx: VertexId,
+) -> VertexId {
+    match target {
+        OpWriteTarget::LocalVar(var_name) => {
+            install_local_var_write(genv, lenv, changes, var_name, value_vtx)
+        }
+        OpWriteTarget::Ivar(ivar_name) => install_ivar_write(genv, ivar_name, value_vtx),
+        OpWriteTarget::ClassVar(cvar_name) => {
+            finish_cvar_write(genv, changes, cvar_name, value_vtx)
+        }
+        OpWriteTarget::GlobalVar(var_name) => {
+            finish_global_var_write(genv, changes, var_name, value_vtx)
+        }
+        OpWriteTarget::Index {
+            recv_vtx,
+            key,
+            location,
+        } => install_index_write(genv, changes, recv_vtx, key, value_vtx, Some(location)),
+        OpWriteTarget::Attribute {
+            recv_vtx,
+            write_name,
+            location,
+            safe_navigation,
+            self_receiver,
+            ..
+        } => {
+            finish_method_call(
+                genv,
+                recv_vtx,
+                write_name,
+                vec![value_vtx],
+                location,
+                safe_navigation,
+                self_receiver,
+            );
+            // `obj.name = value` evaluates to value
+            value_vtx
+        }
+    }
+}
+
+/// Finish multiple assignment after value is processed
+///
+/// Returns the value vertex, since `a, b = value` evaluates to `value`.
+pub fn finish_multi_write(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    write_node: &ruby_prism::MultiWriteNode,
+    value_vtx: VertexId,
+) -> VertexId {
+    install_multi_write_targets(
+        genv,
+        lenv,
+        write_node.lefts(),
+        write_node.rest(),
+        write_node.rights(),
+        value_vtx,
+    );
+    value_vtx
+}
+
+/// Finish element access after receiver and index are processed
+pub fn finish_index_access(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    recv_vtx: VertexId,
+    key: Option<IndexKey>,
+    location: SourceLocation,
+) -> VertexId {
+    install_index_access(genv, changes, recv_vtx, key, Some(location))
+}
+
+/// Finish element write after receiver, index and value are processed
+pub fn finish_index_write(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    recv_vtx: VertexId,
+    key: Option<IndexKey>,
+    value_vtx: VertexId,
+    location: SourceLocation,
+) -> VertexId {
+    install_index_write(genv, changes, recv_vtx, key, value_vtx, Some(location))
+}
+
 /// Finish method call after receiver is processed
+///
+/// `self_receiver` is true for `self.foo`, which may call private methods.
 pub fn finish_method_call(
     genv: &mut GlobalEnv,
     recv_vtx: VertexId,
     method_name: String,
+    arg_vtxs: Vec<VertexId>,
     location: SourceLocation,
+    safe_navigation: bool,
+    self_receiver: bool,
 ) -> VertexId {
-    install_method_call(genv, recv_vtx, method_name, Some(location))
+    if safe_navigation {
+        install_safe_navigation_call(
+            genv,
+            recv_vtx,
+            method_name,
+            arg_vtxs,
+            Some(location),
+            self_receiver,
+        )
+    } else {
+        install_method_call(
+            genv,
+            recv_vtx,
+            method_name,
+            arg_vtxs,
+            Some(location),
+            self_receiver,
+        )
+    }
 }
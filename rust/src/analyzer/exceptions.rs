@@ -0,0 +1,110 @@
+//! Exception Handlers - Processing rescue clauses
+//!
+//! This module is responsible for:
+//! - Typing the exception classes listed in `rescue A, B`
+//! - Binding the rescue variable (`rescue => e`)
+
+use crate::env::{GlobalEnv, LocalEnv};
+use crate::graph::ChangeSet;
+use crate::types::Type;
+use ruby_prism::{Node, NodeList};
+
+use super::definitions::constant_name;
+use super::variables::{install_ivar_write, install_local_var_write};
+
+/// Exception class rescued when no class is listed
+const DEFAULT_RESCUE_CLASS: &str = "StandardError";
+
+/// Type of the exception caught by a rescue clause
+///
+/// The union of the listed classes, or `StandardError` when none is listed.
+/// A splat (`rescue *ERRORS`) cannot be resolved and also falls back to
+/// `StandardError`.
+pub fn rescue_exception_type(exceptions: NodeList, source: &str) -> Type {
+    let mut types: Vec<Type> = Vec::new();
+
+    for exception in &exceptions {
+        match constant_name(&exception, source) {
+            Some(class_name) => {
+                let ty = Type::Instance { class_name };
+                if !types.contains(&ty) {
+                    types.push(ty);
+                }
+            }
+            None => return default_exception_type(),
+        }
+    }
+
+    match types.len() {
+        0 => default_exception_type(),
+        1 => types.pop().unwrap(),
+        _ => Type::Union(types),
+    }
+}
+
+/// Bind the rescue variable (`rescue => e`, `rescue => @error`) to the exception
+pub fn install_rescue_reference(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    changes: &mut ChangeSet,
+    reference: &Node,
+    exception_ty: Type,
+) {
+    // One source per class, so the variable holds each class as a separate type
+    let exception_vtx = genv.new_vertex();
+    for ty in exception_ty.union_members() {
+        let src_vtx = genv.new_source(ty);
+        genv.add_edge(src_vtx, exception_vtx);
+    }
+
+    if let Some(local) = reference.as_local_variable_target_node() {
+        let var_name = String::from_utf8_lossy(local.name().as_slice()).to_string();
+        install_local_var_write(genv, lenv, changes, var_name, exception_vtx);
+    } else if let Some(ivar) = reference.as_instance_variable_target_node() {
+        let ivar_name = String::from_utf8_lossy(ivar.name().as_slice()).to_string();
+        install_ivar_write(genv, ivar_name, exception_vtx);
+    }
+}
+
+fn default_exception_type() -> Type {
+    Type::Instance {
+        class_name: DEFAULT_RESCUE_CLASS.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_exception_type() {
+        assert_eq!(default_exception_type().show(), "StandardError");
+    }
+
+    #[test]
+    fn test_install_rescue_reference_local_var() {
+        let source = "begin; rescue => e; end";
+        let parse_result = crate::parser::parse_ruby_source(source, "test.rb".to_string()).unwrap();
+        let root = parse_result.node();
+        let program = root.as_program_node().unwrap();
+        let stmt = program.statements().body().iter().next().unwrap();
+        let rescue = stmt.as_begin_node().unwrap().rescue_clause().unwrap();
+
+        let mut genv = GlobalEnv::new();
+        let mut lenv = LocalEnv::new();
+        let mut changes = ChangeSet::new();
+
+        let exception_ty = rescue_exception_type(rescue.exceptions(), source);
+        install_rescue_reference(
+            &mut genv,
+            &mut lenv,
+            &mut changes,
+            &rescue.reference().unwrap(),
+            exception_ty,
+        );
+        genv.apply_changes(changes);
+
+        let e_vtx = lenv.get_var("e").unwrap();
+        assert_eq!(genv.get_vertex(e_vtx).unwrap().show(), "StandardError");
+    }
+}
@@ -4,24 +4,56 @@
 //! - Traversing the Ruby AST (Abstract Syntax Tree)
 //! - Coordinating the graph construction process
 
-use crate::env::{GlobalEnv, LocalEnv};
+use crate::env::method_registry::Visibility;
+use crate::env::{GlobalEnv, LocalEnv, ScopeKind};
 use crate::graph::{BlockParameterTypeBox, ChangeSet, VertexId};
+use crate::source_map::SourceLocation;
+use crate::types::Type;
 use ruby_prism::Node;
+use std::collections::HashSet;
 
-use super::blocks::{enter_block_scope, exit_block_scope, install_block_parameter};
+use super::blocks::{enter_block_scope, exit_block_scope, install_block_parameter, install_yield};
+use super::calls::{install_element_mutation, install_super_call};
 use super::definitions::{
-    exit_scope, extract_class_name, extract_module_name, install_class, install_method,
-    install_module,
+    alias_user_method, constant_name, exit_scope, extract_class_name, extract_module_name,
+    install_class, install_method, install_module, literal_method_name, register_user_method,
+    set_method_visibility,
 };
 use super::dispatch::{
-    dispatch_needs_child, dispatch_simple, finish_ivar_write, finish_local_var_write,
-    finish_method_call, DispatchResult, NeedsChildKind,
+    dispatch_needs_child, dispatch_simple, finish_cvar_write, finish_global_var_write,
+    finish_index_access, finish_index_write, finish_ivar_write, finish_local_var_write,
+    finish_method_call, finish_multi_write, finish_op_write, DispatchResult, NeedsChildKind,
+    OpWriteTarget,
 };
-use super::literals::install_literal;
+use super::exceptions::{install_rescue_reference, rescue_exception_type};
+use super::literals::{install_boolean, install_literal};
 use super::parameters::{
-    install_keyword_rest_parameter, install_optional_parameter, install_required_parameter,
-    install_rest_parameter,
+    install_keyword_rest_parameter, install_optional_parameter, install_proc_parameter,
+    install_required_parameter, install_rest_parameter,
 };
+use super::patterns::install_pattern;
+use super::structs::{install_struct_class, struct_definition};
+use super::variables::{install_constant_read, install_constant_write};
+
+/// Maximum number of elements for an array literal to be typed as a tuple
+const MAX_TUPLE_SIZE: usize = 4;
+
+/// State of the method definition being installed
+#[derive(Default)]
+struct MethodContext {
+    /// Name of the method
+    name: String,
+    /// Whether this is a singleton method (`def self.foo`)
+    singleton: bool,
+    /// Positional parameter vertices (forwarded by a bare `super`)
+    param_vtxs: Vec<VertexId>,
+    /// Values yielded to the block, one vertex per block parameter
+    yield_vtxs: Vec<VertexId>,
+    /// Name of the explicit block parameter (`&block`)
+    block_param: Option<String>,
+    /// Return value of the method, fed by the body and by `return`
+    return_vtx: Option<VertexId>,
+}
 
 /// Build graph from AST
 pub struct AstInstaller<'a> {
@@ -29,6 +61,8 @@ pub struct AstInstaller<'a> {
     lenv: &'a mut LocalEnv,
     changes: ChangeSet,
     source: &'a str,
+    /// Method definition being installed (None outside `def`)
+    method_context: Option<MethodContext>,
 }
 
 impl<'a> AstInstaller<'a> {
@@ -38,6 +72,7 @@ impl<'a> AstInstaller<'a> {
             lenv,
             changes: ChangeSet::new(),
             source,
+            method_context: None,
         }
     }
 
@@ -63,6 +98,126 @@ impl<'a> AstInstaller<'a> {
             return self.install_block_node(&block_node);
         }
 
+        // yield x, block.call(x)
+        if let Some(arguments) = self.yielded_arguments(node) {
+            return Some(self.install_yielded_arguments(arguments));
+        }
+
+        // super(args), super
+        if let Some(super_node) = node.as_super_node() {
+            let arguments: Vec<Node> = super_node
+                .arguments()
+                .map(|args| args.arguments().iter().collect())
+                .unwrap_or_default();
+            let arg_vtxs = self.install_arguments(&arguments);
+            if let Some(block_node) = super_node.block().and_then(|block| block.as_block_node()) {
+                self.install_block_node(&block_node);
+            }
+            return Some(self.install_super(arg_vtxs, node));
+        }
+        if let Some(forwarding_super) = node.as_forwarding_super_node() {
+            // A bare `super` forwards the method's own arguments
+            let arg_vtxs = self
+                .method_context
+                .as_ref()
+                .map(|context| context.param_vtxs.clone())
+                .unwrap_or_default();
+            if let Some(block_node) = forwarding_super.block() {
+                self.install_block_node(&block_node);
+            }
+            return Some(self.install_super(arg_vtxs, node));
+        }
+
+        // return value
+        if let Some(return_node) = node.as_return_node() {
+            self.install_return_node(&return_node);
+            return None;
+        }
+
+        // NAME = value, Point = Struct.new(:x, :y)
+        if let Some(constant_write) = node.as_constant_write_node() {
+            return self.install_constant_write_node(&constant_write);
+        }
+
+        // NAME, Config::DEFAULTS
+        if node.as_constant_read_node().is_some() || node.as_constant_path_node().is_some() {
+            let const_name = constant_name(node, self.source)?;
+            self.record_constant_reference(node, &const_name);
+            return install_constant_read(self.genv, &const_name);
+        }
+
+        // alias new_name old_name
+        if let Some(alias_node) = node.as_alias_method_node() {
+            let new_name = literal_method_name(&alias_node.new_name());
+            let old_name = literal_method_name(&alias_node.old_name());
+            if let (Some(new_name), Some(old_name)) = (new_name, old_name) {
+                alias_user_method(self.genv, &new_name, &old_name);
+            }
+            return None;
+        }
+
+        // Class body calls: private, alias_method, define_method
+        if let Some(call_node) = node.as_call_node() {
+            if let Some(visibility) = self.visibility_modifier(&call_node) {
+                return self.install_visibility_modifier(&call_node, visibility);
+            }
+            if self.is_class_body_call(&call_node) {
+                match call_node.name().as_slice() {
+                    b"alias_method" => return self.install_alias_method(&call_node),
+                    b"attr_reader" | b"attr_writer" | b"attr_accessor" => {
+                        return self.install_attr_methods(&call_node)
+                    }
+                    b"define_method" => return self.install_define_method(&call_node),
+                    // Methods and constants may come from the module
+                    b"include" | b"extend" | b"prepend" => {
+                        self.genv.mark_open_ancestry();
+                        return None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // begin ... rescue ... else ... ensure ... end
+        if let Some(begin_node) = node.as_begin_node() {
+            return self.install_begin_node(&begin_node);
+        }
+
+        // case value in pattern ... end
+        if let Some(case_match) = node.as_case_match_node() {
+            return self.install_case_match_node(&case_match);
+        }
+
+        // value => pattern (raises when nothing matches, evaluates to nil)
+        if let Some(match_required) = node.as_match_required_node() {
+            if let Some(value_vtx) = self.install_node(&match_required.value()) {
+                self.install_pattern(&match_required.pattern(), value_vtx);
+            }
+            return Some(self.genv.new_source(Type::Nil));
+        }
+
+        // value in pattern
+        if let Some(match_predicate) = node.as_match_predicate_node() {
+            if let Some(value_vtx) = self.install_node(&match_predicate.value()) {
+                self.install_pattern(&match_predicate.pattern(), value_vtx);
+            }
+            return Some(install_boolean(self.genv));
+        }
+
+        // expr rescue fallback
+        if let Some(rescue_modifier) = node.as_rescue_modifier_node() {
+            let result_vtx = self.genv.new_vertex();
+            for branch in [
+                rescue_modifier.expression(),
+                rescue_modifier.rescue_expression(),
+            ] {
+                if let Some(vtx) = self.install_node(&branch) {
+                    self.changes.add_edge(vtx, result_vtx);
+                }
+            }
+            return Some(result_vtx);
+        }
+
         // Try simple dispatch first (no child processing needed)
         match dispatch_simple(self.genv, self.lenv, node) {
             DispatchResult::Vertex(vtx) => return Some(vtx),
@@ -74,6 +229,14 @@ impl<'a> AstInstaller<'a> {
             return Some(vtx);
         }
 
+        // Bare identifier that is not a local variable: nmae
+        if let Some(call_node) = node.as_call_node() {
+            if call_node.is_variable_call() {
+                self.record_identifier(&call_node);
+                return None;
+            }
+        }
+
         // Check if node needs child processing
         if let Some(kind) = dispatch_needs_child(node, self.source) {
             return self.process_needs_child(kind);
@@ -87,50 +250,278 @@ impl<'a> AstInstaller<'a> {
     /// Handles all literals including Array with element type inference
     fn install_literal_node(&mut self, node: &Node) -> Option<VertexId> {
         // Array literals need special handling for element type inference
+        // (`%w[a b]` and `%i[a b]` are Array literals too)
         if node.as_array_node().is_some() {
             let elements: Vec<Node> = node.as_array_node().unwrap().elements().iter().collect();
             return self.install_array_literal_elements(elements);
         }
 
-        // Other literals (String, Integer, Hash, nil, true, false, Symbol)
-        install_literal(self.genv, node)
+        // {name: "x"}, {"k" => 1}
+        if let Some(hash_node) = node.as_hash_node() {
+            return self.install_hash_literal(&hash_node);
+        }
+
+        // 1..10, "a"..."z", 1..
+        if let Some(range_node) = node.as_range_node() {
+            return self.install_range_literal(&range_node);
+        }
+
+        // (1..), (a; b): evaluates to the last expression
+        if let Some(parens) = node.as_parentheses_node() {
+            let body = parens.body()?;
+            return match body.as_statements_node() {
+                Some(statements) => self.install_statements_value(&statements),
+                None => self.install_node(&body),
+            };
+        }
+
+        // "#{x}", <<~EOS with interpolation, `echo #{x}`
+        if let Some(interpolated) = node.as_interpolated_string_node() {
+            self.install_interpolation_parts(interpolated.parts());
+            return Some(self.genv.new_source(Type::string()));
+        }
+        if let Some(interpolated) = node.as_interpolated_x_string_node() {
+            self.install_interpolation_parts(interpolated.parts());
+            return Some(self.genv.new_source(Type::string()));
+        }
+
+        // :"sym#{x}"
+        if let Some(interpolated) = node.as_interpolated_symbol_node() {
+            self.install_interpolation_parts(interpolated.parts());
+            return Some(self.genv.new_source(Type::Instance {
+                class_name: "Symbol".to_string(),
+            }));
+        }
+
+        // /re#{x}/
+        if let Some(interpolated) = node.as_interpolated_regular_expression_node() {
+            self.install_interpolation_parts(interpolated.parts());
+            return Some(self.genv.new_source(Type::Instance {
+                class_name: "Regexp".to_string(),
+            }));
+        }
+
+        // ->(x) { ... }
+        if let Some(lambda_node) = node.as_lambda_node() {
+            return self.install_lambda_literal(&lambda_node);
+        }
+
+        // Other literals (String, Integer, Float, Hash, nil, true, false, Symbol, ...)
+        install_literal(self.genv, node, self.source)
     }
 
     /// Install array literal with pre-collected elements
+    ///
+    /// Short heterogeneous literals keep per-position types as a tuple
+    /// (`[1, "a"]` is `[Integer, String]`); others become `Array[Elem]`.
     fn install_array_literal_elements(&mut self, elements: Vec<Node>) -> Option<VertexId> {
-        use crate::types::Type;
-        use std::collections::HashSet;
-
         if elements.is_empty() {
             return Some(self.genv.new_source(Type::array()));
         }
 
-        let mut element_types: HashSet<Type> = HashSet::new();
+        let position_types: Vec<Option<Type>> = elements
+            .iter()
+            .map(|element| {
+                let types = self.install_and_collect_types(std::slice::from_ref(element));
+                Self::union_of(types)
+            })
+            .collect();
+
+        if let Some(tuple_types) = Self::tuple_types(&position_types) {
+            return Some(self.genv.new_source(Type::Tuple(tuple_types)));
+        }
+
+        let element_types: HashSet<Type> = position_types
+            .into_iter()
+            .flatten()
+            .flat_map(|ty| ty.union_members())
+            .collect();
+
+        let array_type = match Self::union_of(element_types) {
+            Some(elem_type) => Type::array_of(elem_type),
+            None => Type::array(),
+        };
+
+        Some(self.genv.new_source(array_type))
+    }
+
+    /// Per-position types if an array literal should be typed as a tuple
+    ///
+    /// Every position must be typed and at least two positions must differ.
+    fn tuple_types(position_types: &[Option<Type>]) -> Option<Vec<Type>> {
+        if position_types.len() > MAX_TUPLE_SIZE {
+            return None;
+        }
 
-        for element in &elements {
-            if let Some(vtx) = self.install_node(element) {
+        let types: Vec<Type> = position_types.iter().cloned().collect::<Option<_>>()?;
+        let heterogeneous = types.iter().any(|ty| *ty != types[0]);
+        heterogeneous.then_some(types)
+    }
+
+    /// Install hash literal
+    ///
+    /// Symbol-keyed literals become records (`{ name: String }`) so that
+    /// `opts[:name]` can be typed per key; other literals become `Hash[K, V]`.
+    fn install_hash_literal(&mut self, hash_node: &ruby_prism::HashNode) -> Option<VertexId> {
+        let mut keys: Vec<Node> = Vec::new();
+        let mut values: Vec<Node> = Vec::new();
+        let mut has_splat = false;
+
+        for element in &hash_node.elements() {
+            if let Some(assoc) = element.as_assoc_node() {
+                keys.push(assoc.key());
+                values.push(assoc.value());
+            } else if let Some(splat) = element.as_assoc_splat_node() {
+                // {**other}: keys are no longer known
+                has_splat = true;
+                if let Some(value) = splat.value() {
+                    self.install_node(&value);
+                }
+            }
+        }
+
+        if keys.is_empty() && !has_splat {
+            return Some(self.genv.new_source(Type::hash()));
+        }
+
+        let symbol_keys: Option<Vec<String>> = if has_splat {
+            None
+        } else {
+            keys.iter()
+                .map(|key| {
+                    key.as_symbol_node()
+                        .map(|sym| String::from_utf8_lossy(sym.unescaped()).to_string())
+                })
+                .collect()
+        };
+
+        let value_types: Vec<Option<Type>> = values
+            .iter()
+            .map(|value| {
+                let types = self.install_and_collect_types(std::slice::from_ref(value));
+                Self::union_of(types)
+            })
+            .collect();
+        // A value typed only after propagation (a parameter, a method call)
+        // would be baked in as untyped, so every value must be typed here
+        let value_types: Option<Vec<Type>> = value_types.into_iter().collect();
+
+        if let (Some(names), Some(value_types)) = (symbol_keys, &value_types) {
+            let mut fields: Vec<(String, Type)> = Vec::new();
+            for (name, ty) in names.into_iter().zip(value_types.iter().cloned()) {
+                // Later duplicate keys win, as in Ruby
+                match fields.iter_mut().find(|(key, _)| *key == name) {
+                    Some(field) => field.1 = ty,
+                    None => fields.push((name, ty)),
+                }
+            }
+            return Some(self.genv.new_source(Type::Record { fields }));
+        }
+
+        let key_type = Self::union_of(self.install_and_collect_types(&keys));
+        let value_type = value_types.and_then(|types| {
+            Self::union_of(types.iter().flat_map(|ty| ty.union_members()).collect())
+        });
+
+        let hash_type = match (key_type, value_type) {
+            (Some(key_type), Some(value_type)) => Type::hash_of(key_type, value_type),
+            _ => Type::hash(),
+        };
+
+        Some(self.genv.new_source(hash_type))
+    }
+
+    /// Install range literal: element type comes from the bounds
+    fn install_range_literal(&mut self, range_node: &ruby_prism::RangeNode) -> Option<VertexId> {
+        let bounds: Vec<Node> = range_node
+            .left()
+            .into_iter()
+            .chain(range_node.right())
+            .collect();
+
+        // Endless/beginless ranges (`1..`, `..1`) have a nil bound, which is not an element
+        let mut bound_types = self.install_and_collect_types(&bounds);
+        bound_types.remove(&Type::Nil);
+
+        let range_type = match Self::union_of(bound_types) {
+            Some(elem_type) => Type::range_of(elem_type),
+            None => Type::Instance {
+                class_name: "Range".to_string(),
+            },
+        };
+
+        Some(self.genv.new_source(range_type))
+    }
+
+    /// Install the embedded expressions of an interpolated literal
+    ///
+    /// The literal's own type does not depend on them, but errors inside
+    /// `#{...}` must still be found.
+    fn install_interpolation_parts(&mut self, parts: ruby_prism::NodeList) {
+        for part in &parts {
+            if let Some(embedded) = part.as_embedded_statements_node() {
+                if let Some(statements) = embedded.statements() {
+                    self.install_statements(&statements);
+                }
+            } else if let Some(embedded) = part.as_embedded_variable_node() {
+                self.install_node(&embedded.variable());
+            }
+        }
+    }
+
+    /// Install lambda literal: ->(x) { ... }
+    ///
+    /// The body is installed in a block scope like any other block.
+    fn install_lambda_literal(&mut self, lambda_node: &ruby_prism::LambdaNode) -> Option<VertexId> {
+        enter_block_scope(self.genv);
+
+        if let Some(params_node) = lambda_node.parameters() {
+            if let Some(block_params) = params_node.as_block_parameters_node() {
+                self.install_block_parameters_with_vtxs(&block_params);
+            }
+        }
+
+        if let Some(body) = lambda_node.body() {
+            if let Some(statements) = body.as_statements_node() {
+                self.install_statements(&statements);
+            } else {
+                self.install_node(&body);
+            }
+        }
+
+        exit_block_scope(self.genv);
+
+        Some(self.genv.new_source(Type::Instance {
+            class_name: "Proc".to_string(),
+        }))
+    }
+
+    /// Install nodes and collect the types known right away
+    fn install_and_collect_types(&mut self, nodes: &[Node]) -> HashSet<Type> {
+        let mut types: HashSet<Type> = HashSet::new();
+
+        for node in nodes {
+            if let Some(vtx) = self.install_node(node) {
                 if let Some(source) = self.genv.get_source(vtx) {
-                    element_types.insert(source.ty.clone());
+                    types.insert(source.ty.clone());
                 } else if let Some(vertex) = self.genv.get_vertex(vtx) {
                     for ty in vertex.types.keys() {
-                        element_types.insert(ty.clone());
+                        types.insert(ty.clone());
                     }
                 }
             }
         }
 
-        let array_type = if element_types.is_empty() {
-            Type::array()
-        } else if element_types.len() == 1 {
-            let elem_type = element_types.into_iter().next().unwrap();
-            Type::array_of(elem_type)
-        } else {
-            let types_vec: Vec<Type> = element_types.into_iter().collect();
-            let union_type = Type::Union(types_vec);
-            Type::array_of(union_type)
-        };
+        types
+    }
 
-        Some(self.genv.new_source(array_type))
+    /// Combine collected types into a single type (None if empty)
+    fn union_of(types: HashSet<Type>) -> Option<Type> {
+        match types.len() {
+            0 => None,
+            1 => types.into_iter().next(),
+            _ => Some(Type::Union(types.into_iter().collect())),
+        }
     }
 
     /// Process nodes that need child evaluation first
@@ -150,13 +541,148 @@ impl<'a> AstInstaller<'a> {
                     value_vtx,
                 ))
             }
+            NeedsChildKind::CvarWrite { cvar_name, value } => {
+                let value_vtx = self.install_node(&value)?;
+                Some(finish_cvar_write(
+                    self.genv,
+                    &mut self.changes,
+                    cvar_name,
+                    value_vtx,
+                ))
+            }
+            NeedsChildKind::GlobalVarWrite { var_name, value } => {
+                let value_vtx = self.install_node(&value)?;
+                Some(finish_global_var_write(
+                    self.genv,
+                    &mut self.changes,
+                    var_name,
+                    value_vtx,
+                ))
+            }
+            NeedsChildKind::OpWrite {
+                target,
+                operator,
+                value,
+            } => {
+                let value_vtx = self.install_node(&value)?;
+                Some(finish_op_write(
+                    self.genv,
+                    self.lenv,
+                    &mut self.changes,
+                    target,
+                    operator,
+                    value_vtx,
+                ))
+            }
+            NeedsChildKind::IndexOpWrite {
+                receiver,
+                arguments,
+                key,
+                operator,
+                value,
+                location,
+            } => {
+                let recv_vtx = self.install_node(&receiver)?;
+                for argument in &arguments {
+                    self.install_node(argument);
+                }
+                let value_vtx = self.install_node(&value)?;
+                let target = OpWriteTarget::Index {
+                    recv_vtx,
+                    key,
+                    location,
+                };
+                Some(finish_op_write(
+                    self.genv,
+                    self.lenv,
+                    &mut self.changes,
+                    target,
+                    operator,
+                    value_vtx,
+                ))
+            }
+            NeedsChildKind::CallOpWrite {
+                receiver,
+                read_name,
+                write_name,
+                operator,
+                value,
+                location,
+                safe_navigation,
+            } => {
+                let recv_vtx = self.install_node(&receiver)?;
+                let value_vtx = self.install_node(&value)?;
+                let target = OpWriteTarget::Attribute {
+                    recv_vtx,
+                    read_name,
+                    write_name,
+                    location,
+                    safe_navigation,
+                    self_receiver: receiver.as_self_node().is_some(),
+                };
+                Some(finish_op_write(
+                    self.genv,
+                    self.lenv,
+                    &mut self.changes,
+                    target,
+                    operator,
+                    value_vtx,
+                ))
+            }
+            NeedsChildKind::MultiWrite { write_node } => {
+                let value_vtx = self.install_node(&write_node.value())?;
+                Some(finish_multi_write(
+                    self.genv,
+                    self.lenv,
+                    &write_node,
+                    value_vtx,
+                ))
+            }
+            NeedsChildKind::IndexAccess {
+                receiver,
+                index,
+                key,
+                location,
+            } => {
+                let recv_vtx = self.install_node(&receiver)?;
+                self.install_node(&index);
+                Some(finish_index_access(
+                    self.genv,
+                    &mut self.changes,
+                    recv_vtx,
+                    key,
+                    location,
+                ))
+            }
+            NeedsChildKind::IndexWrite {
+                receiver,
+                index,
+                value,
+                key,
+                location,
+            } => {
+                let recv_vtx = self.install_node(&receiver)?;
+                self.install_node(&index);
+                let value_vtx = self.install_node(&value)?;
+                Some(finish_index_write(
+                    self.genv,
+                    &mut self.changes,
+                    recv_vtx,
+                    key,
+                    value_vtx,
+                    location,
+                ))
+            }
             NeedsChildKind::MethodCall {
                 receiver,
                 method_name,
                 location,
+                arguments,
                 block,
+                safe_navigation,
             } => {
                 let recv_vtx = self.install_node(&receiver)?;
+                let arg_vtxs = self.install_arguments(&arguments);
 
                 // Process block if present (e.g., `x.each { |i| ... }`)
                 // Collect block parameter vertex IDs for type inference
@@ -180,29 +706,279 @@ impl<'a> AstInstaller<'a> {
                     self.genv.register_box(box_id, Box::new(block_box));
                 }
 
+                install_element_mutation(self.genv, recv_vtx, &method_name, &arg_vtxs);
+
                 Some(finish_method_call(
                     self.genv,
                     recv_vtx,
                     method_name,
+                    arg_vtxs,
                     location,
+                    safe_navigation,
+                    receiver.as_self_node().is_some(),
                 ))
             }
         }
     }
 
+    /// Install call arguments and return the vertices of the positional ones
+    ///
+    /// Arguments that cannot be typed get an empty vertex so later positions
+    /// still line up; positions are not tracked past a splat or keywords.
+    fn install_arguments(&mut self, arguments: &[Node]) -> Vec<VertexId> {
+        let mut arg_vtxs = Vec::new();
+        let mut positional = true;
+        for arg in arguments {
+            if arg.as_splat_node().is_some() || arg.as_keyword_hash_node().is_some() {
+                positional = false;
+            }
+            let arg_vtx = self.install_node(arg);
+            if positional {
+                arg_vtxs.push(arg_vtx.unwrap_or_else(|| self.genv.new_vertex()));
+            }
+        }
+        arg_vtxs
+    }
+
     /// Install class definition
     fn install_class_node(&mut self, class_node: &ruby_prism::ClassNode) -> Option<VertexId> {
         let class_name = extract_class_name(class_node);
-        install_class(self.genv, class_name);
+        let superclass = class_node.superclass().and_then(|superclass| {
+            // class Point < Struct.new(:x, :y)
+            let definition = superclass
+                .as_call_node()
+                .and_then(|call_node| struct_definition(&call_node, self.source));
+            match definition {
+                Some(definition) => {
+                    install_struct_class(self.genv, &class_name, &definition);
+                    Some(definition.base_class())
+                }
+                None => {
+                    let superclass_name = constant_name(&superclass, self.source)?;
+                    self.record_constant_reference(&superclass, &superclass_name);
+                    Some(superclass_name)
+                }
+            }
+        });
+        // class Foo < Base.build(...): the superclass is not known
+        let open_superclass = superclass.is_none() && class_node.superclass().is_some();
+        install_class(self.genv, class_name, superclass);
+        if open_superclass {
+            self.genv.mark_open_ancestry();
+        }
 
-        if let Some(body) = class_node.body() {
+        self.install_body(class_node.body());
+
+        exit_scope(self.genv);
+        None
+    }
+
+    /// Record a constant reference, checked once every definition is known
+    ///
+    /// References through an expression (`self.class::LIMIT`) are not checked.
+    fn record_constant_reference(&mut self, node: &Node, const_name: &str) {
+        let is_static = const_name.split("::").all(|segment| {
+            segment.starts_with(|c: char| c.is_ascii_uppercase())
+                && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+        });
+        if is_static {
+            let location =
+                SourceLocation::from_prism_location_with_source(&node.location(), self.source);
+            self.genv
+                .record_constant_reference(const_name, Some(location));
+        }
+    }
+
+    /// Record a bare identifier (`nmae`), checked once every method is known
+    ///
+    /// Only identifiers directly in an instance method body or at the top
+    /// level are checked: a block may be evaluated with another self
+    /// (`instance_eval` DSLs), and class bodies and singleton methods have
+    /// the class as self.
+    fn record_identifier(&mut self, call_node: &ruby_prism::CallNode) {
+        let self_type = match self.genv.scope_manager.current_scope().kind {
+            ScopeKind::TopLevel => Type::Instance {
+                class_name: "Object".to_string(),
+            },
+            ScopeKind::Method { .. }
+                if self
+                    .method_context
+                    .as_ref()
+                    .is_some_and(|context| !context.singleton) =>
+            {
+                Type::Instance {
+                    class_name: self
+                        .genv
+                        .scope_manager
+                        .current_namespace()
+                        .unwrap_or_else(|| "Object".to_string()),
+                }
+            }
+            _ => return,
+        };
+        let name = String::from_utf8_lossy(call_node.name().as_slice()).to_string();
+        let location =
+            SourceLocation::from_prism_location_with_source(&call_node.location(), self.source);
+        self.genv
+            .record_identifier(&name, self_type, Some(location));
+    }
+
+    /// Install a class or module body
+    fn install_body(&mut self, body: Option<Node>) {
+        if let Some(body) = body {
             if let Some(statements) = body.as_statements_node() {
                 self.install_statements(&statements);
+            } else {
+                // Body with rescue/ensure clauses (an implicit begin)
+                self.install_node(&body);
             }
         }
+    }
 
-        exit_scope(self.genv);
-        None
+    /// Install a constant write: `NAME = value`
+    ///
+    /// `Point = Struct.new(:x, :y) do ... end` and `Data.define` define a
+    /// class named after the constant; the block is its body.
+    fn install_constant_write_node(
+        &mut self,
+        constant_write: &ruby_prism::ConstantWriteNode,
+    ) -> Option<VertexId> {
+        let const_name = String::from_utf8_lossy(constant_write.name().as_slice()).to_string();
+        let value = constant_write.value();
+
+        let definition = value
+            .as_call_node()
+            .and_then(|call_node| Some((struct_definition(&call_node, self.source)?, call_node)));
+        let value_vtx = match definition {
+            Some((definition, call_node)) => {
+                install_struct_class(self.genv, &const_name, &definition);
+                if let Some(block_node) = call_node.block().and_then(|block| block.as_block_node())
+                {
+                    install_class(self.genv, const_name.clone(), Some(definition.base_class()));
+                    self.install_body(block_node.body());
+                    exit_scope(self.genv);
+                }
+                self.genv.new_source(Type::Singleton {
+                    class_name: const_name.clone(),
+                })
+            }
+            None => self.install_node(&value)?,
+        };
+
+        Some(install_constant_write(
+            self.genv,
+            &mut self.changes,
+            &const_name,
+            value_vtx,
+        ))
+    }
+
+    /// Install begin/rescue/else/ensure
+    ///
+    /// The value is the union of the body (or else clause, when present) and
+    /// every rescue clause; the ensure clause is evaluated for its side effects only.
+    fn install_begin_node(&mut self, begin_node: &ruby_prism::BeginNode) -> Option<VertexId> {
+        let result_vtx = self.genv.new_vertex();
+
+        let body_vtx = begin_node
+            .statements()
+            .and_then(|statements| self.install_statements_value(&statements));
+        // With an else clause, the body's value is discarded
+        let value_vtx = match begin_node.else_clause() {
+            Some(else_node) => else_node
+                .statements()
+                .and_then(|statements| self.install_statements_value(&statements)),
+            None => body_vtx,
+        };
+        if let Some(vtx) = value_vtx {
+            self.changes.add_edge(vtx, result_vtx);
+        }
+
+        let mut rescue_clause = begin_node.rescue_clause();
+        while let Some(rescue_node) = rescue_clause {
+            let exception_ty = rescue_exception_type(rescue_node.exceptions(), self.source);
+            if let Some(reference) = rescue_node.reference() {
+                install_rescue_reference(
+                    self.genv,
+                    self.lenv,
+                    &mut self.changes,
+                    &reference,
+                    exception_ty,
+                );
+            }
+
+            if let Some(vtx) = rescue_node
+                .statements()
+                .and_then(|statements| self.install_statements_value(&statements))
+            {
+                self.changes.add_edge(vtx, result_vtx);
+            }
+
+            rescue_clause = rescue_node.subsequent();
+        }
+
+        if let Some(statements) = begin_node
+            .ensure_clause()
+            .and_then(|ensure_node| ensure_node.statements())
+        {
+            self.install_statements(&statements);
+        }
+
+        Some(result_vtx)
+    }
+
+    /// Install case/in
+    ///
+    /// Each `in` clause binds its pattern variables against the case value;
+    /// the value of the case is the union of every clause body.
+    fn install_case_match_node(
+        &mut self,
+        case_match: &ruby_prism::CaseMatchNode,
+    ) -> Option<VertexId> {
+        let value_vtx = match case_match.predicate() {
+            Some(predicate) => self.install_node(&predicate),
+            None => None,
+        };
+        let result_vtx = self.genv.new_vertex();
+
+        for condition in &case_match.conditions() {
+            let Some(in_node) = condition.as_in_node() else {
+                continue;
+            };
+
+            if let Some(value_vtx) = value_vtx {
+                self.install_pattern(&in_node.pattern(), value_vtx);
+            }
+
+            if let Some(vtx) = in_node
+                .statements()
+                .and_then(|statements| self.install_statements_value(&statements))
+            {
+                self.changes.add_edge(vtx, result_vtx);
+            }
+        }
+
+        if let Some(vtx) = case_match
+            .else_clause()
+            .and_then(|else_node| else_node.statements())
+            .and_then(|statements| self.install_statements_value(&statements))
+        {
+            self.changes.add_edge(vtx, result_vtx);
+        }
+
+        Some(result_vtx)
+    }
+
+    /// Install a pattern matched against `value_vtx`, binding its variables
+    fn install_pattern(&mut self, pattern: &Node, value_vtx: VertexId) {
+        install_pattern(
+            self.genv,
+            self.lenv,
+            &mut self.changes,
+            pattern,
+            value_vtx,
+            self.source,
+        );
     }
 
     /// Install module definition
@@ -210,11 +986,7 @@ impl<'a> AstInstaller<'a> {
         let module_name = extract_module_name(module_node);
         install_module(self.genv, module_name);
 
-        if let Some(body) = module_node.body() {
-            if let Some(statements) = body.as_statements_node() {
-                self.install_statements(&statements);
-            }
-        }
+        self.install_body(module_node.body());
 
         exit_scope(self.genv);
         None
@@ -223,24 +995,358 @@ impl<'a> AstInstaller<'a> {
     /// Install method definition
     fn install_def_node(&mut self, def_node: &ruby_prism::DefNode) -> Option<VertexId> {
         let method_name = String::from_utf8_lossy(def_node.name().as_slice()).to_string();
-        install_method(self.genv, method_name);
+
+        // def obj.method defines a singleton of an arbitrary object, which is not tracked
+        let singleton = def_node.receiver().is_some();
+        // A bare `private` in the enclosing body only applies to instance methods
+        let visibility = if singleton {
+            Visibility::Public
+        } else {
+            self.genv.scope_manager.default_visibility()
+        };
+        install_method(self.genv, method_name.clone(), singleton);
+
+        let tracked = def_node
+            .receiver()
+            .is_none_or(|receiver| receiver.as_self_node().is_some());
+        let return_vtx = self.genv.new_vertex();
+        let outer_context = self.method_context.replace(MethodContext {
+            name: method_name.clone(),
+            singleton,
+            return_vtx: Some(return_vtx),
+            ..MethodContext::default()
+        });
 
         // Process parameters BEFORE processing body
         // This ensures parameters are available as local variables in the method body
         if let Some(params_node) = def_node.parameters() {
-            self.install_parameters(&params_node);
-        }
+            let param_vtxs = self.install_parameters(&params_node);
+            if let Some(context) = self.method_context.as_mut() {
+                context.param_vtxs = param_vtxs;
+            }
 
-        if let Some(body) = def_node.body() {
-            if let Some(statements) = body.as_statements_node() {
-                self.install_statements(&statements);
+            // def foo(&block): `block.call(x)` yields to the caller's block
+            let block_name = params_node
+                .block()
+                .and_then(|block_param| block_param.name())
+                .map(|name| String::from_utf8_lossy(name.as_slice()).to_string());
+            if let Some(name) = block_name {
+                install_proc_parameter(self.genv, self.lenv, name.clone());
+                if let Some(context) = self.method_context.as_mut() {
+                    context.block_param = Some(name);
+                }
             }
         }
 
+        if let Some(body_vtx) = self.install_method_body(def_node.body()) {
+            self.changes.add_edge(body_vtx, return_vtx);
+        }
+
+        let context =
+            std::mem::replace(&mut self.method_context, outer_context).unwrap_or_default();
+        if tracked {
+            register_user_method(
+                self.genv,
+                &method_name,
+                singleton,
+                return_vtx,
+                context.yield_vtxs,
+                context.param_vtxs,
+                visibility,
+            );
+        }
+
         exit_scope(self.genv);
         None
     }
 
+    /// Install `return value`: the value flows into the method's return vertex
+    ///
+    /// `return a, b` returns an array. Outside a `def` the value is only installed.
+    fn install_return_node(&mut self, return_node: &ruby_prism::ReturnNode) {
+        let mut arguments: Vec<Node> = return_node
+            .arguments()
+            .map(|args| args.arguments().iter().collect())
+            .unwrap_or_default();
+        let value_vtx = match arguments.len() {
+            0 => Some(self.genv.new_source(Type::Nil)),
+            1 => self.install_node(&arguments.remove(0)),
+            _ => self.install_array_literal_elements(arguments),
+        };
+
+        let return_vtx = self
+            .method_context
+            .as_ref()
+            .and_then(|context| context.return_vtx);
+        if let (Some(value_vtx), Some(return_vtx)) = (value_vtx, return_vtx) {
+            self.changes.add_edge(value_vtx, return_vtx);
+        }
+    }
+
+    /// Install a method body and return its value
+    ///
+    /// The method returns the value of its last expression (nil when empty).
+    fn install_method_body(&mut self, body: Option<Node>) -> Option<VertexId> {
+        match body {
+            Some(body) => match body.as_statements_node() {
+                Some(statements) => self.install_statements_value(&statements),
+                // Body with rescue/ensure clauses (an implicit begin)
+                None => self.install_node(&body),
+            },
+            None => Some(self.genv.new_source(Type::Nil)),
+        }
+    }
+
+    /// Check if a call is a receiverless call in a class or module body
+    /// (`private`, `alias_method`, `attr_reader`, `define_method`)
+    fn is_class_body_call(&self, call_node: &ruby_prism::CallNode) -> bool {
+        self.method_context.is_none() && call_node.receiver().is_none()
+    }
+
+    /// Visibility set by a receiverless `private`/`protected`/`public` call
+    /// in a class or module body
+    fn visibility_modifier(&self, call_node: &ruby_prism::CallNode) -> Option<Visibility> {
+        if !self.is_class_body_call(call_node) || call_node.block().is_some() {
+            return None;
+        }
+        Visibility::parse(&String::from_utf8_lossy(call_node.name().as_slice()))
+    }
+
+    /// Install `private`, `private :foo, :bar` or `private def foo ... end`
+    fn install_visibility_modifier(
+        &mut self,
+        call_node: &ruby_prism::CallNode,
+        visibility: Visibility,
+    ) -> Option<VertexId> {
+        let Some(arguments) = call_node.arguments() else {
+            // Without arguments, applies to the methods defined after it
+            self.genv.scope_manager.set_default_visibility(visibility);
+            return None;
+        };
+
+        for argument in &arguments.arguments() {
+            let method_name = if let Some(def_node) = argument.as_def_node() {
+                self.install_def_node(&def_node);
+                if def_node.receiver().is_some() {
+                    continue;
+                }
+                String::from_utf8_lossy(def_node.name().as_slice()).to_string()
+            } else if let Some(method_name) = literal_method_name(&argument) {
+                method_name
+            } else if argument.as_call_node().is_some() {
+                // private attr_reader :token: the methods it defines take the visibility
+                let default_visibility = self.genv.scope_manager.default_visibility();
+                self.genv.scope_manager.set_default_visibility(visibility);
+                self.install_node(&argument);
+                self.genv
+                    .scope_manager
+                    .set_default_visibility(default_visibility);
+                continue;
+            } else {
+                continue;
+            };
+            set_method_visibility(self.genv, &method_name, visibility);
+        }
+        None
+    }
+
+    /// Install `alias_method :new_name, :old_name`
+    fn install_alias_method(&mut self, call_node: &ruby_prism::CallNode) -> Option<VertexId> {
+        let names: Vec<String> = call_node
+            .arguments()
+            .map(|args| {
+                args.arguments()
+                    .iter()
+                    .filter_map(|arg| literal_method_name(&arg))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let [new_name, old_name] = names.as_slice() {
+            alias_user_method(self.genv, new_name, old_name);
+        }
+        None
+    }
+
+    /// Install `attr_reader :name`, `attr_writer :name` and `attr_accessor :name`
+    ///
+    /// The reader returns `@name` and the writer assigns it, like the
+    /// equivalent `def name` and `def name=(value)`.
+    fn install_attr_methods(&mut self, call_node: &ruby_prism::CallNode) -> Option<VertexId> {
+        let kind = call_node.name().as_slice();
+        let (reader, writer) = (kind != b"attr_writer", kind != b"attr_reader");
+        let visibility = self.genv.scope_manager.default_visibility();
+        let names: Vec<String> = call_node
+            .arguments()
+            .map(|args| {
+                args.arguments()
+                    .iter()
+                    .filter_map(|arg| literal_method_name(&arg))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for name in names {
+            let ivar_name = format!("@{}", name);
+            if reader {
+                install_method(self.genv, name.clone(), false);
+                let ivar_vtx = self
+                    .genv
+                    .read_instance_var(&ivar_name)
+                    .unwrap_or_else(|| self.genv.new_vertex());
+                exit_scope(self.genv);
+                register_user_method(
+                    self.genv,
+                    &name,
+                    false,
+                    ivar_vtx,
+                    Vec::new(),
+                    Vec::new(),
+                    visibility,
+                );
+            }
+            if writer {
+                let writer_name = format!("{}=", name);
+                install_method(self.genv, writer_name.clone(), false);
+                let value_vtx = self.genv.new_vertex();
+                self.genv.write_instance_var(&ivar_name, value_vtx);
+                exit_scope(self.genv);
+                register_user_method(
+                    self.genv,
+                    &writer_name,
+                    false,
+                    value_vtx,
+                    Vec::new(),
+                    vec![value_vtx],
+                    visibility,
+                );
+            }
+        }
+        None
+    }
+
+    /// Install `define_method(:name) { |args| ... }`
+    ///
+    /// The block is the method body. Only literal names are registered; a
+    /// dynamic name (`define_method(name)`) just installs the block.
+    fn install_define_method(&mut self, call_node: &ruby_prism::CallNode) -> Option<VertexId> {
+        let method_name = call_node
+            .arguments()
+            .and_then(|args| args.arguments().iter().next())
+            .and_then(|arg| literal_method_name(&arg));
+        let visibility = self.genv.scope_manager.default_visibility();
+
+        let Some(block_node) = call_node.block().and_then(|block| block.as_block_node()) else {
+            // define_method(:name, other_method): the body is not known
+            if let Some(method_name) = method_name {
+                let return_vtx = self.genv.new_vertex();
+                register_user_method(
+                    self.genv,
+                    &method_name,
+                    false,
+                    return_vtx,
+                    Vec::new(),
+                    Vec::new(),
+                    visibility,
+                );
+            }
+            return None;
+        };
+
+        enter_block_scope(self.genv);
+        let param_vtxs = match block_node
+            .parameters()
+            .and_then(|params| params.as_block_parameters_node())
+        {
+            Some(block_params) => self.install_block_parameters_with_vtxs(&block_params),
+            None => Vec::new(),
+        };
+        let return_vtx = self.genv.new_vertex();
+        if let Some(body_vtx) = self.install_method_body(block_node.body()) {
+            self.changes.add_edge(body_vtx, return_vtx);
+        }
+        exit_block_scope(self.genv);
+
+        if let Some(method_name) = method_name {
+            register_user_method(
+                self.genv,
+                &method_name,
+                false,
+                return_vtx,
+                Vec::new(),
+                param_vtxs,
+                visibility,
+            );
+        }
+        None
+    }
+
+    /// Install a `super` call from the method being defined
+    fn install_super(&mut self, arg_vtxs: Vec<VertexId>, node: &Node) -> VertexId {
+        let Some(context) = self.method_context.as_ref() else {
+            // super outside a method (e.g., in a define_method block)
+            return self.genv.new_vertex();
+        };
+
+        let location =
+            SourceLocation::from_prism_location_with_source(&node.location(), self.source);
+        install_super_call(
+            self.genv,
+            context.name.clone(),
+            context.singleton,
+            arg_vtxs,
+            Some(location),
+        )
+    }
+
+    /// Arguments passed to the method's block by `yield args` or `block.call(args)`
+    fn yielded_arguments<'n>(&self, node: &Node<'n>) -> Option<Vec<Node<'n>>> {
+        if let Some(yield_node) = node.as_yield_node() {
+            return Some(
+                yield_node
+                    .arguments()
+                    .map(|args| args.arguments().iter().collect())
+                    .unwrap_or_default(),
+            );
+        }
+
+        // block.call(x), block.(x), block.yield(x) on the `&block` parameter
+        let call_node = node.as_call_node()?;
+        let block_param = self.method_context.as_ref()?.block_param.as_ref()?;
+        let receiver = call_node.receiver()?.as_local_variable_read_node()?;
+        let method_name = call_node.name();
+        if receiver.name().as_slice() != block_param.as_bytes()
+            || !matches!(method_name.as_slice(), b"call" | b"yield")
+        {
+            return None;
+        }
+
+        Some(
+            call_node
+                .arguments()
+                .map(|args| args.arguments().iter().collect())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Install yielded arguments and connect them to the method's yielded values
+    fn install_yielded_arguments(&mut self, arguments: Vec<Node>) -> VertexId {
+        let arg_vtxs: Vec<VertexId> = arguments
+            .iter()
+            .filter_map(|arg| self.install_node(arg))
+            .collect();
+
+        match self.method_context.as_mut() {
+            Some(context) => install_yield(
+                self.genv,
+                &mut self.changes,
+                &mut context.yield_vtxs,
+                arg_vtxs,
+            ),
+            // yield outside a method (e.g., in a define_method block): nothing to connect
+            None => self.genv.new_vertex(),
+        }
+    }
+
     /// Install block node
     ///
     /// Processes blocks like `{ |x| x.to_s }` or `do |item| item.upcase end`
@@ -356,12 +1462,16 @@ impl<'a> AstInstaller<'a> {
     }
 
     /// Install method parameters as local variables
-    fn install_parameters(&mut self, params_node: &ruby_prism::ParametersNode) {
+    ///
+    /// Returns the vertices of the positional (required and optional) parameters.
+    fn install_parameters(&mut self, params_node: &ruby_prism::ParametersNode) -> Vec<VertexId> {
+        let mut positional_vtxs = Vec::new();
+
         // Required parameters: def foo(a, b)
         for node in params_node.requireds().iter() {
             if let Some(req_param) = node.as_required_parameter_node() {
                 let name = String::from_utf8_lossy(req_param.name().as_slice()).to_string();
-                install_required_parameter(self.genv, self.lenv, name);
+                positional_vtxs.push(install_required_parameter(self.genv, self.lenv, name));
             }
         }
 
@@ -372,18 +1482,19 @@ impl<'a> AstInstaller<'a> {
                 let default_value = opt_param.value();
 
                 // Process default value to get its type
-                if let Some(default_vtx) = self.install_node(&default_value) {
+                let param_vtx = if let Some(default_vtx) = self.install_node(&default_value) {
                     install_optional_parameter(
                         self.genv,
                         self.lenv,
                         &mut self.changes,
                         name,
                         default_vtx,
-                    );
+                    )
                 } else {
                     // Fallback to untyped if default can't be processed
-                    install_required_parameter(self.genv, self.lenv, name);
-                }
+                    install_required_parameter(self.genv, self.lenv, name)
+                };
+                positional_vtxs.push(param_vtx);
             }
         }
 
@@ -406,6 +1517,8 @@ impl<'a> AstInstaller<'a> {
                 }
             }
         }
+
+        positional_vtxs
     }
 
     /// Process multiple statements
@@ -415,11 +1528,32 @@ impl<'a> AstInstaller<'a> {
         }
     }
 
+    /// Process multiple statements and return the vertex of the last one
+    fn install_statements_value(
+        &mut self,
+        statements: &ruby_prism::StatementsNode,
+    ) -> Option<VertexId> {
+        statements
+            .body()
+            .iter()
+            .fold(None, |_, stmt| self.install_node(&stmt))
+    }
+
     /// Finish installation (apply changes and execute Boxes)
     pub fn finish(self) {
         self.genv.apply_changes(self.changes);
+        self.genv.finish_instance_vars();
+        self.genv.finish_references();
         self.genv.run_all();
     }
+
+    /// Finish installation without executing Boxes
+    ///
+    /// Used when installing several files into one environment: the Boxes
+    /// run once every file has declared its classes and methods.
+    pub fn finish_declarations(self) {
+        self.genv.apply_changes(self.changes);
+    }
 }
 
 #[cfg(test)]
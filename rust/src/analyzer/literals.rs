@@ -1,14 +1,17 @@
 //! Literal Handlers - Processing Ruby literal values
 //!
 //! This module is responsible for:
-//! - String, Integer, Hash literals
-//! - nil, true, false, Symbol literals
+//! - String, Integer, Float, Rational, Complex literals
+//! - nil, true, false, Symbol, Regexp literals
+//! - `__FILE__`, `__LINE__`, `__ENCODING__`
 //! - Creating Source vertices with fixed types
 //!
-//! Note: Array literals are handled in install.rs for element type inference
+//! Note: Array, Hash, Range, interpolated and lambda literals are handled in
+//! install.rs because their children need to be installed first
 
 use crate::env::GlobalEnv;
 use crate::graph::VertexId;
+use crate::source_map::SourceLocation;
 use crate::types::Type;
 use ruby_prism::Node;
 
@@ -16,25 +19,59 @@ use ruby_prism::Node;
 ///
 /// Note: Array literals are NOT handled here because they require
 /// child processing for element type inference. See install.rs.
-pub fn install_literal(genv: &mut GlobalEnv, node: &Node) -> Option<VertexId> {
-    // "hello"
-    if node.as_string_node().is_some() {
+pub fn install_literal(genv: &mut GlobalEnv, node: &Node, source: &str) -> Option<VertexId> {
+    // "hello", `ls`, __FILE__
+    if node.as_string_node().is_some()
+        || node.as_x_string_node().is_some()
+        || node.as_source_file_node().is_some()
+    {
         return Some(genv.new_source(Type::string()));
     }
 
-    // 42
-    if node.as_integer_node().is_some() {
+    // 42, __LINE__
+    if node.as_integer_node().is_some() || node.as_source_line_node().is_some() {
         return Some(genv.new_source(Type::integer()));
     }
 
-    // {a: 1}
-    if node.as_hash_node().is_some() {
-        return Some(genv.new_source(Type::hash()));
+    // 1.5
+    if node.as_float_node().is_some() {
+        return Some(genv.new_source(Type::Instance {
+            class_name: "Float".to_string(),
+        }));
+    }
+
+    // 3r
+    if node.as_rational_node().is_some() {
+        return Some(genv.new_source(Type::Instance {
+            class_name: "Rational".to_string(),
+        }));
     }
 
-    // nil
+    // 2i
+    if node.as_imaginary_node().is_some() {
+        return Some(genv.new_source(Type::Instance {
+            class_name: "Complex".to_string(),
+        }));
+    }
+
+    // /pattern/
+    if node.as_regular_expression_node().is_some() {
+        return Some(genv.new_source(Type::Instance {
+            class_name: "Regexp".to_string(),
+        }));
+    }
+
+    // __ENCODING__
+    if node.as_source_encoding_node().is_some() {
+        return Some(genv.new_source(Type::Instance {
+            class_name: "Encoding".to_string(),
+        }));
+    }
+
+    // nil (location is kept so nil receiver errors can point back here)
     if node.as_nil_node().is_some() {
-        return Some(genv.new_source(Type::Nil));
+        let location = SourceLocation::from_prism_location_with_source(&node.location(), source);
+        return Some(genv.new_source_with_location(Type::Nil, location));
     }
 
     // true
@@ -61,6 +98,18 @@ pub fn install_literal(genv: &mut GlobalEnv, node: &Node) -> Option<VertexId> {
     None
 }
 
+/// Install a value that is either true or false (`block_given?`, `x in pattern`)
+pub fn install_boolean(genv: &mut GlobalEnv) -> VertexId {
+    let result_vtx = genv.new_vertex();
+    for class_name in ["TrueClass", "FalseClass"] {
+        let src_vtx = genv.new_source(Type::Instance {
+            class_name: class_name.to_string(),
+        });
+        genv.add_edge(src_vtx, result_vtx);
+    }
+    result_vtx
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,12 +2,18 @@ mod blocks;
 mod calls;
 mod definitions;
 mod dispatch;
+mod exceptions;
 mod install;
 mod literals;
+mod multi_write;
 mod parameters;
+mod patterns;
+mod requires;
+mod structs;
 mod variables;
 
 #[cfg(test)]
 mod tests;
 
 pub use install::AstInstaller;
+pub use requires::{collect_requires, RequireCall};
@@ -0,0 +1,113 @@
+//! Multiple Assignment Handlers - Processing destructuring writes
+//!
+//! This module is responsible for:
+//! - Multiple assignment (`a, b = foo`)
+//! - Splat targets (`first, *rest = list`)
+//! - Nested destructuring (`(a, b), c = pairs`)
+
+use crate::env::{GlobalEnv, LocalEnv};
+use crate::graph::{DestructureSlot, MultiWriteBox, VertexId};
+use ruby_prism::{Node, NodeList};
+
+use super::variables::install_ivar_write;
+
+/// Install the targets of a multiple assignment
+///
+/// Each target gets a fresh vertex; a MultiWriteBox fills them in from the
+/// element types of `value_vtx` once its types are known.
+///
+/// # Example
+/// ```ruby
+/// first, *rest = [1, 2, 3]  # first: Integer, rest: Array[Integer]
+/// ```
+pub fn install_multi_write_targets(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    lefts: NodeList,
+    rest: Option<Node>,
+    rights: NodeList,
+    value_vtx: VertexId,
+) {
+    let mut targets: Vec<(DestructureSlot, VertexId)> = Vec::new();
+    let pre_len = lefts.len();
+    let post_len = rights.len();
+
+    for (i, target) in lefts.iter().enumerate() {
+        if let Some(vtx) = install_target(genv, lenv, &target) {
+            targets.push((DestructureSlot::Pre(i), vtx));
+        }
+    }
+
+    // `*rest` (anonymous `*` and the implicit rest of `a, = list` bind nothing)
+    if let Some(rest_node) = rest {
+        if let Some(splat) = rest_node.as_splat_node() {
+            if let Some(expression) = splat.expression() {
+                if let Some(vtx) = install_target(genv, lenv, &expression) {
+                    targets.push((DestructureSlot::Rest, vtx));
+                }
+            }
+        }
+    }
+
+    for (i, target) in rights.iter().enumerate() {
+        if let Some(vtx) = install_target(genv, lenv, &target) {
+            targets.push((DestructureSlot::Post(i), vtx));
+        }
+    }
+
+    if targets.is_empty() {
+        return;
+    }
+
+    let box_id = genv.alloc_box_id();
+    let multi_write_box = MultiWriteBox::new(box_id, value_vtx, targets, pre_len, post_len);
+    genv.register_box(box_id, Box::new(multi_write_box));
+}
+
+/// Install a single assignment target and return the vertex receiving its value
+///
+/// Returns None for targets that are not tracked (e.g., `obj.attr`).
+fn install_target(genv: &mut GlobalEnv, lenv: &mut LocalEnv, target: &Node) -> Option<VertexId> {
+    // Local variable: a, b = ...
+    if let Some(local) = target.as_local_variable_target_node() {
+        let var_name = String::from_utf8_lossy(local.name().as_slice()).to_string();
+        let vtx = genv.new_vertex();
+        lenv.new_var(var_name, vtx);
+        return Some(vtx);
+    }
+
+    // Instance variable: @a, @b = ...
+    if let Some(ivar) = target.as_instance_variable_target_node() {
+        let ivar_name = String::from_utf8_lossy(ivar.name().as_slice()).to_string();
+        let vtx = genv.new_vertex();
+        return Some(install_ivar_write(genv, ivar_name, vtx));
+    }
+
+    // Class variable: @@a, @@b = ... (the class variable's own vertex)
+    if let Some(cvar) = target.as_class_variable_target_node() {
+        let cvar_name = String::from_utf8_lossy(cvar.name().as_slice()).to_string();
+        return genv.class_var_vertex(&cvar_name);
+    }
+
+    // Global variable: $a, $b = ...
+    if let Some(global) = target.as_global_variable_target_node() {
+        let var_name = String::from_utf8_lossy(global.name().as_slice()).to_string();
+        return Some(genv.global_var_vertex(&var_name));
+    }
+
+    // Nested destructuring: (a, b), c = ...
+    if let Some(multi_target) = target.as_multi_target_node() {
+        let vtx = genv.new_vertex();
+        install_multi_write_targets(
+            genv,
+            lenv,
+            multi_target.lefts(),
+            multi_target.rest(),
+            multi_target.rights(),
+            vtx,
+        );
+        return Some(vtx);
+    }
+
+    None
+}
@@ -85,6 +85,28 @@ pub fn install_rest_parameter(genv: &mut GlobalEnv, lenv: &mut LocalEnv, name: S
     param_vtx
 }
 
+/// Install a block parameter (&block) as a local variable with Proc type
+///
+/// # Example
+/// ```ruby
+/// def each_name(&block)  # 'block' has type Proc
+///   names.each(&block)
+/// end
+/// ```
+pub fn install_proc_parameter(genv: &mut GlobalEnv, lenv: &mut LocalEnv, name: String) -> VertexId {
+    let param_vtx = genv.new_vertex();
+
+    // Block parameters are always Procs
+    let proc_src = genv.new_source(Type::Instance {
+        class_name: "Proc".to_string(),
+    });
+    genv.add_edge(proc_src, param_vtx);
+
+    lenv.new_var(name, param_vtx);
+
+    param_vtx
+}
+
 /// Install a keyword rest parameter (**kwargs) as a local variable with Hash type
 ///
 /// Keyword rest parameters collect all remaining keyword arguments into a Hash.
@@ -151,4 +173,15 @@ mod tests {
         assert_ne!(vtx_b, vtx_c);
         assert_ne!(vtx_a, vtx_c);
     }
+
+    #[test]
+    fn test_install_proc_parameter() {
+        let mut genv = GlobalEnv::new();
+        let mut lenv = LocalEnv::new();
+
+        let vtx = install_proc_parameter(&mut genv, &mut lenv, "block".to_string());
+
+        assert_eq!(lenv.get_var("block"), Some(vtx));
+        assert_eq!(genv.get_vertex(vtx).unwrap().show(), "Proc");
+    }
 }
@@ -0,0 +1,227 @@
+//! Pattern Matching Handlers - Processing `case/in`, `=>` and `in` patterns
+//!
+//! This module is responsible for:
+//! - Binding pattern variables (`in [x, y]`, `in {name:}`, `in String => s`)
+//! - Narrowing bound types from constant, array and hash patterns
+
+use crate::env::{GlobalEnv, LocalEnv};
+use crate::graph::{ChangeSet, DestructureSlot, HashPatternBox, MultiWriteBox, VertexId};
+use crate::types::Type;
+use ruby_prism::Node;
+
+use super::definitions::constant_name;
+use super::literals::install_literal;
+use super::variables::install_local_var_write;
+
+/// Install a pattern matched against `value_vtx`
+///
+/// Binds every variable in the pattern and returns the vertex holding the
+/// value narrowed by the pattern (what `pattern => name` binds to `name`).
+///
+/// # Example
+/// ```ruby
+/// case response
+/// in {status: Integer => code, body:}  # code: Integer, body: type of response[:body]
+/// end
+/// ```
+pub fn install_pattern(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    changes: &mut ChangeSet,
+    pattern: &Node,
+    value_vtx: VertexId,
+    source: &str,
+) -> VertexId {
+    // in x
+    if let Some(target) = pattern.as_local_variable_target_node() {
+        let var_name = String::from_utf8_lossy(target.name().as_slice()).to_string();
+        install_local_var_write(genv, lenv, changes, var_name, value_vtx);
+        return value_vtx;
+    }
+
+    // in String => s
+    if let Some(capture) = pattern.as_capture_pattern_node() {
+        let narrowed_vtx =
+            install_pattern(genv, lenv, changes, &capture.value(), value_vtx, source);
+        let target = capture.target();
+        let var_name = String::from_utf8_lossy(target.name().as_slice()).to_string();
+        install_local_var_write(genv, lenv, changes, var_name, narrowed_vtx);
+        return narrowed_vtx;
+    }
+
+    // in Integer | Float
+    if let Some(alternation) = pattern.as_alternation_pattern_node() {
+        let result_vtx = genv.new_vertex();
+        for branch in [alternation.left(), alternation.right()] {
+            let branch_vtx = install_pattern(genv, lenv, changes, &branch, value_vtx, source);
+            changes.add_edge(branch_vtx, result_vtx);
+        }
+        return result_vtx;
+    }
+
+    // in [x, *rest], in Point[x, y]
+    if let Some(array_pattern) = pattern.as_array_pattern_node() {
+        let mut targets: Vec<(DestructureSlot, Node)> = Vec::new();
+        for (i, element) in array_pattern.requireds().iter().enumerate() {
+            targets.push((DestructureSlot::Pre(i), element));
+        }
+        // *rest (anonymous `*` binds nothing)
+        if let Some(rest) = array_pattern.rest().as_ref().and_then(splat_target) {
+            targets.push((DestructureSlot::Rest, rest));
+        }
+        for (i, element) in array_pattern.posts().iter().enumerate() {
+            targets.push((DestructureSlot::Post(i), element));
+        }
+
+        let pre_len = array_pattern.requireds().len();
+        let post_len = array_pattern.posts().len();
+        install_array_pattern(
+            genv, lenv, changes, targets, pre_len, post_len, value_vtx, source,
+        );
+        return narrowed_by_constant(genv, array_pattern.constant(), value_vtx, source);
+    }
+
+    // in [*, x, *]
+    if let Some(find_pattern) = pattern.as_find_pattern_node() {
+        let mut targets: Vec<(DestructureSlot, Node)> = Vec::new();
+        if let Some(left) = splat_target(&find_pattern.left().as_node()) {
+            targets.push((DestructureSlot::Rest, left));
+        }
+        for element in &find_pattern.requireds() {
+            targets.push((DestructureSlot::Any, element));
+        }
+        if let Some(right) = splat_target(&find_pattern.right()) {
+            targets.push((DestructureSlot::Rest, right));
+        }
+
+        install_array_pattern(genv, lenv, changes, targets, 0, 0, value_vtx, source);
+        return narrowed_by_constant(genv, find_pattern.constant(), value_vtx, source);
+    }
+
+    // in {name: String => name, **rest}
+    if let Some(hash_pattern) = pattern.as_hash_pattern_node() {
+        install_hash_pattern(genv, lenv, changes, &hash_pattern, value_vtx, source);
+        return narrowed_by_constant(genv, hash_pattern.constant(), value_vtx, source);
+    }
+
+    // in User, in Net::HTTPSuccess
+    if let Some(class_name) = constant_name(pattern, source) {
+        return genv.new_source(Type::Instance { class_name });
+    }
+
+    // in nil, in :ok, in "text"
+    if let Some(literal_vtx) = install_literal(genv, pattern, source) {
+        return literal_vtx;
+    }
+
+    // Other patterns (ranges, pinned values) do not narrow the value
+    value_vtx
+}
+
+/// Bind the elements of an array or find pattern
+///
+/// Each sub-pattern is matched against a vertex filled in by a MultiWriteBox.
+#[allow(clippy::too_many_arguments)]
+fn install_array_pattern(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    changes: &mut ChangeSet,
+    targets: Vec<(DestructureSlot, Node)>,
+    pre_len: usize,
+    post_len: usize,
+    value_vtx: VertexId,
+    source: &str,
+) {
+    if targets.is_empty() {
+        return;
+    }
+
+    let mut slot_vtxs: Vec<(DestructureSlot, VertexId)> = Vec::new();
+    let mut sub_patterns: Vec<(Node, VertexId)> = Vec::new();
+    for (slot, sub_pattern) in targets {
+        let element_vtx = genv.new_vertex();
+        slot_vtxs.push((slot, element_vtx));
+        sub_patterns.push((sub_pattern, element_vtx));
+    }
+
+    let box_id = genv.alloc_box_id();
+    let destructure_box =
+        MultiWriteBox::new(box_id, value_vtx, slot_vtxs, pre_len, post_len).for_pattern();
+    genv.register_box(box_id, Box::new(destructure_box));
+
+    for (sub_pattern, element_vtx) in sub_patterns {
+        install_pattern(genv, lenv, changes, &sub_pattern, element_vtx, source);
+    }
+}
+
+/// Pattern bound by a splat (`*rest`), or None for an anonymous `*`
+fn splat_target<'pr>(node: &Node<'pr>) -> Option<Node<'pr>> {
+    node.as_splat_node()?.expression()
+}
+
+/// Bind the keys of a hash pattern
+fn install_hash_pattern(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    changes: &mut ChangeSet,
+    hash_pattern: &ruby_prism::HashPatternNode,
+    value_vtx: VertexId,
+    source: &str,
+) {
+    let mut keys: Vec<(String, VertexId)> = Vec::new();
+    let mut sub_patterns: Vec<(Node, VertexId)> = Vec::new();
+
+    for element in &hash_pattern.elements() {
+        let Some(assoc) = element.as_assoc_node() else {
+            continue;
+        };
+        let Some(symbol) = assoc.key().as_symbol_node() else {
+            continue;
+        };
+        let key = String::from_utf8_lossy(symbol.unescaped()).to_string();
+        let key_vtx = genv.new_vertex();
+        keys.push((key, key_vtx));
+
+        // `{name:}` is shorthand for `{name: name}`
+        let value = assoc.value();
+        match value.as_implicit_node() {
+            Some(implicit) => sub_patterns.push((implicit.value(), key_vtx)),
+            None => sub_patterns.push((value, key_vtx)),
+        }
+    }
+
+    // **rest (`**nil` binds nothing)
+    let mut rest_vtx = None;
+    if let Some(rest_value) = hash_pattern
+        .rest()
+        .and_then(|rest_node| rest_node.as_assoc_splat_node())
+        .and_then(|splat| splat.value())
+    {
+        let vtx = genv.new_vertex();
+        rest_vtx = Some(vtx);
+        sub_patterns.push((rest_value, vtx));
+    }
+
+    if !keys.is_empty() || rest_vtx.is_some() {
+        let box_id = genv.alloc_box_id();
+        let hash_pattern_box = HashPatternBox::new(box_id, value_vtx, keys, rest_vtx);
+        genv.register_box(box_id, Box::new(hash_pattern_box));
+    }
+
+    for (sub_pattern, key_vtx) in sub_patterns {
+        install_pattern(genv, lenv, changes, &sub_pattern, key_vtx, source);
+    }
+}
+
+/// `Point[x, y]` narrows the value to `Point`; a bare `[x, y]` keeps it as is
+fn narrowed_by_constant(
+    genv: &mut GlobalEnv,
+    constant: Option<Node>,
+    value_vtx: VertexId,
+    source: &str,
+) -> VertexId {
+    match constant.and_then(|constant| constant_name(&constant, source)) {
+        Some(class_name) => genv.new_source(Type::Instance { class_name }),
+        None => value_vtx,
+    }
+}
@@ -0,0 +1,231 @@
+//! Require Handlers - Finding the files a Ruby file loads
+//!
+//! This module is responsible for:
+//! - Collecting `require "name"` / `require_relative "path"` calls with a literal path
+//! - Resolving them to files: `require_relative` from the requiring file's
+//!   directory, `require` against the load paths (e.g., `lib/`)
+//! - Telling project requires from gem and standard library ones
+
+use crate::source_map::SourceLocation;
+use ruby_prism::{CallNode, Node, Visit};
+use std::path::{Path, PathBuf};
+
+/// `require` or `require_relative`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequireKind {
+    Require,
+    RequireRelative,
+}
+
+impl RequireKind {
+    /// Method name as written in Ruby
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequireKind::Require => "require",
+            RequireKind::RequireRelative => "require_relative",
+        }
+    }
+}
+
+/// A `require` / `require_relative` call with a literal path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequireCall {
+    pub kind: RequireKind,
+    pub path: String,
+    pub location: SourceLocation,
+}
+
+impl RequireCall {
+    /// Resolve the required file
+    ///
+    /// `require_relative` is relative to the directory of `from_file`;
+    /// `require` is looked up in each load path in order. The `.rb`
+    /// extension may be omitted.
+    pub fn resolve(&self, from_file: &Path, load_paths: &[PathBuf]) -> Option<PathBuf> {
+        match self.kind {
+            RequireKind::RequireRelative => {
+                let dir = from_file.parent().unwrap_or_else(|| Path::new("."));
+                ruby_file(&dir.join(&self.path))
+            }
+            RequireKind::Require => load_paths
+                .iter()
+                .find_map(|load_path| ruby_file(&load_path.join(&self.path))),
+        }
+    }
+
+    /// Check if the required file belongs to the project
+    ///
+    /// A `require_relative` always does. A `require` does when the first
+    /// segment of its path is in a load path (`billing/invoice` with
+    /// `lib/billing/`); other names are gems or standard libraries (`json`).
+    pub fn is_project_file(&self, load_paths: &[PathBuf]) -> bool {
+        match self.kind {
+            RequireKind::RequireRelative => true,
+            RequireKind::Require => {
+                let first_segment = self.path.split('/').next().unwrap_or_default();
+                load_paths.iter().any(|load_path| {
+                    let path = load_path.join(first_segment);
+                    path.is_dir() || ruby_file(&path).is_some()
+                })
+            }
+        }
+    }
+}
+
+/// The Ruby file a required path refers to, if it exists
+fn ruby_file(path: &Path) -> Option<PathBuf> {
+    if path.extension().is_some_and(|ext| ext == "rb") {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let mut with_extension = path.as_os_str().to_owned();
+    with_extension.push(".rb");
+    let with_extension = PathBuf::from(with_extension);
+    with_extension.is_file().then_some(with_extension)
+}
+
+/// Collect the `require` / `require_relative` calls of a program
+///
+/// Calls with a receiver or a non-literal path (`require File.join(...)`)
+/// are not collected.
+pub fn collect_requires(node: &Node, source: &str) -> Vec<RequireCall> {
+    let mut collector = RequireCollector {
+        source,
+        requires: Vec::new(),
+    };
+    collector.visit(node);
+    collector.requires
+}
+
+struct RequireCollector<'a> {
+    source: &'a str,
+    requires: Vec<RequireCall>,
+}
+
+impl<'pr> Visit<'pr> for RequireCollector<'_> {
+    fn visit_call_node(&mut self, node: &CallNode<'pr>) {
+        if let Some(require) = require_call(node, self.source) {
+            self.requires.push(require);
+        }
+        ruby_prism::visit_call_node(self, node);
+    }
+}
+
+/// Recognize `require "name"` / `require_relative "path"`
+fn require_call(call_node: &CallNode, source: &str) -> Option<RequireCall> {
+    if call_node.receiver().is_some() {
+        return None;
+    }
+    let kind = match call_node.name().as_slice() {
+        b"require" => RequireKind::Require,
+        b"require_relative" => RequireKind::RequireRelative,
+        _ => return None,
+    };
+
+    let args = call_node.arguments()?.arguments();
+    if args.iter().count() != 1 {
+        return None;
+    }
+    let string_node = args.iter().next()?.as_string_node()?;
+
+    Some(RequireCall {
+        kind,
+        path: String::from_utf8_lossy(string_node.unescaped()).to_string(),
+        location: SourceLocation::from_prism_location_with_source(&call_node.location(), source),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ruby_source;
+
+    fn requires_of(source: &str) -> Vec<RequireCall> {
+        let parse_result = parse_ruby_source(source, "test.rb".to_string()).unwrap();
+        collect_requires(&parse_result.node(), source)
+    }
+
+    #[test]
+    fn test_collect_requires() {
+        let requires = requires_of(
+            r#"
+require "json"
+require_relative "user/validations"
+
+module App
+  require "app/version" if defined?(App)
+end
+
+require File.join("lib", "x")
+Kernel.require "set"
+"#,
+        );
+
+        let paths: Vec<(RequireKind, &str)> = requires
+            .iter()
+            .map(|require| (require.kind, require.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (RequireKind::Require, "json"),
+                (RequireKind::RequireRelative, "user/validations"),
+                (RequireKind::Require, "app/version"),
+            ]
+        );
+        assert_eq!(requires[1].location.line, 3);
+    }
+
+    #[test]
+    fn test_resolve_requires() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = dir.path().join("lib");
+        std::fs::create_dir_all(lib.join("app")).unwrap();
+        std::fs::create_dir_all(dir.path().join("models/user")).unwrap();
+        std::fs::write(lib.join("app/version.rb"), "").unwrap();
+        std::fs::write(dir.path().join("models/user/validations.rb"), "").unwrap();
+        let user_rb = dir.path().join("models/user.rb");
+
+        let requires = requires_of(
+            r#"
+require_relative "user/validations"
+require "app/version.rb"
+require "json"
+"#,
+        );
+        let load_paths = vec![lib.clone()];
+
+        assert_eq!(
+            requires[0].resolve(&user_rb, &load_paths),
+            Some(dir.path().join("models/user/validations.rb"))
+        );
+        assert_eq!(
+            requires[1].resolve(&user_rb, &load_paths),
+            Some(lib.join("app/version.rb"))
+        );
+        assert_eq!(requires[2].resolve(&user_rb, &load_paths), None);
+    }
+
+    #[test]
+    fn test_is_project_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = dir.path().join("lib");
+        std::fs::create_dir_all(lib.join("billing")).unwrap();
+        std::fs::write(lib.join("app.rb"), "").unwrap();
+
+        let requires = requires_of(
+            r#"
+require_relative "missing"
+require "billing/invoise"
+require "app/version"
+require "json"
+"#,
+        );
+        let load_paths = vec![lib];
+
+        let project_files: Vec<bool> = requires
+            .iter()
+            .map(|require| require.is_project_file(&load_paths))
+            .collect();
+        assert_eq!(project_files, vec![true, true, true, false]);
+    }
+}
@@ -0,0 +1,188 @@
+//! Struct Handlers - Synthesizing classes from `Struct.new` and `Data.define`
+//!
+//! This module is responsible for:
+//! - Recognizing `Struct.new(:x, :y)` and `Data.define(:lat, :lng)` calls
+//! - Registering the synthesized class with its member readers (and writers for Struct)
+//! - Registering the `new`/`[]` constructors, whose arguments fill the members
+//!   (more positional arguments than members raise ArgumentError)
+//!
+//! Other methods (`to_h`, `members`, ...) resolve through the Struct/Data base class.
+
+use crate::env::method_registry::Visibility;
+use crate::env::GlobalEnv;
+use crate::graph::VertexId;
+use crate::types::Type;
+use ruby_prism::CallNode;
+
+use super::definitions::constant_name;
+
+/// Base class of a synthesized class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructKind {
+    /// `Struct.new(:x, :y)`: mutable members
+    Struct,
+    /// `Data.define(:lat, :lng)`: immutable members
+    Data,
+}
+
+impl StructKind {
+    fn base_class(&self) -> &'static str {
+        match self {
+            StructKind::Struct => "Struct",
+            StructKind::Data => "Data",
+        }
+    }
+}
+
+/// A `Struct.new` / `Data.define` call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructDefinition {
+    pub kind: StructKind,
+    /// Member names, in declaration (and constructor argument) order
+    pub members: Vec<String>,
+}
+
+impl StructDefinition {
+    /// Name of the class the synthesized class inherits from
+    pub fn base_class(&self) -> String {
+        self.kind.base_class().to_string()
+    }
+}
+
+/// Recognize `Struct.new(:x, :y)` or `Data.define(:lat, :lng)`
+///
+/// Non-symbol arguments (`keyword_init: true`) are not members.
+pub fn struct_definition(call_node: &CallNode, source: &str) -> Option<StructDefinition> {
+    let receiver = call_node.receiver()?;
+    let kind = match (
+        constant_name(&receiver, source)?.as_str(),
+        call_node.name().as_slice(),
+    ) {
+        ("Struct", b"new") => StructKind::Struct,
+        ("Data", b"define") => StructKind::Data,
+        _ => return None,
+    };
+
+    let members = call_node
+        .arguments()
+        .map(|args| {
+            args.arguments()
+                .iter()
+                .filter_map(|arg| {
+                    arg.as_symbol_node()
+                        .map(|symbol| String::from_utf8_lossy(symbol.unescaped()).to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(StructDefinition { kind, members })
+}
+
+/// Register the class synthesized by a `Struct.new` / `Data.define` call
+///
+/// Each member gets one vertex shared by its reader, its writer and the
+/// matching constructor argument.
+pub fn install_struct_class(genv: &mut GlobalEnv, class_name: &str, definition: &StructDefinition) {
+    genv.register_class(class_name, Some(definition.base_class()));
+
+    let instance_ty = Type::Instance {
+        class_name: class_name.to_string(),
+    };
+    let member_vtxs: Vec<VertexId> = definition
+        .members
+        .iter()
+        .map(|_| genv.new_vertex())
+        .collect();
+
+    for (member, &member_vtx) in definition.members.iter().zip(&member_vtxs) {
+        // point.x
+        genv.register_user_method(
+            instance_ty.clone(),
+            member,
+            Some(member_vtx),
+            Vec::new(),
+            Vec::new(),
+            Visibility::Public,
+        );
+        // point.x = 1 (Data members are immutable)
+        if definition.kind == StructKind::Struct {
+            genv.register_user_method(
+                instance_ty.clone(),
+                &format!("{}=", member),
+                Some(member_vtx),
+                Vec::new(),
+                vec![member_vtx],
+                Visibility::Public,
+            );
+        }
+    }
+
+    // Point.new(1, 2), Point[1, 2]
+    let singleton_ty = Type::Singleton {
+        class_name: class_name.to_string(),
+    };
+    for constructor in ["new", "[]"] {
+        let instance_vtx = genv.new_source(instance_ty.clone());
+        genv.register_user_method(
+            singleton_ty.clone(),
+            constructor,
+            Some(instance_vtx),
+            Vec::new(),
+            member_vtxs.clone(),
+            Visibility::Public,
+        );
+        // Missing members are nil (Struct) or given as keywords (Data)
+        genv.set_max_args(&singleton_ty, constructor, member_vtxs.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_definition(source: &str) -> Option<StructDefinition> {
+        let parse_result = crate::parser::parse_ruby_source(source, "test.rb".to_string()).unwrap();
+        let root = parse_result.node();
+        let program = root.as_program_node().unwrap();
+        let stmt = program.statements().body().iter().next().unwrap();
+        let call_node = stmt.as_call_node().unwrap();
+        struct_definition(&call_node, source)
+    }
+
+    #[test]
+    fn test_struct_definition() {
+        let definition = parse_definition("Struct.new(:x, :y, keyword_init: true)").unwrap();
+        assert_eq!(definition.kind, StructKind::Struct);
+        assert_eq!(definition.members, vec!["x", "y"]);
+
+        let definition = parse_definition("Data.define(:lat, :lng)").unwrap();
+        assert_eq!(definition.kind, StructKind::Data);
+        assert_eq!(definition.base_class(), "Data");
+
+        assert!(parse_definition("Struct.define(:x)").is_none());
+        assert!(parse_definition("Point.new(:x)").is_none());
+    }
+
+    #[test]
+    fn test_install_struct_class_accessors() {
+        let mut genv = GlobalEnv::new();
+        let definition = StructDefinition {
+            kind: StructKind::Data,
+            members: vec!["lat".to_string(), "lng".to_string()],
+        };
+        install_struct_class(&mut genv, "Coord", &definition);
+
+        let coord = Type::Instance {
+            class_name: "Coord".to_string(),
+        };
+        let coord_class = Type::Singleton {
+            class_name: "Coord".to_string(),
+        };
+        assert!(genv.resolve_method(&coord, "lat").is_some());
+        assert!(genv.resolve_method(&coord, "lat=").is_none());
+        let constructor = genv.resolve_method(&coord_class, "new").unwrap();
+        assert_eq!(constructor.param_vtxs.len(), 2);
+        assert_eq!(constructor.max_args, Some(2));
+    }
+}
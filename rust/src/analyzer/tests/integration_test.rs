@@ -7,27 +7,107 @@
 //! - Method chain type inference
 
 use crate::analyzer::AstInstaller;
+use crate::env::method_registry::Visibility;
+use crate::env::type_error::TypeErrorKind;
 use crate::env::{GlobalEnv, LocalEnv};
 use crate::parser::parse_ruby_source;
 use crate::types::Type;
 
 /// Helper to run analysis on Ruby source code
 fn analyze(source: &str) -> (GlobalEnv, LocalEnv) {
-    let parse_result = parse_ruby_source(source, "test.rb".to_string()).unwrap();
+    let mut genv = builtin_env();
+    let lenv = install_source(&mut genv, source, true);
+    (genv, lenv)
+}
+
+/// Helper to run the two-phase project analysis and check the first source
+fn analyze_project(sources: &[&str]) -> (GlobalEnv, LocalEnv) {
+    let mut index_genv = builtin_env();
+    for source in sources {
+        install_source(&mut index_genv, source, false);
+    }
+    index_genv.finish_instance_vars();
+    index_genv.run_all();
+    let index = index_genv.project_index();
+
+    let mut genv = builtin_env();
+    genv.load_project_index(&index);
+    let lenv = install_source(&mut genv, sources[0], true);
+    (genv, lenv)
+}
+
+/// Helper to run analysis with the core classes declared, as when the RBS
+/// cache is loaded (enables the constant and identifier checks)
+fn analyze_with_core(source: &str) -> (GlobalEnv, LocalEnv) {
+    let mut genv = core_env();
+    let lenv = install_source(&mut genv, source, true);
+    (genv, lenv)
+}
+
+/// Helper to run analysis with the core classes declared, as in a project
+/// check without gems or standard libraries (every top-level constant known)
+fn analyze_closed_project(source: &str) -> (GlobalEnv, LocalEnv) {
+    let mut genv = core_env();
+    genv.mark_top_level_constants_known();
+    let lenv = install_source(&mut genv, source, true);
+    (genv, lenv)
+}
+
+/// Helper to create a GlobalEnv with the core classes declared
+fn core_env() -> GlobalEnv {
+    let mut genv = builtin_env();
+    for constant in [
+        "Object",
+        "String",
+        "Integer",
+        "Array",
+        "Hash",
+        "File",
+        "File::SEPARATOR",
+    ] {
+        genv.register_builtin_constant(constant);
+    }
+    let object = Type::Instance {
+        class_name: "Object".to_string(),
+    };
+    genv.register_builtin_method(object, "puts", Type::Nil);
 
+    genv
+}
+
+/// Helper to create a GlobalEnv with the built-in methods the tests use
+fn builtin_env() -> GlobalEnv {
     let mut genv = GlobalEnv::new();
 
     // Register common methods
     genv.register_builtin_method(Type::string(), "upcase", Type::string());
     genv.register_builtin_method(Type::string(), "downcase", Type::string());
+    genv.register_builtin_method(Type::integer(), "+", Type::integer());
 
     // Register iterator methods for block tests
     genv.register_builtin_method(Type::array(), "each", Type::array());
     genv.register_builtin_method(Type::array(), "map", Type::array());
     genv.register_builtin_method(Type::hash(), "each", Type::hash());
 
+    // Register element writes
+    genv.register_builtin_method(Type::array(), "[]=", Type::Bot);
+    genv.register_builtin_method(Type::hash(), "[]=", Type::Bot);
+
+    // Register base class methods for Struct/Data tests
+    let struct_ty = Type::Instance {
+        class_name: "Struct".to_string(),
+    };
+    genv.register_builtin_method(struct_ty, "to_h", Type::hash());
+
+    genv
+}
+
+/// Helper to install Ruby source code, optionally running the Boxes
+fn install_source(genv: &mut GlobalEnv, source: &str, run_boxes: bool) -> LocalEnv {
+    let parse_result = parse_ruby_source(source, "test.rb".to_string()).unwrap();
+
     let mut lenv = LocalEnv::new();
-    let mut installer = AstInstaller::new(&mut genv, &mut lenv, source);
+    let mut installer = AstInstaller::new(genv, &mut lenv, source);
 
     let root = parse_result.node();
 
@@ -38,9 +118,13 @@ fn analyze(source: &str) -> (GlobalEnv, LocalEnv) {
         }
     }
 
-    installer.finish();
+    if run_boxes {
+        installer.finish();
+    } else {
+        installer.finish_declarations();
+    }
 
-    (genv, lenv)
+    lenv
 }
 
 #[test]
@@ -405,3 +489,2250 @@ end
     // No type errors should occur
     assert_eq!(genv.type_errors.len(), 0);
 }
+
+// ============================================
+// Nil Receiver Tests
+// ============================================
+
+#[test]
+fn test_nil_receiver_reports_nil_origin() {
+    let source = r#"
+x = nil
+x.upcase
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 1);
+    let err = &genv.type_errors[0];
+    assert_eq!(err.method_name, "upcase");
+    match &err.kind {
+        TypeErrorKind::NilReceiver { nil_origin } => {
+            let origin = nil_origin.as_ref().expect("nil origin should be tracked");
+            assert_eq!(origin.line, 2);
+            assert_eq!(origin.column, 5);
+        }
+        other => panic!("expected NilReceiver, got {:?}", other),
+    }
+}
+
+// ============================================
+// Multiple Assignment Tests
+// ============================================
+
+#[test]
+fn test_multi_write_from_array_literal() {
+    let source = r#"
+a, b = 1, 2
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let a_vtx = lenv.get_var("a").expect("a should be defined");
+    let b_vtx = lenv.get_var("b").expect("b should be defined");
+    assert_eq!(genv.get_vertex(a_vtx).unwrap().show(), "Integer");
+    assert_eq!(genv.get_vertex(b_vtx).unwrap().show(), "Integer");
+}
+
+#[test]
+fn test_multi_write_splat_target() {
+    let source = r#"
+list = ["a", "b", "c"]
+first, *rest = list
+first.upcase
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let first_vtx = lenv.get_var("first").unwrap();
+    let rest_vtx = lenv.get_var("rest").unwrap();
+    assert_eq!(genv.get_vertex(first_vtx).unwrap().show(), "String");
+    assert_eq!(genv.get_vertex(rest_vtx).unwrap().show(), "Array[String]");
+    assert_eq!(genv.type_errors.len(), 0);
+}
+
+#[test]
+fn test_multi_write_from_method_call() {
+    let source = r#"
+name = "john"
+head, tail = name.upcase
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    // A non-array value is bound to the first target only
+    let head_vtx = lenv.get_var("head").unwrap();
+    let tail_vtx = lenv.get_var("tail").unwrap();
+    assert_eq!(genv.get_vertex(head_vtx).unwrap().show(), "String");
+    assert_eq!(genv.get_vertex(tail_vtx).unwrap().show(), "nil");
+}
+
+#[test]
+fn test_multi_write_nested_destructuring() {
+    let source = r#"
+(a, b), c = [[1, 2], [3, 4]]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let a_vtx = lenv.get_var("a").unwrap();
+    let c_vtx = lenv.get_var("c").unwrap();
+    assert_eq!(genv.get_vertex(a_vtx).unwrap().show(), "Integer");
+    assert_eq!(genv.get_vertex(c_vtx).unwrap().show(), "Array[Integer]");
+}
+
+#[test]
+fn test_multi_write_instance_variables() {
+    let source = r#"
+class Point
+  def initialize
+    @x, @y = 1, 2
+  end
+
+  def label
+    @x.upcase
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "Integer");
+}
+
+// ============================================
+// Operator Assignment Tests
+// ============================================
+
+#[test]
+fn test_local_var_or_write_unions_previous_value() {
+    let source = r#"
+name = nil
+name ||= "guest"
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let name_vtx = lenv.get_var("name").unwrap();
+    assert_eq!(genv.get_vertex(name_vtx).unwrap().show(), "(String | nil)");
+}
+
+#[test]
+fn test_ivar_or_write_memoization() {
+    let source = r#"
+class Repo
+  def cache
+    @cache ||= "empty"
+  end
+
+  def fetch
+    @cache.upcase
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    // @cache is typed by the memoized value, but fetch may run before cache
+    assert_eq!(genv.type_errors.len(), 1);
+    assert!(matches!(
+        genv.type_errors[0].kind,
+        TypeErrorKind::NilReceiver { .. }
+    ));
+}
+
+#[test]
+fn test_local_var_operator_write_calls_operator() {
+    let source = r#"
+count = 0
+count += 1
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let count_vtx = lenv.get_var("count").unwrap();
+    assert_eq!(genv.get_vertex(count_vtx).unwrap().show(), "Integer");
+    assert_eq!(genv.type_errors.len(), 0);
+}
+
+#[test]
+fn test_operator_write_reports_undefined_operator() {
+    let source = r#"
+label = "a"
+label -= "b"
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "-");
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "String");
+    let location = genv.type_errors[0].location.as_ref().unwrap();
+    assert_eq!(location.line, 3);
+    assert_eq!(location.column, 7);
+}
+
+#[test]
+fn test_index_operator_write_calls_operator_on_element() {
+    let source = r#"
+labels = ["a"]
+labels[0] -= "b"
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "-");
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "String");
+}
+
+#[test]
+fn test_attribute_operator_write_reads_and_writes() {
+    let source = r#"
+class Profile
+  attr_accessor :visits
+
+  def initialize
+    @visits = 0
+  end
+
+  def visit
+    self.visits += 1
+    self.title ||= "untitled"
+  end
+
+  def report
+    count = self.visits
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let count_vtx = lenv.get_var("count").unwrap();
+    assert_eq!(genv.get_vertex(count_vtx).unwrap().show(), "Integer");
+
+    let mut names: Vec<&str> = genv
+        .type_errors
+        .iter()
+        .map(|error| error.method_name.as_str())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["title", "title="]);
+}
+
+// ============================================
+// Safe Navigation Tests
+// ============================================
+
+#[test]
+fn test_safe_navigation_skips_nil_receiver() {
+    let source = r#"
+name = nil
+name ||= "guest"
+result = name&.upcase
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    // No nil receiver error, and the result may be nil
+    assert_eq!(genv.type_errors.len(), 0);
+    let result_vtx = lenv.get_var("result").unwrap();
+    assert_eq!(
+        genv.get_vertex(result_vtx).unwrap().show(),
+        "(String | nil)"
+    );
+}
+
+#[test]
+fn test_safe_navigation_on_non_nil_receiver() {
+    let source = r#"
+name = "guest"
+result = name&.upcase
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let result_vtx = lenv.get_var("result").unwrap();
+    assert_eq!(genv.get_vertex(result_vtx).unwrap().show(), "String");
+}
+
+// ============================================
+// Literal Tests
+// ============================================
+
+#[test]
+fn test_numeric_and_regexp_literal_types() {
+    let source = r#"
+f = 1.5
+r = 3r
+c = 2i
+re = /ab+c/
+file = __FILE__
+line = __LINE__
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let show = |name: &str| genv.get_vertex(lenv.get_var(name).unwrap()).unwrap().show();
+    assert_eq!(show("f"), "Float");
+    assert_eq!(show("r"), "Rational");
+    assert_eq!(show("c"), "Complex");
+    assert_eq!(show("re"), "Regexp");
+    assert_eq!(show("file"), "String");
+    assert_eq!(show("line"), "Integer");
+}
+
+#[test]
+fn test_range_literal_types() {
+    let source = r#"
+digits = 1..10
+letters = "a"..."z"
+endless = (1..)
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let show = |name: &str| genv.get_vertex(lenv.get_var(name).unwrap()).unwrap().show();
+    assert_eq!(show("digits"), "Range[Integer]");
+    assert_eq!(show("letters"), "Range[String]");
+    assert_eq!(show("endless"), "Range[Integer]");
+}
+
+#[test]
+fn test_interpolated_literals() {
+    let source = r#"
+name = "john"
+greeting = "hello #{name.upcase}"
+sym = :"key_#{name}"
+pattern = /#{name}/
+words = %w[a b c]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let show = |name: &str| genv.get_vertex(lenv.get_var(name).unwrap()).unwrap().show();
+    assert_eq!(show("greeting"), "String");
+    assert_eq!(show("sym"), "Symbol");
+    assert_eq!(show("pattern"), "Regexp");
+    assert_eq!(show("words"), "Array[String]");
+}
+
+#[test]
+fn test_error_inside_interpolation_is_found() {
+    let source = r#"
+count = 42
+message = <<~EOS
+  total: #{count.upcase}
+EOS
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "upcase");
+    let message_vtx = lenv.get_var("message").unwrap();
+    assert_eq!(genv.get_vertex(message_vtx).unwrap().show(), "String");
+}
+
+#[test]
+fn test_lambda_literal() {
+    let source = r#"
+formatter = ->(x) { 42.upcase }
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let formatter_vtx = lenv.get_var("formatter").unwrap();
+    assert_eq!(genv.get_vertex(formatter_vtx).unwrap().show(), "Proc");
+    // Lambda body is analyzed
+    assert_eq!(genv.type_errors.len(), 1);
+}
+
+// ============================================
+// Hash Literal Tests
+// ============================================
+
+#[test]
+fn test_hash_literal_generic_type() {
+    let source = r#"
+ages = { "alice" => 30, "bob" => 25 }
+empty = {}
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let ages_vtx = lenv.get_var("ages").unwrap();
+    let empty_vtx = lenv.get_var("empty").unwrap();
+    assert_eq!(
+        genv.get_vertex(ages_vtx).unwrap().show(),
+        "Hash[String, Integer]"
+    );
+    assert_eq!(genv.get_vertex(empty_vtx).unwrap().show(), "Hash");
+}
+
+#[test]
+fn test_symbol_keyed_hash_literal_is_record() {
+    let source = r#"
+opts = { timeout: 30, name: "job" }
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let opts_vtx = lenv.get_var("opts").unwrap();
+    assert_eq!(
+        genv.get_vertex(opts_vtx).unwrap().show(),
+        "{ timeout: Integer, name: String }"
+    );
+}
+
+#[test]
+fn test_record_field_access() {
+    let source = r#"
+opts = { timeout: 30, name: "job" }
+label = opts[:name].upcase
+limit = opts[:timeout]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 0);
+    let label_vtx = lenv.get_var("label").unwrap();
+    let limit_vtx = lenv.get_var("limit").unwrap();
+    assert_eq!(genv.get_vertex(label_vtx).unwrap().show(), "String");
+    assert_eq!(genv.get_vertex(limit_vtx).unwrap().show(), "Integer");
+}
+
+#[test]
+fn test_record_unknown_key_is_flagged() {
+    let source = r#"
+opts = { timeout: 30 }
+retries = opts[:retries]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(
+        genv.type_errors[0].kind,
+        TypeErrorKind::UnknownRecordKey {
+            key: "retries".to_string()
+        }
+    );
+    let retries_vtx = lenv.get_var("retries").unwrap();
+    assert_eq!(genv.get_vertex(retries_vtx).unwrap().show(), "nil");
+}
+
+#[test]
+fn test_hash_literal_with_untyped_value_is_not_record() {
+    let source = r#"
+def build(name)
+  opts = { name: name, retries: 3 }
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    // The parameter is typed after installation: no untyped field is baked in
+    let opts_vtx = lenv.get_var("opts").unwrap();
+    assert_eq!(genv.get_vertex(opts_vtx).unwrap().show(), "Hash");
+    assert!(genv.type_errors.is_empty());
+}
+
+// ============================================
+// Index Access Tests
+// ============================================
+
+#[test]
+fn test_array_index_read_is_optional_element() {
+    let source = r#"
+names = ["alice", "bob"]
+first = names[0]
+pair = names[0..1]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let first_vtx = lenv.get_var("first").unwrap();
+    let pair_vtx = lenv.get_var("pair").unwrap();
+    assert_eq!(genv.get_vertex(first_vtx).unwrap().show(), "(String | nil)");
+    assert_eq!(
+        genv.get_vertex(pair_vtx).unwrap().show(),
+        "(Array[String] | nil)"
+    );
+}
+
+#[test]
+fn test_array_index_read_may_be_nil() {
+    let source = r#"
+names = ["alice", "bob"]
+names[0].upcase
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert!(matches!(
+        genv.type_errors[0].kind,
+        TypeErrorKind::NilReceiver { .. }
+    ));
+}
+
+#[test]
+fn test_hash_index_read_is_optional_value() {
+    let source = r#"
+ages = { "alice" => 30 }
+age = ages["alice"]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let age_vtx = lenv.get_var("age").unwrap();
+    assert_eq!(genv.get_vertex(age_vtx).unwrap().show(), "(Integer | nil)");
+}
+
+#[test]
+fn test_index_write_widens_elements() {
+    let source = r#"
+list = [1, 2]
+list[2] = "three"
+item = list[0]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 0);
+    let item_vtx = lenv.get_var("item").unwrap();
+    assert_eq!(
+        genv.get_vertex(item_vtx).unwrap().show(),
+        "(Integer | String | nil)"
+    );
+}
+
+#[test]
+fn test_record_index_write_adds_key() {
+    let source = r#"
+opts = { timeout: 30 }
+opts[:retries] = 3
+retries = opts[:retries]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 0);
+    let retries_vtx = lenv.get_var("retries").unwrap();
+    assert_eq!(genv.get_vertex(retries_vtx).unwrap().show(), "Integer");
+}
+
+#[test]
+fn test_record_mutated_by_hash_method_is_widened() {
+    let cases = [
+        ("opts.merge!(extra)", "(Integer | String | nil)"),
+        ("opts.update(extra)", "(Integer | String | nil)"),
+        ("opts.store(:mode, \"fast\")", "(Integer | String | nil)"),
+        ("opts.replace(extra)", "(Integer | String | nil)"),
+        ("opts.delete(:timeout)", "(Integer | nil)"),
+        ("opts.default = \"none\"", "(Integer | String | nil)"),
+    ];
+
+    for (mutation, expected) in cases {
+        let source = format!(
+            "opts = {{ timeout: 30 }}\nextra = {{ mode: \"fast\" }}\n{mutation}\nmode = opts[:mode]\n"
+        );
+
+        let (genv, lenv) = analyze(&source);
+
+        // Any key may have been added or removed: `:mode` is not reported
+        assert!(
+            !genv
+                .type_errors
+                .iter()
+                .any(|error| matches!(error.kind, TypeErrorKind::UnknownRecordKey { .. })),
+            "{mutation}"
+        );
+        let mode_vtx = lenv.get_var("mode").unwrap();
+        assert_eq!(
+            genv.get_vertex(mode_vtx).unwrap().show(),
+            expected,
+            "{mutation}"
+        );
+    }
+}
+
+#[test]
+fn test_record_passed_to_mutating_method_is_widened() {
+    let source = r#"
+class Job
+  def self.configure(options)
+    options.delete(:timeout)
+  end
+end
+
+opts = { timeout: 30 }
+Job.configure(opts)
+limit = opts[:timeout]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let limit_vtx = lenv.get_var("limit").unwrap();
+    assert_eq!(
+        genv.get_vertex(limit_vtx).unwrap().show(),
+        "(Integer | nil)"
+    );
+}
+
+#[test]
+fn test_index_write_on_unsupported_receiver() {
+    let source = r#"
+count = 1
+count[0] = 2
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "[]=");
+}
+
+// ============================================
+// Tuple Tests
+// ============================================
+
+#[test]
+fn test_heterogeneous_array_literal_is_tuple() {
+    let source = r#"
+pair = [1, "a"]
+list = [1, 2, 3]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let pair_vtx = lenv.get_var("pair").unwrap();
+    let list_vtx = lenv.get_var("list").unwrap();
+    assert_eq!(
+        genv.get_vertex(pair_vtx).unwrap().show(),
+        "[Integer, String]"
+    );
+    assert_eq!(genv.get_vertex(list_vtx).unwrap().show(), "Array[Integer]");
+}
+
+#[test]
+fn test_tuple_index_read_is_positional() {
+    let source = r#"
+pair = [1, "a"]
+pair[1].upcase
+pair[-2].upcase
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    // Only `pair[-2]` (Integer) lacks upcase
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "Integer");
+}
+
+#[test]
+fn test_tuple_destructuring_is_positional() {
+    let source = r#"
+id, name = [1, "alice"]
+first, *middle, last = [1, "a", :b, 2.5]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let show = |name: &str| genv.get_vertex(lenv.get_var(name).unwrap()).unwrap().show();
+    assert_eq!(show("id"), "Integer");
+    assert_eq!(show("name"), "String");
+    assert_eq!(show("first"), "Integer");
+    assert_eq!(show("middle"), "Array[String | Symbol]");
+    assert_eq!(show("last"), "Float");
+}
+
+#[test]
+fn test_mutated_tuple_is_widened() {
+    let source = r#"
+pair = [1, "a"]
+pair[0] = :id
+value = pair[1]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let value_vtx = lenv.get_var("value").unwrap();
+    assert_eq!(
+        genv.get_vertex(value_vtx).unwrap().show(),
+        "(Integer | String | Symbol | nil)"
+    );
+}
+
+#[test]
+fn test_tuple_mutated_by_array_method_is_widened() {
+    let source = r#"
+pair = [1, "a"]
+pair << :id
+third = pair[2]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let third_vtx = lenv.get_var("third").unwrap();
+    assert_eq!(
+        genv.get_vertex(third_vtx).unwrap().show(),
+        "(Integer | String | Symbol | nil)"
+    );
+}
+
+#[test]
+fn test_array_method_names_mutate_only_arrays() {
+    let source = r#"
+pair = [1, "a"]
+pair.delete(1)
+name = "a"
+name << "b"
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    // `String#<<` appends to the string, not to an element vertex
+    assert!(genv.is_element_written(lenv.get_var("pair").unwrap()));
+    assert!(!genv.is_element_written(lenv.get_var("name").unwrap()));
+}
+
+#[test]
+fn test_tuple_passed_as_argument_is_widened() {
+    let source = r#"
+class Store
+  def self.add(list)
+    list << :id
+  end
+end
+
+pair = [1, "a"]
+Store.add(pair)
+third = pair[2]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    // The callee adds a Symbol to its parameter
+    let third_vtx = lenv.get_var("third").unwrap();
+    assert_eq!(
+        genv.get_vertex(third_vtx).unwrap().show(),
+        "(Integer | String | Symbol | nil)"
+    );
+}
+
+#[test]
+fn test_tuple_passed_to_reading_method_is_kept() {
+    let source = r#"
+class Report
+  def self.show(list)
+    list[0]
+  end
+end
+
+pair = [1, "a"]
+Report.show(pair)
+name = pair[1]
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let name_vtx = lenv.get_var("name").unwrap();
+    assert_eq!(genv.get_vertex(name_vtx).unwrap().show(), "String");
+}
+
+#[test]
+fn test_tuple_passed_to_method_of_other_file_is_widened() {
+    let main = r#"
+pair = [1, "a"]
+Store.add(pair)
+third = pair[2]
+"#;
+    let store = r#"
+class Store
+  def self.add(list)
+    list << :id
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze_project(&[main, store]);
+
+    let third_vtx = lenv.get_var("third").unwrap();
+    assert_eq!(
+        genv.get_vertex(third_vtx).unwrap().show(),
+        "(Integer | String | Symbol | nil)"
+    );
+}
+
+// ============================================
+// Exception Handling Tests
+// ============================================
+
+#[test]
+fn test_rescue_variable_defaults_to_standard_error() {
+    let source = r#"
+begin
+  x = 1
+rescue => e
+  y = 2
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let e_vtx = lenv.get_var("e").unwrap();
+    assert_eq!(genv.get_vertex(e_vtx).unwrap().show(), "StandardError");
+}
+
+#[test]
+fn test_rescue_variable_is_union_of_listed_classes() {
+    let source = r#"
+begin
+  x = 1
+rescue ArgumentError, ::Net::ReadTimeout => e
+  y = 2
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let e_vtx = lenv.get_var("e").unwrap();
+    assert_eq!(
+        genv.get_vertex(e_vtx).unwrap().show(),
+        "(ArgumentError | Net::ReadTimeout)"
+    );
+}
+
+#[test]
+fn test_begin_value_is_union_of_body_and_rescue() {
+    let source = r#"
+value = begin
+  "ok"
+rescue ArgumentError
+  1
+rescue
+  nil
+ensure
+  cleanup = :done
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let value_vtx = lenv.get_var("value").unwrap();
+    assert_eq!(
+        genv.get_vertex(value_vtx).unwrap().show(),
+        "(Integer | String | nil)"
+    );
+    assert!(lenv.get_var("cleanup").is_some());
+}
+
+#[test]
+fn test_begin_else_replaces_body_value() {
+    let source = r#"
+value = begin
+  "ok"
+rescue
+  1
+else
+  :fine
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let value_vtx = lenv.get_var("value").unwrap();
+    assert_eq!(
+        genv.get_vertex(value_vtx).unwrap().show(),
+        "(Integer | Symbol)"
+    );
+}
+
+#[test]
+fn test_rescue_modifier_value() {
+    let source = r#"
+value = "ok".upcase rescue 0
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let value_vtx = lenv.get_var("value").unwrap();
+    assert_eq!(
+        genv.get_vertex(value_vtx).unwrap().show(),
+        "(Integer | String)"
+    );
+}
+
+#[test]
+fn test_errors_detected_in_all_clauses() {
+    let source = r#"
+class Worker
+  def run
+    1.upcase
+  rescue => e
+    2.downcase
+  else
+    3.upcase
+  ensure
+    4.downcase
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 4);
+}
+
+// ============================================
+// Pattern Matching Tests
+// ============================================
+
+#[test]
+fn test_case_in_hash_pattern_binds_record_fields() {
+    let source = r#"
+user = { name: "alice", age: 30 }
+case user
+in { name: String => name, age: }
+  name.upcase
+  age.upcase
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let show = |name: &str| genv.get_vertex(lenv.get_var(name).unwrap()).unwrap().show();
+    assert_eq!(show("name"), "String");
+    assert_eq!(show("age"), "Integer");
+
+    // `age.upcase` is checked inside the `in` body
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "Integer");
+}
+
+#[test]
+fn test_case_in_array_pattern_binds_elements() {
+    let source = r#"
+pair = [1, "a"]
+case pair
+in [Integer => id, label]
+  x = 1
+end
+
+list = [1, 2, 3]
+case list
+in [first, *rest]
+  y = 2
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let show = |name: &str| genv.get_vertex(lenv.get_var(name).unwrap()).unwrap().show();
+    assert_eq!(show("id"), "Integer");
+    assert_eq!(show("label"), "String");
+    assert_eq!(show("first"), "Integer");
+    assert_eq!(show("rest"), "Array[Integer]");
+}
+
+#[test]
+fn test_case_in_find_pattern_binds_element_union() {
+    let source = r#"
+pair = [1, "a"]
+case pair
+in [*, found, *]
+  x = 1
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let found_vtx = lenv.get_var("found").unwrap();
+    assert_eq!(
+        genv.get_vertex(found_vtx).unwrap().show(),
+        "(Integer | String)"
+    );
+}
+
+#[test]
+fn test_case_in_value_is_union_of_branches() {
+    let source = r#"
+value = case 1
+        in Integer | Float => n
+          "number"
+        in String
+          :text
+        else
+          nil
+        end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let value_vtx = lenv.get_var("value").unwrap();
+    assert_eq!(
+        genv.get_vertex(value_vtx).unwrap().show(),
+        "(String | Symbol | nil)"
+    );
+    let n_vtx = lenv.get_var("n").unwrap();
+    assert_eq!(genv.get_vertex(n_vtx).unwrap().show(), "(Float | Integer)");
+}
+
+#[test]
+fn test_rightward_assignment_binds_pattern() {
+    let source = r#"
+config = { host: "localhost", port: 8080 }
+config => { host:, **rest }
+[1, 2] => [a, b]
+matched = (config in { port: Integer })
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let show = |name: &str| genv.get_vertex(lenv.get_var(name).unwrap()).unwrap().show();
+    assert_eq!(show("host"), "String");
+    assert_eq!(show("rest"), "{ port: Integer }");
+    assert_eq!(show("a"), "Integer");
+    assert_eq!(show("b"), "Integer");
+    assert_eq!(show("matched"), "(FalseClass | TrueClass)");
+}
+
+#[test]
+fn test_array_pattern_does_not_match_scalar() {
+    let source = r#"
+case "text"
+in [x]
+  y = 1
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let x_vtx = lenv.get_var("x").unwrap();
+    assert_eq!(genv.get_vertex(x_vtx).unwrap().show(), "untyped");
+}
+
+// ============================================
+// Yield and Block Parameter Tests
+// ============================================
+
+#[test]
+fn test_yield_types_caller_block_parameters() {
+    let source = r#"
+class Roster
+  def each_name
+    yield "alice"
+    yield "bob"
+  end
+
+  def shout
+    self.each_name { |name| name.upcase }
+    self.each_name { |other| other.floor }
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let name_vtx = lenv.get_var("name").unwrap();
+    assert_eq!(genv.get_vertex(name_vtx).unwrap().show(), "String");
+
+    // Only `other.floor` is undefined
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "floor");
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "String");
+}
+
+#[test]
+fn test_explicit_block_parameter_is_proc_and_call_yields() {
+    let source = r#"
+class Counter
+  def each_count(&block)
+    handler = block
+    block.call(1, "one")
+  end
+
+  def run
+    self.each_count { |n, label| label.upcase }
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let show = |name: &str| genv.get_vertex(lenv.get_var(name).unwrap()).unwrap().show();
+    assert_eq!(show("handler"), "Proc");
+    assert_eq!(show("n"), "Integer");
+    assert_eq!(show("label"), "String");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_block_given_is_boolean() {
+    let source = r#"
+def maybe_yield
+  given = block_given?
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let given_vtx = lenv.get_var("given").unwrap();
+    assert_eq!(
+        genv.get_vertex(given_vtx).unwrap().show(),
+        "(FalseClass | TrueClass)"
+    );
+}
+
+#[test]
+fn test_user_method_return_value_flows_to_call() {
+    let source = r#"
+class Greeter
+  def greeting
+    "hello"
+  end
+
+  def run
+    message = self.greeting
+    self.missing
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let message_vtx = lenv.get_var("message").unwrap();
+    assert_eq!(genv.get_vertex(message_vtx).unwrap().show(), "String");
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "missing");
+}
+
+#[test]
+fn test_explicit_return_flows_to_call() {
+    let source = r#"
+class Greeter
+  def greeting
+    return "hello"
+  end
+
+  def check(flag)
+    if flag
+      "yes"
+    end
+  end
+
+  def run
+    message = self.greeting
+    self.check(true).upcase
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let message_vtx = lenv.get_var("message").unwrap();
+    assert_eq!(genv.get_vertex(message_vtx).unwrap().show(), "String");
+    // An untyped return value is not an error
+    assert!(genv.type_errors.is_empty());
+}
+
+// ============================================
+// Super Call Tests
+// ============================================
+
+#[test]
+fn test_super_returns_superclass_method_value() {
+    let source = r#"
+class Base
+  def label(prefix)
+    "base"
+  end
+end
+
+class Child < Base
+  def label(prefix)
+    value = super
+    explicit = super("x")
+    value
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let show = |name: &str| genv.get_vertex(lenv.get_var(name).unwrap()).unwrap().show();
+    assert_eq!(show("value"), "String");
+    assert_eq!(show("explicit"), "String");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_super_forwards_arguments_to_superclass_parameters() {
+    let source = r#"
+class Base
+  def initialize(name)
+    stored = name
+  end
+end
+
+class Child < Base
+  def initialize(name)
+    super
+  end
+
+  def rename
+    super(42)
+  end
+end
+
+class Other < Base
+  def initialize
+    super("alice")
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    // Base#initialize's `name` receives the String passed by Other
+    let stored_vtx = lenv.get_var("stored").unwrap();
+    assert_eq!(genv.get_vertex(stored_vtx).unwrap().show(), "String");
+
+    // Base has no `rename`, and its ancestry is fully known
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].kind, TypeErrorKind::NoSuperMethod);
+    assert_eq!(genv.type_errors[0].method_name, "rename");
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "Child");
+}
+
+#[test]
+fn test_super_with_unknown_superclass_is_not_reported() {
+    let source = r#"
+class Model < ActiveRecord::Base
+  def save
+    super
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_super_through_mixin_or_expression_superclass_is_not_reported() {
+    let source = r#"
+class Base
+end
+
+class Admin < Base
+  include Auditable
+
+  def save
+    super
+  end
+end
+
+class Job < Base.with_retries(3)
+  def perform
+    super
+  end
+end
+
+class Report < Admin
+  def save
+    super
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    // The module, or the class built by the expression, may define the method
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_inherited_user_method_resolves() {
+    let source = r#"
+class Base
+  def greeting
+    "hello"
+  end
+end
+
+class Child < Base
+  def run
+    message = self.greeting
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let message_vtx = lenv.get_var("message").unwrap();
+    assert_eq!(genv.get_vertex(message_vtx).unwrap().show(), "String");
+    assert!(genv.type_errors.is_empty());
+}
+
+// ============================================
+// Visibility Tests
+// ============================================
+
+#[test]
+fn test_private_method_with_explicit_receiver_is_reported() {
+    let source = r#"
+class Account
+  def balance
+    other = self
+    other.secret
+  end
+
+  private
+
+  def secret
+    "hidden"
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(
+        genv.type_errors[0].kind,
+        TypeErrorKind::VisibilityViolation {
+            visibility: Visibility::Private
+        }
+    );
+    assert_eq!(genv.type_errors[0].method_name, "secret");
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "Account");
+}
+
+#[test]
+fn test_private_method_with_self_receiver_is_allowed() {
+    let source = r#"
+class Wallet
+  def total
+    amount = self.cents
+  end
+
+  private def cents
+    100
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let amount_vtx = lenv.get_var("amount").unwrap();
+    assert_eq!(genv.get_vertex(amount_vtx).unwrap().show(), "Integer");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_private_with_symbol_and_public_reset() {
+    let source = r#"
+class Vault
+  def code
+    "1234"
+  end
+  private :code
+
+  private
+
+  def pin
+    "0000"
+  end
+
+  public
+
+  def open
+    vault = self
+    vault.code
+    vault.pin
+    vault.label
+  end
+
+  def label
+    "vault"
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    let reported: Vec<&str> = genv
+        .type_errors
+        .iter()
+        .map(|error| error.method_name.as_str())
+        .collect();
+    assert_eq!(reported, vec!["code", "pin"]);
+}
+
+#[test]
+fn test_protected_method_within_hierarchy_is_allowed() {
+    let source = r#"
+class Member
+  protected
+
+  def rank
+    1
+  end
+end
+
+class Leader < Member
+  def compare
+    peer = self
+    peer_rank = peer.rank
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let rank_vtx = lenv.get_var("peer_rank").unwrap();
+    assert_eq!(genv.get_vertex(rank_vtx).unwrap().show(), "Integer");
+    assert!(genv.type_errors.is_empty());
+}
+
+// ============================================
+// Metaprogramming Tests
+// ============================================
+
+#[test]
+fn test_alias_and_alias_method() {
+    let source = r#"
+class Person
+  def name
+    "alice"
+  end
+  alias full_name name
+  alias_method :nickname, :name
+
+  def describe
+    person = self
+    first = person.full_name
+    second = person.nickname
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let first_vtx = lenv.get_var("first").unwrap();
+    assert_eq!(genv.get_vertex(first_vtx).unwrap().show(), "String");
+    let second_vtx = lenv.get_var("second").unwrap();
+    assert_eq!(genv.get_vertex(second_vtx).unwrap().show(), "String");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_alias_before_definition_and_across_files() {
+    let person = r#"
+class Person
+  alias_method :title, :name
+
+  def name
+    "alice"
+  end
+
+  def describe
+    person = self
+    label = person.title
+    person.nickname
+  end
+end
+"#;
+    let person_ext = r#"
+class Person
+  alias_method :nickname, :name
+end
+"#;
+
+    let (genv, lenv) = analyze_project(&[person, person_ext]);
+
+    let label_vtx = lenv.get_var("label").unwrap();
+    assert_eq!(genv.get_vertex(label_vtx).unwrap().show(), "String");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_define_method_with_literal_name() {
+    let source = r#"
+class Counter
+  define_method(:count) do
+    42
+  end
+
+  def report
+    counter = self
+    total = counter.count
+    counter.missing_count
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let total_vtx = lenv.get_var("total").unwrap();
+    assert_eq!(genv.get_vertex(total_vtx).unwrap().show(), "Integer");
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "missing_count");
+}
+
+#[test]
+fn test_method_missing_suppresses_undefined_method() {
+    let source = r#"
+class Ghost
+  def method_missing(name, *args)
+    nil
+  end
+
+  def haunt
+    ghost = self
+    ghost.boo
+  end
+end
+
+class Poltergeist < Ghost
+  def rattle
+    spirit = self
+    spirit.chains
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert!(genv.type_errors.is_empty());
+}
+
+// ============================================
+// Struct and Data Tests
+// ============================================
+
+#[test]
+fn test_struct_new_members_and_constructor() {
+    let source = r#"
+Point = Struct.new(:x, :y)
+point = Point.new(1, "one")
+px = point.x
+py = point.y
+point.x = 2.5
+hash = point.to_h
+point.z
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let px_vtx = lenv.get_var("px").unwrap();
+    assert_eq!(genv.get_vertex(px_vtx).unwrap().show(), "(Float | Integer)");
+    let py_vtx = lenv.get_var("py").unwrap();
+    assert_eq!(genv.get_vertex(py_vtx).unwrap().show(), "String");
+    let hash_vtx = lenv.get_var("hash").unwrap();
+    assert_eq!(genv.get_vertex(hash_vtx).unwrap().show(), "Hash");
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "z");
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "Point");
+}
+
+#[test]
+fn test_data_define_with_block_body() {
+    let source = r#"
+Coord = Data.define(:lat, :lng) do
+  def label
+    "coord"
+  end
+end
+coord = Coord[35, 139]
+lat = coord.lat
+label = coord.label
+coord.lat = 1
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let lat_vtx = lenv.get_var("lat").unwrap();
+    assert_eq!(genv.get_vertex(lat_vtx).unwrap().show(), "Integer");
+    let label_vtx = lenv.get_var("label").unwrap();
+    assert_eq!(genv.get_vertex(label_vtx).unwrap().show(), "String");
+
+    // Data members have no writers
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "lat=");
+}
+
+#[test]
+fn test_struct_constructor_arity_and_class_methods() {
+    let source = r#"
+Point = Struct.new(:x, :y)
+Point.new(1)
+Point.new(1, 2, 3)
+Point.members
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    // Missing members are nil; class methods of Struct are not loaded
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(
+        genv.type_errors[0].kind,
+        TypeErrorKind::WrongArity { given: 3, max: 2 }
+    );
+    assert_eq!(genv.type_errors[0].method_name, "new");
+    assert_eq!(genv.type_errors[0].location.as_ref().unwrap().line, 4);
+}
+
+#[test]
+fn test_class_inheriting_struct_new() {
+    let source = r#"
+class Pair < Struct.new(:left, :right)
+  def swap
+    pair = self
+    first = pair.right
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    assert!(lenv.get_var("first").is_some());
+    assert!(genv.type_errors.is_empty());
+}
+
+// ============================================
+// Class Variable, Global Variable and Constant Tests
+// ============================================
+
+#[test]
+fn test_class_variable_shared_across_methods() {
+    let source = r#"
+class Registry
+  def read_label
+    current_label = @@label
+  end
+
+  def write_label
+    @@label = "registry"
+    @@count ||= 0
+    @@count += 1
+  end
+
+  def read_count
+    current_count = @@count
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    // Reads before the write in source order see the written type
+    let label_vtx = lenv.get_var("current_label").unwrap();
+    assert_eq!(genv.get_vertex(label_vtx).unwrap().show(), "String");
+    let count_vtx = lenv.get_var("current_count").unwrap();
+    assert_eq!(genv.get_vertex(count_vtx).unwrap().show(), "Integer");
+}
+
+#[test]
+fn test_global_variables() {
+    let source = r#"
+$app_name = "methodray"
+app = $app_name
+app.upcase
+out = $stdout
+script = $PROGRAM_NAME
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let app_vtx = lenv.get_var("app").unwrap();
+    assert_eq!(genv.get_vertex(app_vtx).unwrap().show(), "String");
+    let out_vtx = lenv.get_var("out").unwrap();
+    assert_eq!(genv.get_vertex(out_vtx).unwrap().show(), "IO");
+    let script_vtx = lenv.get_var("script").unwrap();
+    assert_eq!(genv.get_vertex(script_vtx).unwrap().show(), "String");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_constant_value_typing() {
+    let source = r#"
+MAX = 10
+limit = MAX
+MAX.upcase
+
+class Config
+  DEFAULTS = "defaults"
+
+  def defaults
+    own = DEFAULTS
+  end
+end
+
+qualified = Config::DEFAULTS
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let limit_vtx = lenv.get_var("limit").unwrap();
+    assert_eq!(genv.get_vertex(limit_vtx).unwrap().show(), "Integer");
+    let own_vtx = lenv.get_var("own").unwrap();
+    assert_eq!(genv.get_vertex(own_vtx).unwrap().show(), "String");
+    let qualified_vtx = lenv.get_var("qualified").unwrap();
+    assert_eq!(genv.get_vertex(qualified_vtx).unwrap().show(), "String");
+
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "upcase");
+    assert_eq!(genv.type_errors[0].receiver_type.show(), "Integer");
+}
+
+// ============================================
+// Instance Variable Tests
+// ============================================
+
+#[test]
+fn test_ivar_unions_writes_across_methods() {
+    let source = r#"
+class Account
+  def initialize
+    @owner = "alice"
+  end
+
+  def owner_name
+    owner = @owner
+  end
+
+  def transfer
+    @owner = 42
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    // The read in owner_name sees the write in a later method
+    let owner_vtx = lenv.get_var("owner").unwrap();
+    assert_eq!(
+        genv.get_vertex(owner_vtx).unwrap().show(),
+        "(Integer | String)"
+    );
+}
+
+#[test]
+fn test_ivar_shared_across_reopened_class() {
+    let source = r#"
+class Session
+  def initialize
+    @token = "abc"
+  end
+end
+
+class Session
+  def token_size
+    session_token = @token
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let token_vtx = lenv.get_var("session_token").unwrap();
+    assert_eq!(genv.get_vertex(token_vtx).unwrap().show(), "String");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_ivar_in_module() {
+    let source = r#"
+module Labeled
+  def label=(value)
+    @label = "label"
+  end
+
+  def label_text
+    module_label = @label
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let label_vtx = lenv.get_var("module_label").unwrap();
+    assert_eq!(genv.get_vertex(label_vtx).unwrap().show(), "(String | nil)");
+}
+
+#[test]
+fn test_ivar_inherits_parent_initialize() {
+    let source = r#"
+class Base
+  def initialize
+    @title = "base"
+  end
+end
+
+class Page < Base
+  def heading
+    @title.upcase
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    // Assigned by the inherited initialize, so never nil
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_ivar_read_before_assignment_is_nilable() {
+    let source = r#"
+class Counter
+  def current
+    current_label = @label
+  end
+
+  def reset
+    @label = "zero"
+  end
+
+  def touch
+    @touched = "yes"
+    touched_value = @touched
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let label_vtx = lenv.get_var("current_label").unwrap();
+    assert_eq!(genv.get_vertex(label_vtx).unwrap().show(), "(String | nil)");
+    // Assigned earlier in the same method before being read
+    let touched_vtx = lenv.get_var("touched_value").unwrap();
+    assert_eq!(genv.get_vertex(touched_vtx).unwrap().show(), "String");
+}
+
+#[test]
+fn test_class_level_ivar_is_separate_from_instance_ivar() {
+    let source = r#"
+class Registry
+  @entries = 0
+
+  def self.count
+    class_entries = @entries
+  end
+
+  def initialize
+    @entries = "list"
+  end
+
+  def entries
+    instance_entries = @entries
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let class_vtx = lenv.get_var("class_entries").unwrap();
+    assert_eq!(genv.get_vertex(class_vtx).unwrap().show(), "Integer");
+    let instance_vtx = lenv.get_var("instance_entries").unwrap();
+    assert_eq!(genv.get_vertex(instance_vtx).unwrap().show(), "String");
+}
+
+// ============================================
+// Project Index Tests
+// ============================================
+
+#[test]
+fn test_project_reopened_class_across_files() {
+    let user = r#"
+class User
+  def initialize
+    @name = "alice"
+  end
+
+  def name
+    @name
+  end
+end
+"#;
+    let validations = r#"
+class User
+  def valid?
+    shouted = self.name.upcase
+    @name.downcase
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze_project(&[validations, user]);
+
+    // name and @name come from user.rb, with their types
+    let shouted_vtx = lenv.get_var("shouted").unwrap();
+    assert_eq!(genv.get_vertex(shouted_vtx).unwrap().show(), "String");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_project_checked_file_reopens_class_of_other_file() {
+    let account = r#"
+class Account
+  def initialize
+    @balance = 0
+  end
+
+  def deposit(amount)
+    total = @balance
+    total + amount
+  end
+end
+"#;
+    let audit = r#"
+class Account
+  def audit
+    @balance
+  end
+end
+
+class SavingsAccount < Account
+  def interest
+    @balance
+  end
+end
+"#;
+
+    // account.rb is in the index and installed again when checked
+    let (genv, lenv) = analyze_project(&[account, audit]);
+
+    let total_vtx = lenv.get_var("total").unwrap();
+    assert_eq!(genv.get_vertex(total_vtx).unwrap().show(), "Integer");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_project_declarations_used_before_definition() {
+    let app = r#"
+class App
+  def title
+    label = Settings::NAME
+    label.upcase
+    Formatter.new.shout("x")
+  end
+end
+"#;
+    let settings = r#"
+module Settings
+  NAME = "app"
+end
+
+class Formatter
+  def shout(text)
+    "LOUD"
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze_project(&[app, settings]);
+
+    let label_vtx = lenv.get_var("label").unwrap();
+    assert_eq!(genv.get_vertex(label_vtx).unwrap().show(), "String");
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_project_monkey_patch() {
+    let core_ext = r#"
+class String
+  def shout
+    upcase
+  end
+end
+"#;
+    let main = r#"
+greeting = "hi".shout
+"#;
+
+    let (genv, _lenv) = analyze_project(&[main, core_ext]);
+
+    assert!(genv.type_errors.is_empty());
+}
+
+// ============================================
+// Constant and Identifier Reference Tests
+// ============================================
+
+#[test]
+fn test_uninitialized_constant() {
+    let source = r#"
+user = Usr.new
+admin = Admin.new
+separator = File::SEPARATOR
+
+class Admin
+end
+"#;
+
+    let (genv, _lenv) = analyze_closed_project(source);
+
+    // Admin is defined after its use
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(
+        genv.type_errors[0].kind,
+        TypeErrorKind::UninitializedConstant
+    );
+    assert_eq!(genv.type_errors[0].method_name, "Usr");
+}
+
+#[test]
+fn test_constant_lexical_and_ancestor_lookup() {
+    let source = r#"
+module Billing
+  RATE = 10
+
+  class Base
+    LIMIT = 5
+  end
+
+  class Invoice < Base
+    def total
+      rate = RATE
+      limit = LIMIT
+      inherited = Invoice::LIMIT
+      missing = Billing::RAET
+    end
+  end
+end
+
+class Config
+  TIMEOUT = 30
+end
+
+timeout = Config::TIMEOUT
+typo = Config::TIMOUT
+"#;
+
+    let (genv, _lenv) = analyze_with_core(source);
+
+    let mut missing: Vec<&str> = genv
+        .type_errors
+        .iter()
+        .map(|error| error.method_name.as_str())
+        .collect();
+    missing.sort();
+    assert_eq!(missing, vec!["Billing::RAET", "Config::TIMOUT"]);
+}
+
+#[test]
+fn test_constant_in_class_with_unknown_superclass() {
+    let source = r#"
+class Post < ApplicationRecord
+  def page_size
+    PER_PAGE
+  end
+end
+"#;
+
+    let (genv, _lenv) = analyze_closed_project(source);
+
+    // PER_PAGE may come from ApplicationRecord, which is itself undefined
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "ApplicationRecord");
+}
+
+#[test]
+fn test_library_constants_are_not_reported() {
+    let source = r#"
+require "json"
+
+class User < ActiveRecord::Base
+  def self.parse(text)
+    JSON.parse(text)
+  end
+
+  def log
+    Rails.logger
+    Order.new
+  end
+end
+
+class Config
+  TIMEOUT = 30
+end
+
+typo = Config::TIMOUT
+"#;
+
+    let (genv, _lenv) = analyze_with_core(source);
+
+    // Gems, standard libraries and other project files may define top-level
+    // constants; a namespace of the project is still checked
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "Config::TIMOUT");
+}
+
+#[test]
+fn test_references_in_namespace_with_mixin_are_not_reported() {
+    let source = r#"
+class User
+  include Helpers
+
+  def label
+    helper_name
+  end
+
+  def limit
+    MAX_ITEMS
+  end
+end
+
+module Reports
+  include Formatting
+
+  def header
+    TITLE
+  end
+end
+
+Reports::WIDTH
+"#;
+
+    let (genv, _lenv) = analyze_closed_project(source);
+
+    // The mixed-in modules may define the methods and constants
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_undefined_local_variable_or_method() {
+    let source = r#"
+class Greeter
+  attr_reader :name
+
+  def initialize
+    @name = "world"
+  end
+
+  def greet
+    name
+    nmae
+    puts
+    [1].each { |n| anything }
+  end
+
+  def self.build
+    new
+  end
+end
+
+helper
+missing_helper
+
+def helper
+end
+"#;
+
+    let (genv, _lenv) = analyze_with_core(source);
+
+    let mut undefined: Vec<(String, String)> = genv
+        .type_errors
+        .iter()
+        .map(|error| {
+            assert_eq!(error.kind, TypeErrorKind::UndefinedLocal);
+            (error.receiver_type.show(), error.method_name.clone())
+        })
+        .collect();
+    undefined.sort();
+    assert_eq!(
+        undefined,
+        vec![
+            ("Greeter".to_string(), "nmae".to_string()),
+            ("Object".to_string(), "missing_helper".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_references_not_checked_without_core_declarations() {
+    let source = r#"
+user = Usr.new
+missing_helper
+"#;
+
+    let (genv, _lenv) = analyze(source);
+
+    assert!(genv.type_errors.is_empty());
+}
+
+#[test]
+fn test_attr_accessor_methods() {
+    let source = r#"
+class Profile
+  attr_accessor :bio
+  attr_reader :nickname
+
+  def initialize
+    @bio = "hello"
+  end
+
+  def update
+    self.bio = 42
+  end
+
+  def summary
+    profile_bio = self.bio
+    profile_nickname = self.nickname
+  end
+end
+"#;
+
+    let (genv, lenv) = analyze(source);
+
+    let bio_vtx = lenv.get_var("profile_bio").unwrap();
+    assert_eq!(
+        genv.get_vertex(bio_vtx).unwrap().show(),
+        "(Integer | String)"
+    );
+    // Never assigned, so the reader returns nil
+    let nickname_vtx = lenv.get_var("profile_nickname").unwrap();
+    assert_eq!(genv.get_vertex(nickname_vtx).unwrap().show(), "nil");
+}
+
+#[test]
+fn test_private_attr_reader() {
+    let source = r#"
+class Session
+  private attr_reader :token
+
+  def initialize
+    @token = "abc"
+  end
+
+  def header
+    token
+  end
+end
+
+session = Session.new
+session.token
+"#;
+
+    let (genv, _lenv) = analyze_with_core(source);
+
+    // `token` is defined inside the class, but private outside of it
+    assert_eq!(genv.type_errors.len(), 1);
+    assert_eq!(genv.type_errors[0].method_name, "token");
+    assert_eq!(
+        genv.type_errors[0].kind,
+        TypeErrorKind::VisibilityViolation {
+            visibility: Visibility::Private
+        }
+    );
+}
@@ -3,6 +3,10 @@
 //! This module is responsible for:
 //! - Local variable read/write (x, x = value)
 //! - Instance variable read/write (@name, @name = value)
+//! - Logical assignment (x ||= value, @name &&= value)
+//! - Class variable read/write (@@count, @@count = value)
+//! - Global variable read/write ($stdout, $debug = value)
+//! - Constant read/write (NAME, NAME = value)
 //! - self node handling
 
 use crate::env::{GlobalEnv, LocalEnv};
@@ -23,25 +27,128 @@ pub fn install_local_var_write(
     var_vtx
 }
 
+/// Install local variable `||=` / `&&=`: x ||= value
+///
+/// The result is the union of the previous value and the assigned value.
+pub fn install_local_var_logical_write(
+    genv: &mut GlobalEnv,
+    lenv: &mut LocalEnv,
+    changes: &mut ChangeSet,
+    var_name: String,
+    value_vtx: VertexId,
+) -> VertexId {
+    let var_vtx = genv.new_vertex();
+    if let Some(prev_vtx) = lenv.get_var(&var_name) {
+        changes.add_edge(prev_vtx, var_vtx);
+    }
+    changes.add_edge(value_vtx, var_vtx);
+    lenv.new_var(var_name, var_vtx);
+    var_vtx
+}
+
 /// Install local variable read: x
 pub fn install_local_var_read(lenv: &LocalEnv, var_name: &str) -> Option<VertexId> {
     lenv.get_var(var_name)
 }
 
 /// Install instance variable write: @name = value
+///
+/// The value flows into the variable's vertex for the enclosing class or
+/// module (not tracked outside one); the write evaluates to the value.
 pub fn install_ivar_write(
     genv: &mut GlobalEnv,
     ivar_name: String,
     value_vtx: VertexId,
 ) -> VertexId {
-    genv.scope_manager
-        .set_instance_var_in_class(ivar_name, value_vtx);
+    genv.write_instance_var(&ivar_name, value_vtx);
     value_vtx
 }
 
+/// Install instance variable `||=` / `&&=`: @name ||= value
+///
+/// The variable's vertex already holds the previous value, so the result is
+/// the union of every value assigned to it. `@memo ||= value` is not a read
+/// that may see nil.
+pub fn install_ivar_logical_write(
+    genv: &mut GlobalEnv,
+    ivar_name: String,
+    value_vtx: VertexId,
+) -> VertexId {
+    genv.write_instance_var(&ivar_name, value_vtx)
+        .unwrap_or(value_vtx)
+}
+
 /// Install instance variable read: @name
-pub fn install_ivar_read(genv: &GlobalEnv, ivar_name: &str) -> Option<VertexId> {
-    genv.scope_manager.lookup_instance_var(ivar_name)
+pub fn install_ivar_read(genv: &mut GlobalEnv, ivar_name: &str) -> Option<VertexId> {
+    genv.read_instance_var(ivar_name)
+}
+
+/// Install class variable write: @@count = value
+///
+/// Returns None outside a class or module.
+pub fn install_cvar_write(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    cvar_name: &str,
+    value_vtx: VertexId,
+) -> Option<VertexId> {
+    let cvar_vtx = genv.class_var_vertex(cvar_name)?;
+    changes.add_edge(value_vtx, cvar_vtx);
+    Some(cvar_vtx)
+}
+
+/// Install class variable read: @@count
+///
+/// The class variable vertex collects every write in the class, so reads
+/// before the first write see its type too.
+pub fn install_cvar_read(genv: &mut GlobalEnv, cvar_name: &str) -> Option<VertexId> {
+    genv.class_var_vertex(cvar_name)
+}
+
+/// Install global variable write: $name = value
+pub fn install_global_var_write(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    var_name: &str,
+    value_vtx: VertexId,
+) -> VertexId {
+    let global_vtx = genv.global_var_vertex(var_name);
+    changes.add_edge(value_vtx, global_vtx);
+    global_vtx
+}
+
+/// Install global variable read: $name
+pub fn install_global_var_read(genv: &mut GlobalEnv, var_name: &str) -> VertexId {
+    genv.global_var_vertex(var_name)
+}
+
+/// Install constant write: NAME = value
+///
+/// The constant is qualified by the enclosing class or module (`Config::DEFAULTS`).
+pub fn install_constant_write(
+    genv: &mut GlobalEnv,
+    changes: &mut ChangeSet,
+    const_name: &str,
+    value_vtx: VertexId,
+) -> VertexId {
+    let qualified_name = match genv.scope_manager.current_namespace() {
+        Some(namespace) => format!("{}::{}", namespace, const_name),
+        None => const_name.to_string(),
+    };
+    let const_vtx = genv.constant_vertex(&qualified_name);
+    changes.add_edge(value_vtx, const_vtx);
+    const_vtx
+}
+
+/// Install constant read: NAME, Config::DEFAULTS
+///
+/// Looks in the enclosing class or module first, then at the top level.
+/// Only assigned constants are tracked; classes and modules are not.
+pub fn install_constant_read(genv: &GlobalEnv, const_name: &str) -> Option<VertexId> {
+    genv.scope_manager
+        .current_namespace()
+        .and_then(|namespace| genv.lookup_constant(&format!("{}::{}", namespace, const_name)))
+        .or_else(|| genv.lookup_constant(const_name))
 }
 
 /// Install self node
@@ -73,4 +180,36 @@ mod tests {
 
         assert_eq!(install_local_var_read(&lenv, "unknown"), None);
     }
+
+    #[test]
+    fn test_local_var_logical_write_keeps_previous_value() {
+        let mut genv = GlobalEnv::new();
+        let mut lenv = LocalEnv::new();
+        let mut changes = ChangeSet::new();
+
+        let first_src = genv.new_source(Type::integer());
+        install_local_var_write(
+            &mut genv,
+            &mut lenv,
+            &mut changes,
+            "x".to_string(),
+            first_src,
+        );
+
+        let second_src = genv.new_source(Type::string());
+        let x_vtx = install_local_var_logical_write(
+            &mut genv,
+            &mut lenv,
+            &mut changes,
+            "x".to_string(),
+            second_src,
+        );
+
+        genv.apply_changes(changes);
+
+        assert_eq!(lenv.get_var("x"), Some(x_vtx));
+        let shown = genv.get_vertex(x_vtx).unwrap().show();
+        assert!(shown.contains("Integer"));
+        assert!(shown.contains("String"));
+    }
 }
@@ -4,9 +4,16 @@ use std::fs;
 use std::path::PathBuf;
 use std::time::SystemTime;
 
+use crate::env::method_registry::Visibility;
+
 #[cfg(feature = "ruby-ffi")]
 use crate::rbs::loader::RbsMethodInfo;
 
+/// Version of the cache contents, bumped whenever a field is added or the
+/// loaded classes change. bincode has no field defaults, so caches written
+/// by an older build are rebuilt rather than read
+const FORMAT_VERSION: u32 = 3;
+
 /// Binary cache for RBS method definitions
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RbsCache {
@@ -16,6 +23,8 @@ pub struct RbsCache {
     pub rbs_version: String,
     /// Cached method information
     pub methods: Vec<SerializableMethodInfo>,
+    /// Names of the classes, modules and constants declared in RBS
+    pub constants: Vec<String>,
     /// Cache creation timestamp
     pub timestamp: SystemTime,
 }
@@ -26,21 +35,24 @@ pub struct SerializableMethodInfo {
     pub receiver_class: String,
     pub method_name: String,
     pub return_type_str: String, // Simplified: store as string
-    #[serde(default)]
     pub block_param_types: Option<Vec<String>>,
+    pub visibility: Visibility,
 }
 
 impl SerializableMethodInfo {
-    /// Parse return type string into Type (simple parser for cached data)
+    /// Parse return type string into Type
     pub fn return_type(&self) -> crate::types::Type {
-        crate::types::Type::Instance {
-            class_name: self.return_type_str.clone(),
-        }
+        crate::rbs::converter::RbsTypeConverter::parse(&self.return_type_str)
     }
 }
 
 #[allow(dead_code)]
 impl RbsCache {
+    /// Version recorded by this build: the MethodRay version and the layout
+    pub fn current_version() -> String {
+        format!("{}+format.{}", env!("CARGO_PKG_VERSION"), FORMAT_VERSION)
+    }
+
     /// Get user cache file path (in ~/.cache/methodray/)
     pub fn cache_path() -> Result<PathBuf> {
         let cache_dir = dirs::cache_dir()
@@ -115,6 +127,11 @@ impl RbsCache {
         &self.methods
     }
 
+    /// Get the names of the classes, modules and constants declared in RBS
+    pub fn constants(&self) -> &[String] {
+        &self.constants
+    }
+
     /// Convert to RbsMethodInfo (requires ruby-ffi for full type parsing)
     #[cfg(feature = "ruby-ffi")]
     pub fn to_method_infos(&self) -> Vec<RbsMethodInfo> {
@@ -125,6 +142,7 @@ impl RbsCache {
                 method_name: m.method_name.clone(),
                 return_type: crate::rbs::converter::RbsTypeConverter::parse(&m.return_type_str),
                 block_param_types: m.block_param_types.clone(),
+                visibility: m.visibility,
             })
             .collect()
     }
@@ -133,6 +151,7 @@ impl RbsCache {
     #[cfg(feature = "ruby-ffi")]
     pub fn from_method_infos(
         methods: Vec<RbsMethodInfo>,
+        constants: Vec<String>,
         version: String,
         rbs_version: String,
     ) -> Self {
@@ -143,6 +162,7 @@ impl RbsCache {
                 method_name: m.method_name,
                 return_type_str: m.return_type.show(),
                 block_param_types: m.block_param_types,
+                visibility: m.visibility,
             })
             .collect();
 
@@ -150,6 +170,7 @@ impl RbsCache {
             version,
             rbs_version,
             methods: serializable_methods,
+            constants,
             timestamp: SystemTime::now(),
         }
     }
@@ -170,7 +191,9 @@ mod tests {
                 method_name: "upcase".to_string(),
                 return_type_str: "String".to_string(),
                 block_param_types: None,
+                visibility: Visibility::Public,
             }],
+            constants: vec!["String".to_string(), "File::SEPARATOR".to_string()],
             timestamp: SystemTime::now(),
         };
 
@@ -179,6 +202,7 @@ mod tests {
 
         assert_eq!(deserialized.version, "0.1.0");
         assert_eq!(deserialized.methods.len(), 1);
+        assert_eq!(deserialized.constants(), ["String", "File::SEPARATOR"]);
     }
 
     #[test]
@@ -187,6 +211,7 @@ mod tests {
             version: "0.1.0".to_string(),
             rbs_version: "3.7.0".to_string(),
             methods: vec![],
+            constants: vec![],
             timestamp: SystemTime::now(),
         };
 
@@ -195,6 +220,13 @@ mod tests {
         assert!(!cache.is_valid("0.1.0", "3.8.0"));
     }
 
+    #[test]
+    fn test_current_version_includes_format() {
+        let version = RbsCache::current_version();
+        assert!(version.starts_with(env!("CARGO_PKG_VERSION")));
+        assert_ne!(version, env!("CARGO_PKG_VERSION"));
+    }
+
     #[test]
     fn test_serializable_method_info_return_type() {
         let method_info = SerializableMethodInfo {
@@ -202,6 +234,7 @@ mod tests {
             method_name: "upcase".to_string(),
             return_type_str: "String".to_string(),
             block_param_types: None,
+            visibility: Visibility::Public,
         };
 
         let return_type = method_info.return_type();
@@ -219,14 +252,17 @@ mod tests {
                     method_name: "upcase".to_string(),
                     return_type_str: "String".to_string(),
                     block_param_types: None,
+                    visibility: Visibility::Public,
                 },
                 SerializableMethodInfo {
                     receiver_class: "Integer".to_string(),
                     method_name: "to_s".to_string(),
                     return_type_str: "String".to_string(),
                     block_param_types: None,
+                    visibility: Visibility::Public,
                 },
             ],
+            constants: vec![],
             timestamp: SystemTime::now(),
         };
 
@@ -252,14 +288,17 @@ mod tests {
                     method_name: "upcase".to_string(),
                     return_type_str: "String".to_string(),
                     block_param_types: None,
+                    visibility: Visibility::Public,
                 },
                 SerializableMethodInfo {
                     receiver_class: "Array".to_string(),
                     method_name: "first".to_string(),
                     return_type_str: "Object".to_string(),
                     block_param_types: None,
+                    visibility: Visibility::Public,
                 },
             ],
+            constants: vec![],
             timestamp: SystemTime::now(),
         };
 
@@ -284,6 +323,7 @@ mod tests {
             version: "0.1.0".to_string(),
             rbs_version: "3.7.0".to_string(),
             methods: vec![],
+            constants: vec![],
             timestamp: SystemTime::now(),
         };
 
@@ -300,6 +340,7 @@ mod tests {
             version: "0.1.0".to_string(),
             rbs_version: "3.7.0".to_string(),
             methods: vec![],
+            constants: vec![],
             timestamp: SystemTime::now(),
         };
 
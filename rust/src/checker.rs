@@ -1,27 +1,77 @@
-use crate::analyzer::AstInstaller;
-use crate::diagnostics::Diagnostic;
+use crate::analyzer::{collect_requires, AstInstaller, RequireCall};
+use crate::config::{Config, Severity};
+use crate::diagnostics::{did_you_mean, Diagnostic, DiagnosticLevel, Location};
+use crate::env::method_registry::Visibility;
+use crate::env::project_index::ProjectIndex;
+use crate::env::type_error::{TypeError, TypeErrorKind};
 use crate::env::{GlobalEnv, LocalEnv};
-use crate::parser;
+use crate::parser::{self, SuppressionComment, SuppressionKind};
+use crate::rbs::signature::Signatures;
 use anyhow::{Context, Result};
-use std::path::Path;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 
 /// File type checker
+///
+/// Creates a fresh GlobalEnv for each check. When checking a project, the
+/// declarations of every project file are loaded into it first; otherwise
+/// those of the files the checked file requires.
 pub struct FileChecker {
-    // No state - creates fresh GlobalEnv for each check
+    /// Declarations of the project being checked
+    index: Option<ProjectIndex>,
+    /// Some project file requires a gem or standard library
+    requires_libraries: bool,
+    config: Config,
+    /// Declarations of the project signature files
+    signatures: Signatures,
 }
 
 impl FileChecker {
     /// Create new FileChecker
     /// Note: This is for standalone CLI usage (no Ruby runtime)
     pub fn new() -> Result<Self> {
-        // Just verify cache exists
-        use crate::cache::RbsCache;
-        RbsCache::load().context(
-            "Failed to load RBS cache. Please run from Ruby first to generate cache:\n\
-             ruby -rmethodray -e 'MethodRay::Analyzer.new(\".\").infer_types(\"x=1\")'",
-        )?;
+        // Just verify cache exists and was written by this build
+        load_rbs_cache()?;
+
+        Ok(Self {
+            index: None,
+            requires_libraries: false,
+            config: Config::default(),
+            signatures: Signatures::default(),
+        })
+    }
 
-        Ok(Self {})
+    /// Check with a project configuration, reading its signature files
+    pub fn with_config(mut self, config: Config) -> Result<Self> {
+        self.signatures = Signatures::load_dirs(&config.signature_dirs)?;
+        self.config = config;
+        Ok(self)
+    }
+
+    /// Collect the declarations of every project file (first phase of a
+    /// project check)
+    ///
+    /// Files that cannot be read or parsed are skipped here; checking them
+    /// reports the failure.
+    pub fn index_project(&mut self, files: &[PathBuf]) -> Result<()> {
+        let mut genv = self.new_genv()?;
+
+        let load_paths = &self.config.load_paths;
+        let mut requires_libraries = false;
+        for file_path in files {
+            if let Ok(requires) = install_file(&mut genv, file_path, false) {
+                requires_libraries |= requires
+                    .iter()
+                    .any(|require| !require.is_project_file(load_paths));
+            }
+        }
+        genv.finish_instance_vars();
+        genv.run_all();
+
+        self.index = Some(genv.project_index());
+        self.requires_libraries = requires_libraries;
+        Ok(())
     }
 
     /// Check a single Ruby file
@@ -30,45 +80,362 @@ impl FileChecker {
         let source = std::fs::read_to_string(file_path)
             .with_context(|| format!("Failed to read {}", file_path.display()))?;
 
-        // Parse file
-        let parse_result = parser::parse_ruby_file(file_path)
-            .with_context(|| format!("Failed to parse {}", file_path.display()))?;
+        self.check_source(file_path, &source)
+    }
+
+    /// Check the source code of a Ruby file (which may differ from the file
+    /// on disk, e.g. an unsaved editor buffer)
+    pub fn check_source(&self, file_path: &Path, source: &str) -> Result<Vec<Diagnostic>> {
+        let parse_result =
+            match parser::parse_ruby_source(source, file_path.to_string_lossy().to_string()) {
+                Ok(parse_result) => parse_result,
+                // Syntax errors are reported like the other diagnostics
+                Err(error) => {
+                    let diagnostics: Vec<Diagnostic> = parser::syntax_errors(source)
+                        .iter()
+                        .map(|syntax_error| to_syntax_error(syntax_error, file_path))
+                        .collect();
+                    return if diagnostics.is_empty() {
+                        Err(error)
+                    } else {
+                        Ok(diagnostics)
+                    };
+                }
+            };
+        let requires = collect_requires(&parse_result.node(), source);
 
         // Create fresh GlobalEnv for this analysis
+        let mut genv = self.new_genv()?;
+        match &self.index {
+            Some(index) => genv.load_project_index(index),
+            None => {
+                if let Some(index) = self.index_required_files(file_path, &requires)? {
+                    genv.load_project_index(&index);
+                }
+            }
+        }
+        if self.knows_top_level_constants(&requires) {
+            genv.mark_top_level_constants_known();
+        }
+
+        install_source(&mut genv, &parse_result.node(), source, true);
+
+        // Collect diagnostics
+        // Gems and standard libraries are not in the load paths: only missing
+        // project files are reported
+        let mut diagnostics = collect_diagnostics(&genv, file_path, &self.config.ignored_receivers);
+        let load_paths = &self.config.load_paths;
+        diagnostics.extend(
+            requires
+                .iter()
+                .filter(|require| {
+                    require.is_project_file(load_paths)
+                        && require.resolve(file_path, load_paths).is_none()
+                })
+                .map(|require| unresolved_require(require, file_path)),
+        );
+
+        let comments = parser::suppression_comments(&parse_result, source);
+        let diagnostics = apply_suppressions(diagnostics, &comments, file_path);
+        Ok(apply_severity(diagnostics, &self.config))
+    }
+
+    /// Check if every top-level constant the file can see is defined in the
+    /// project or the core RBS: the whole project is indexed, and neither the
+    /// file nor the project loads gems (a Gemfile) or standard libraries
+    fn knows_top_level_constants(&self, requires: &[RequireCall]) -> bool {
+        let load_paths = &self.config.load_paths;
+        self.index.is_some()
+            && !self.requires_libraries
+            && !self.config.root.join("Gemfile").exists()
+            && requires
+                .iter()
+                .all(|require| require.is_project_file(load_paths))
+    }
+
+    /// GlobalEnv with the RBS declarations of the core classes and the
+    /// project signatures
+    fn new_genv(&self) -> Result<GlobalEnv> {
         let mut genv = GlobalEnv::new();
         load_rbs_from_cache(&mut genv)?;
+        load_signatures(&mut genv, &self.signatures);
+        Ok(genv)
+    }
 
-        let mut lenv = LocalEnv::new();
-        let mut installer = AstInstaller::new(&mut genv, &mut lenv, &source);
+    /// Collect the declarations of the files a file requires, transitively
+    ///
+    /// Returns None when it requires no project file.
+    fn index_required_files(
+        &self,
+        file_path: &Path,
+        requires: &[RequireCall],
+    ) -> Result<Option<ProjectIndex>> {
+        let mut pending = self.resolve_requires(file_path, requires);
+        if pending.is_empty() {
+            return Ok(None);
+        }
+
+        let mut genv = self.new_genv()?;
 
-        // Process AST
-        let root = parse_result.node();
-        if let Some(program_node) = root.as_program_node() {
-            let statements = program_node.statements();
-            for stmt in &statements.body() {
-                installer.install_node(&stmt);
+        let mut visited: HashSet<PathBuf> = HashSet::new();
+        visited.insert(canonical_path(file_path));
+        while let Some(required_path) = pending.pop() {
+            if !visited.insert(canonical_path(&required_path)) {
+                continue;
+            }
+            if let Ok(requires) = install_file(&mut genv, &required_path, false) {
+                pending.extend(self.resolve_requires(&required_path, &requires));
             }
         }
+        genv.finish_instance_vars();
+        genv.run_all();
 
-        installer.finish();
+        Ok(Some(genv.project_index()))
+    }
 
-        // Collect diagnostics
-        let diagnostics = collect_diagnostics(&genv, file_path);
+    /// Files of the requires that resolve
+    fn resolve_requires(&self, file_path: &Path, requires: &[RequireCall]) -> Vec<PathBuf> {
+        requires
+            .iter()
+            .filter_map(|require| require.resolve(file_path, &self.config.load_paths))
+            .collect()
+    }
+}
+
+/// Path used to recognize a file required under different names
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
 
-        Ok(diagnostics)
+/// Warning for a require whose file cannot be found
+fn unresolved_require(require: &RequireCall, file_path: &Path) -> Diagnostic {
+    let location = Location {
+        file: file_path.to_path_buf(),
+        line: require.location.line,
+        column: require.location.column,
+        length: Some(require.location.length),
+    };
+    Diagnostic::unresolved_require(location, require.kind.as_str(), &require.path)
+}
+
+/// Lines a suppression comment silences
+struct Suppression<'a> {
+    comment: &'a SuppressionComment,
+    lines: RangeInclusive<usize>,
+    used: bool,
+}
+
+impl Suppression<'_> {
+    fn covers(&self, diagnostic: &Diagnostic) -> bool {
+        self.lines.contains(&diagnostic.location.line)
+            && (self.comment.codes.is_empty()
+                || diagnostic
+                    .code
+                    .as_ref()
+                    .is_some_and(|code| self.comment.codes.contains(code)))
     }
 }
 
-/// Load RBS methods from cache (CLI mode without Ruby runtime)
-fn load_rbs_from_cache(genv: &mut GlobalEnv) -> Result<()> {
+/// Drop the diagnostics silenced by suppression comments, and warn about
+/// the comments that silence nothing
+fn apply_suppressions(
+    diagnostics: Vec<Diagnostic>,
+    comments: &[SuppressionComment],
+    file_path: &Path,
+) -> Vec<Diagnostic> {
+    let mut suppressions = suppressions(comments);
+
+    let mut reported: Vec<Diagnostic> = diagnostics
+        .into_iter()
+        .filter(|diagnostic| {
+            let mut suppressed = false;
+            for suppression in suppressions.iter_mut() {
+                if suppression.covers(diagnostic) {
+                    suppression.used = true;
+                    suppressed = true;
+                }
+            }
+            !suppressed
+        })
+        .collect();
+
+    reported.extend(
+        suppressions
+            .iter()
+            .filter(|suppression| !suppression.used)
+            .map(|suppression| unused_suppression(suppression.comment, file_path)),
+    );
+    reported
+}
+
+/// Lines silenced by each `disable` comment
+///
+/// `# methodray:disable` lasts until an `enable` with the same codes (or
+/// any `enable` without codes), or to the end of the file.
+fn suppressions(comments: &[SuppressionComment]) -> Vec<Suppression<'_>> {
+    let mut suppressions: Vec<Suppression> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+
+    for comment in comments {
+        let line = comment.location.line;
+        let lines = match comment.kind {
+            SuppressionKind::DisableLine => line..=line,
+            SuppressionKind::DisableNextLine => line + 1..=line + 1,
+            SuppressionKind::Disable => {
+                open.push(suppressions.len());
+                line..=usize::MAX
+            }
+            SuppressionKind::Enable => {
+                open.retain(|&index| {
+                    let suppression = &mut suppressions[index];
+                    let closes =
+                        comment.codes.is_empty() || suppression.comment.codes == comment.codes;
+                    if closes {
+                        suppression.lines = *suppression.lines.start()..=line;
+                    }
+                    !closes
+                });
+                continue;
+            }
+        };
+        suppressions.push(Suppression {
+            comment,
+            lines,
+            used: false,
+        });
+    }
+
+    suppressions
+}
+
+/// Warning for a suppression comment that silences nothing
+fn unused_suppression(comment: &SuppressionComment, file_path: &Path) -> Diagnostic {
+    let location = Location {
+        file: file_path.to_path_buf(),
+        line: comment.location.line,
+        column: comment.location.column,
+        length: Some(comment.location.length),
+    };
+    let mut directive = format!("methodray:{}", comment.kind.as_str());
+    if !comment.codes.is_empty() {
+        directive.push(' ');
+        directive.push_str(&comment.codes.join(", "));
+    }
+    Diagnostic::unused_suppression(location, &directive)
+}
+
+/// Apply the configured severities: change the level of a rule's
+/// diagnostics, or drop them when it is off
+fn apply_severity(diagnostics: Vec<Diagnostic>, config: &Config) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            let severity = diagnostic
+                .code
+                .as_deref()
+                .and_then(|code| config.severity(code));
+            match severity {
+                Some(Severity::Off) => return None,
+                Some(Severity::Error) => diagnostic.level = DiagnosticLevel::Error,
+                Some(Severity::Warning) => diagnostic.level = DiagnosticLevel::Warning,
+                None => {}
+            }
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+/// Error for a syntax error (the file is not type checked)
+fn to_syntax_error(syntax_error: &parser::SyntaxError, file_path: &Path) -> Diagnostic {
+    let location = Location {
+        file: file_path.to_path_buf(),
+        line: syntax_error.location.line,
+        column: syntax_error.location.column,
+        length: Some(syntax_error.location.length),
+    };
+    Diagnostic::syntax_error(location, &syntax_error.message)
+}
+
+/// Parse a Ruby file and install it into the environment
+///
+/// Returns the file's requires. With `run_boxes`, the installation is
+/// finished by executing the Boxes; otherwise they wait for the files
+/// installed after this one.
+fn install_file(
+    genv: &mut GlobalEnv,
+    file_path: &Path,
+    run_boxes: bool,
+) -> Result<Vec<RequireCall>> {
+    // Read source code
+    let source = std::fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read {}", file_path.display()))?;
+
+    // Parse file
+    let parse_result = parser::parse_ruby_file(file_path)
+        .with_context(|| format!("Failed to parse {}", file_path.display()))?;
+
+    install_source(genv, &parse_result.node(), &source, run_boxes);
+    Ok(collect_requires(&parse_result.node(), &source))
+}
+
+/// Install a parsed program into the environment
+fn install_source(genv: &mut GlobalEnv, root: &ruby_prism::Node, source: &str, run_boxes: bool) {
+    let mut lenv = LocalEnv::new();
+    let mut installer = AstInstaller::new(genv, &mut lenv, source);
+
+    // Process AST
+    if let Some(program_node) = root.as_program_node() {
+        let statements = program_node.statements();
+        for stmt in &statements.body() {
+            installer.install_node(&stmt);
+        }
+    }
+
+    if run_boxes {
+        installer.finish();
+    } else {
+        installer.finish_declarations();
+    }
+}
+
+/// Load the RBS cache, which must have been written by this build
+fn load_rbs_cache() -> Result<crate::cache::RbsCache> {
     use crate::cache::RbsCache;
-    use crate::rbs::converter::RbsTypeConverter;
-    use crate::types::Type;
 
     let cache = RbsCache::load().context(
         "Failed to load RBS cache. Please run from Ruby first to generate cache:\n\
          ruby -rmethodray -e 'MethodRay::Analyzer.new(\".\").infer_types(\"x=1\")'",
     )?;
+    check_cache_version(&cache)?;
+    Ok(cache)
+}
+
+/// Reject a cache written by another MethodRay version or cache format
+fn check_cache_version(cache: &crate::cache::RbsCache) -> Result<()> {
+    use crate::cache::RbsCache;
+
+    let current_version = RbsCache::current_version();
+    // The RBS gem version is unknown without a Ruby runtime
+    if cache.version != current_version {
+        anyhow::bail!(
+            "RBS cache is stale (written by {}, this is {}): run `methodray clear-cache` and regenerate it",
+            cache.version,
+            current_version
+        );
+    }
+    Ok(())
+}
+
+/// Load RBS methods from cache (CLI mode without Ruby runtime)
+fn load_rbs_from_cache(genv: &mut GlobalEnv) -> Result<()> {
+    use crate::rbs::converter::RbsTypeConverter;
+    use crate::types::Type;
+
+    let cache = load_rbs_cache()?;
+
+    for constant in cache.constants() {
+        genv.register_builtin_constant(constant);
+    }
 
     let methods = cache.methods();
 
@@ -86,33 +453,66 @@ fn load_rbs_from_cache(genv: &mut GlobalEnv) -> Result<()> {
         });
 
         genv.register_builtin_method_with_block(
-            receiver_type,
+            receiver_type.clone(),
             &method_info.method_name,
             method_info.return_type(),
             block_param_types,
         );
+        if method_info.visibility != Visibility::Public {
+            genv.set_method_visibility(
+                &receiver_type,
+                &method_info.method_name,
+                method_info.visibility,
+            );
+        }
     }
 
     Ok(())
 }
 
+/// Register the classes, constants and methods of the project signatures
+fn load_signatures(genv: &mut GlobalEnv, signatures: &Signatures) {
+    for constant in &signatures.constants {
+        genv.register_builtin_constant(constant);
+    }
+    for method in &signatures.methods {
+        genv.register_builtin_method(
+            method.receiver.clone(),
+            &method.method_name,
+            method.return_type.clone(),
+        );
+    }
+}
+
 /// Collect type error diagnostics from GlobalEnv
-fn collect_diagnostics(genv: &GlobalEnv, file_path: &Path) -> Vec<Diagnostic> {
+///
+/// Errors on the ignored receiver classes are not reported.
+fn collect_diagnostics(
+    genv: &GlobalEnv,
+    file_path: &Path,
+    ignored_receivers: &[String],
+) -> Vec<Diagnostic> {
     use crate::diagnostics::{Diagnostic, Location};
     use std::path::PathBuf;
 
+    let to_location = |source_loc: &crate::source_map::SourceLocation| Location {
+        file: PathBuf::from(file_path),
+        line: source_loc.line,
+        column: source_loc.column,
+        length: Some(source_loc.length),
+    };
+
     let mut diagnostics = Vec::new();
 
     // Convert TypeErrors to Diagnostics
     for type_error in &genv.type_errors {
+        if is_ignored_receiver(type_error, ignored_receivers) {
+            continue;
+        }
+
         // Use actual location from TypeError if available
         let location = if let Some(source_loc) = &type_error.location {
-            Location {
-                file: PathBuf::from(file_path),
-                line: source_loc.line,
-                column: source_loc.column,
-                length: Some(source_loc.length),
-            }
+            to_location(source_loc)
         } else {
             // Fallback to placeholder
             Location {
@@ -123,21 +523,105 @@ fn collect_diagnostics(genv: &GlobalEnv, file_path: &Path) -> Vec<Diagnostic> {
             }
         };
 
-        let diagnostic = Diagnostic::undefined_method(
-            location,
-            &type_error.receiver_type.show(),
-            &type_error.method_name,
-        );
+        let diagnostic = match &type_error.kind {
+            TypeErrorKind::UndefinedMethod => Diagnostic::undefined_method(
+                location,
+                &type_error.receiver_type.show(),
+                &type_error.method_name,
+            ),
+            TypeErrorKind::NilReceiver { nil_origin } => Diagnostic::nil_receiver(
+                location,
+                &type_error.method_name,
+                nil_origin.as_ref().map(to_location),
+            ),
+            TypeErrorKind::UnknownRecordKey { key } => {
+                Diagnostic::unknown_record_key(location, &type_error.receiver_type.show(), key)
+            }
+            TypeErrorKind::NoSuperMethod => Diagnostic::no_super_method(
+                location,
+                &type_error.receiver_type.show(),
+                &type_error.method_name,
+            ),
+            TypeErrorKind::VisibilityViolation { visibility } => Diagnostic::visibility_violation(
+                location,
+                visibility.as_str(),
+                &type_error.receiver_type.show(),
+                &type_error.method_name,
+            ),
+            TypeErrorKind::UninitializedConstant => {
+                Diagnostic::uninitialized_constant(location, &type_error.method_name)
+            }
+            TypeErrorKind::UndefinedLocal => Diagnostic::undefined_local(
+                location,
+                &type_error.receiver_type.show(),
+                &type_error.method_name,
+            ),
+            TypeErrorKind::WrongArity { given, max } => Diagnostic::wrong_arity(
+                location,
+                &type_error.receiver_type.show(),
+                &type_error.method_name,
+                *given,
+                *max,
+            ),
+        };
 
-        diagnostics.push(diagnostic);
+        diagnostics.push(diagnostic.with_suggestions(suggestions(genv, type_error)));
     }
 
     diagnostics
 }
 
+/// Check if an error is about a receiver of one of the ignored classes (or a
+/// union including one)
+///
+/// Undefined locals, constants and super methods are reported whatever
+/// class self is.
+fn is_ignored_receiver(type_error: &TypeError, ignored_receivers: &[String]) -> bool {
+    let receiver_error = matches!(
+        type_error.kind,
+        TypeErrorKind::UndefinedMethod
+            | TypeErrorKind::NilReceiver { .. }
+            | TypeErrorKind::UnknownRecordKey { .. }
+            | TypeErrorKind::VisibilityViolation { .. }
+            | TypeErrorKind::WrongArity { .. }
+    );
+    receiver_error
+        && type_error
+            .receiver_type
+            .union_members()
+            .iter()
+            .filter_map(|member| member.base_class_name())
+            .any(|class_name| ignored_receivers.iter().any(|name| name == class_name))
+}
+
+/// "Did you mean?" suggestions for a misspelled method, identifier or constant
+fn suggestions(genv: &GlobalEnv, type_error: &TypeError) -> Vec<String> {
+    match type_error.kind {
+        TypeErrorKind::UndefinedMethod | TypeErrorKind::UndefinedLocal => {
+            let names = genv.method_names(&type_error.receiver_type);
+            did_you_mean::suggest(&type_error.method_name, names.iter().map(String::as_str))
+        }
+        TypeErrorKind::UninitializedConstant => match type_error.method_name.rsplit_once("::") {
+            Some((namespace, name)) => {
+                let names = genv.constant_names_in(Some(namespace));
+                did_you_mean::suggest(name, names.iter().map(String::as_str))
+                    .into_iter()
+                    .map(|name| format!("{}::{}", namespace, name))
+                    .collect()
+            }
+            None => {
+                let names = genv.constant_names_in(None);
+                did_you_mean::suggest(&type_error.method_name, names.iter().map(String::as_str))
+            }
+        },
+        _ => Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::Type;
 
     #[test]
     fn test_file_checker_creation() {
@@ -146,4 +630,195 @@ mod tests {
         let result = FileChecker::new();
         assert!(result.is_ok() || result.is_err()); // Just check it doesn't panic
     }
+
+    #[test]
+    fn test_stale_cache_is_rejected() {
+        use crate::cache::RbsCache;
+
+        let mut cache = RbsCache {
+            version: RbsCache::current_version(),
+            rbs_version: "3.7.0".to_string(),
+            methods: vec![],
+            constants: vec![],
+            timestamp: std::time::SystemTime::now(),
+        };
+        assert!(check_cache_version(&cache).is_ok());
+
+        cache.version = "0.0.1".to_string();
+        let error = check_cache_version(&cache).unwrap_err().to_string();
+        assert!(error.contains("methodray clear-cache"));
+    }
+
+    #[test]
+    fn test_syntax_errors_are_diagnostics() {
+        let checker = FileChecker {
+            index: None,
+            requires_libraries: false,
+            config: Config::default(),
+            signatures: Signatures::default(),
+        };
+
+        let diagnostics = checker
+            .check_source(Path::new("broken.rb"), "x = 1\ndef greeting\n")
+            .unwrap();
+
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.code.as_deref() == Some("MR010")));
+    }
+
+    #[test]
+    fn test_apply_suppressions() {
+        let source = r#"user.nmae # methodray:disable-line MR001
+# methodray:disable-next-line
+user.emial
+user.nmae # methodray:disable-line MR002
+# methodray:disable MR001
+user.nmae
+user.emial
+# methodray:enable MR001
+user.phnoe
+x = 1 # methodray:disable-line
+"#;
+        let parse_result = parser::parse_ruby_source(source, "test.rb".to_string()).unwrap();
+        let comments = parser::suppression_comments(&parse_result, source);
+        let file_path = Path::new("test.rb");
+        let undefined_method = |line, method_name| {
+            let location = Location {
+                file: file_path.to_path_buf(),
+                line,
+                column: 6,
+                length: Some(5),
+            };
+            Diagnostic::undefined_method(location, "User", method_name)
+        };
+        let diagnostics = vec![
+            undefined_method(1, "nmae"),
+            undefined_method(3, "emial"),
+            undefined_method(4, "nmae"),
+            undefined_method(6, "nmae"),
+            undefined_method(7, "emial"),
+            undefined_method(9, "phnoe"),
+        ];
+
+        let reported = apply_suppressions(diagnostics, &comments, file_path);
+
+        let reported: Vec<(usize, &str, &str)> = reported
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.location.line,
+                    diagnostic.code.as_deref().unwrap(),
+                    diagnostic.message.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (4, "MR001", "undefined method `nmae` for User"),
+                (9, "MR001", "undefined method `phnoe` for User"),
+                (
+                    4,
+                    "MR011",
+                    "unused suppression comment `methodray:disable-line MR002`"
+                ),
+                (
+                    10,
+                    "MR011",
+                    "unused suppression comment `methodray:disable-line`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_severity() {
+        let location = |line| Location {
+            file: PathBuf::from("test.rb"),
+            line,
+            column: 1,
+            length: None,
+        };
+        let diagnostics = vec![
+            Diagnostic::undefined_method(location(1), "String", "lenght"),
+            Diagnostic::unknown_record_key(location(2), "{ timeout: Integer }", "retries"),
+            Diagnostic::uninitialized_constant(location(3), "Confg"),
+        ];
+        let mut config = Config::default();
+        config.set_severity("MR001", Severity::Warning).unwrap();
+        config.set_severity("MR003", Severity::Error).unwrap();
+        config.set_severity("MR007", Severity::Off).unwrap();
+
+        let diagnostics = apply_severity(diagnostics, &config);
+
+        let levels: Vec<(usize, DiagnosticLevel)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.location.line, diagnostic.level))
+            .collect();
+        assert_eq!(
+            levels,
+            vec![(1, DiagnosticLevel::Warning), (2, DiagnosticLevel::Error)]
+        );
+    }
+
+    #[test]
+    fn test_ignored_receivers() {
+        let relation = Type::Instance {
+            class_name: "ActiveRecord::Relation".to_string(),
+        };
+        let mut genv = GlobalEnv::new();
+        genv.type_errors
+            .push(TypeError::new(relation.clone(), "wherre".to_string(), None));
+        genv.type_errors
+            .push(TypeError::new(Type::string(), "lenght".to_string(), None));
+        genv.type_errors.push(TypeError::new(
+            Type::Union(vec![relation.clone(), Type::integer()]),
+            "wherre".to_string(),
+            None,
+        ));
+        // Reported whatever class self is
+        genv.type_errors.push(TypeError::undefined_local(
+            relation,
+            "scopee".to_string(),
+            None,
+        ));
+
+        let ignored = vec!["ActiveRecord::Relation".to_string()];
+        let diagnostics = collect_diagnostics(&genv, Path::new("test.rb"), &ignored);
+
+        let messages: Vec<&str> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "undefined method `lenght` for String",
+                "undefined local variable or method `scopee` for ActiveRecord::Relation",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let mut genv = GlobalEnv::new();
+        genv.register_builtin_method(Type::string(), "length", Type::integer());
+        genv.register_builtin_method(Type::string(), "upcase", Type::string());
+        genv.register_builtin_constant("String");
+        genv.register_builtin_constant("File::SEPARATOR");
+
+        let undefined_method = TypeError::new(Type::string(), "lenght".to_string(), None);
+        assert_eq!(suggestions(&genv, &undefined_method), vec!["length"]);
+
+        let constant = TypeError::uninitialized_constant("Strng".to_string(), None);
+        assert_eq!(suggestions(&genv, &constant), vec!["String"]);
+
+        let constant = TypeError::uninitialized_constant("File::SEPARATR".to_string(), None);
+        assert_eq!(suggestions(&genv, &constant), vec!["File::SEPARATOR"]);
+
+        let nil_receiver = TypeError::nil_receiver("lenght".to_string(), None, None);
+        assert!(suggestions(&genv, &nil_receiver).is_empty());
+    }
 }
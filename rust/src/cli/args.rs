@@ -23,6 +23,27 @@ pub enum Commands {
         /// Show detailed output
         #[arg(short, long)]
         verbose: bool,
+
+        /// Additional directory searched by `require` (lib is always searched)
+        #[arg(short = 'I', long = "load-path", value_name = "DIR")]
+        load_paths: Vec<PathBuf>,
+
+        /// Configuration file (default: .methodray.yml in the current directory or a parent)
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// Override the severity of a rule: error, warning or off (e.g. MR003=off)
+        #[arg(long = "severity", value_name = "CODE=LEVEL")]
+        severities: Vec<String>,
+
+        /// Record the current diagnostics in the baseline file instead of reporting them
+        #[arg(long)]
+        generate_baseline: bool,
+
+        /// Baseline file; diagnostics recorded in it are not reported
+        /// (default: .methodray_baseline.json in the project root)
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
     },
 
     /// Watch a Ruby file and re-check on changes
@@ -30,6 +51,17 @@ pub enum Commands {
         /// Ruby file to watch
         #[arg(value_name = "FILE")]
         file: PathBuf,
+
+        /// Configuration file (default: .methodray.yml in the current directory or a parent)
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+    },
+
+    /// Explain a diagnostic code (lists every code if none is given)
+    Explain {
+        /// Diagnostic code, e.g. MR001
+        #[arg(value_name = "CODE")]
+        code: Option<String>,
     },
 
     /// Show version information
@@ -1,44 +1,252 @@
 //! CLI command implementations
 
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::cache::RbsCache;
 use crate::checker::FileChecker;
+use crate::config::Config;
 use crate::diagnostics;
+use crate::diagnostics::baseline::{Baseline, BaselineEntry, DEFAULT_BASELINE_PATH};
+use crate::diagnostics::catalog;
+use crate::diagnostics::Diagnostic;
+
+/// Load the configuration file (given, or found from the current directory
+/// upwards) and apply the command-line overrides
+///
+/// `load_paths` are searched after the configured ones; `severities` are
+/// `CODE=LEVEL` settings that take precedence over the file.
+pub fn load_config(
+    config_path: Option<&Path>,
+    load_paths: &[PathBuf],
+    severities: &[String],
+) -> Result<Config> {
+    let mut config = match config_path {
+        Some(config_path) => Config::load(config_path)?,
+        None => Config::discover(Path::new("."))?,
+    };
+    config.load_paths.extend(load_paths.iter().cloned());
+    for setting in severities {
+        let (code, severity) = setting.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid --severity `{}` (expected CODE=LEVEL, e.g. MR003=off)",
+                setting
+            )
+        })?;
+        config.set_severity(code, severity.parse()?)?;
+    }
+    Ok(config)
+}
 
 /// Check a single Ruby file for type errors
+///
+/// The file is checked even if the configuration excludes it. Diagnostics
+/// recorded in the baseline file (if it exists) are not reported.
 /// Returns Ok(true) if no errors, Ok(false) if errors found
-pub fn check_single_file(file_path: &PathBuf, verbose: bool) -> Result<bool> {
-    let checker = FileChecker::new()?;
+pub fn check_single_file(
+    file_path: &Path,
+    verbose: bool,
+    config: &Config,
+    baseline_path: &Path,
+) -> Result<bool> {
+    let checker = FileChecker::new()?.with_config(config.clone())?;
+    let mut baseline = load_baseline(baseline_path)?;
+
     let diagnostics = checker.check_file(file_path)?;
+    let success = report_diagnostics(file_path, diagnostics, baseline.as_mut(), config, verbose);
+
+    if let Some(baseline) = &baseline {
+        report_fixed(baseline, &[file_path.to_path_buf()], config);
+    }
+    Ok(success)
+}
+
+/// Check all Ruby files in the project (those the configuration includes
+/// under its root)
+///
+/// The declarations of every file are collected first, so classes and
+/// methods defined in one file resolve calls in another.
+/// Returns Ok(true) if no errors, Ok(false) if errors found
+pub fn check_project(verbose: bool, config: &Config, baseline_path: &Path) -> Result<bool> {
+    let files = find_ruby_files(config);
+    if verbose {
+        if let Some(config_path) = &config.path {
+            println!("Using configuration {}", config_path.display());
+        }
+    }
+
+    let mut checker = FileChecker::new()?.with_config(config.clone())?;
+    checker.index_project(&files)?;
+    let mut baseline = load_baseline(baseline_path)?;
+
+    let mut success = true;
+    for file_path in &files {
+        let diagnostics = match checker.check_file(file_path) {
+            Ok(diagnostics) => diagnostics,
+            Err(e) => {
+                eprintln!("{:#}", e);
+                success = false;
+                continue;
+            }
+        };
+
+        if !report_diagnostics(file_path, diagnostics, baseline.as_mut(), config, verbose) {
+            success = false;
+        }
+    }
+
+    if let Some(baseline) = &baseline {
+        report_fixed(baseline, &files, config);
+    }
+    if verbose {
+        println!("Checked {} files", files.len());
+    }
+
+    Ok(success)
+}
+
+/// Record the diagnostics of a file (or of the whole project) in the
+/// baseline file
+///
+/// For a single file, the entries of the other files are kept.
+pub fn generate_baseline(file: Option<&Path>, config: &Config, baseline_path: &Path) -> Result<()> {
+    let mut checker = FileChecker::new()?.with_config(config.clone())?;
+    let (files, mut baseline) = match file {
+        Some(file_path) => {
+            let mut baseline = load_baseline(baseline_path)?.unwrap_or_default();
+            baseline.remove_file(&config.relative_path(file_path));
+            (vec![file_path.to_path_buf()], baseline)
+        }
+        None => {
+            let files = find_ruby_files(config);
+            checker.index_project(&files)?;
+            (files, Baseline::default())
+        }
+    };
+
+    for file_path in &files {
+        match checker.check_file(file_path) {
+            Ok(diagnostics) => {
+                let source = std::fs::read_to_string(file_path).unwrap_or_default();
+                baseline.add(&config.relative_path(file_path), &source, &diagnostics);
+            }
+            Err(e) => eprintln!("{:#}", e),
+        }
+    }
+
+    baseline.save(baseline_path)?;
+    println!(
+        "Baseline with {} diagnostics written to {}",
+        baseline.diagnostic_count(),
+        baseline_path.display()
+    );
+    Ok(())
+}
+
+/// Baseline file used unless another is given: the one in the project root
+pub fn default_baseline_path(config: &Config) -> PathBuf {
+    config.root.join(DEFAULT_BASELINE_PATH)
+}
+
+/// Load the baseline file, if there is one
+fn load_baseline(baseline_path: &Path) -> Result<Option<Baseline>> {
+    if baseline_path.exists() {
+        Baseline::load(baseline_path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Print the diagnostics of a file that are not in the baseline
+///
+/// Baselined files are named relative to the project root. Returns true if
+/// none of the diagnostics is an error.
+fn report_diagnostics(
+    file_path: &Path,
+    diagnostics: Vec<Diagnostic>,
+    baseline: Option<&mut Baseline>,
+    config: &Config,
+    verbose: bool,
+) -> bool {
+    let source = std::fs::read_to_string(file_path).unwrap_or_default();
+    let diagnostics = match baseline {
+        Some(baseline) => {
+            baseline.filter_new(&config.relative_path(file_path), &source, diagnostics)
+        }
+        None => diagnostics,
+    };
 
     if diagnostics.is_empty() {
         if verbose {
             println!("{}: No errors found", file_path.display());
         }
-        Ok(true)
-    } else {
-        let output = diagnostics::format_diagnostics_with_file(&diagnostics, file_path);
-        println!("{}", output);
+        return true;
+    }
 
-        let has_errors = diagnostics
-            .iter()
-            .any(|d| d.level == diagnostics::DiagnosticLevel::Error);
+    let output = diagnostics::format_diagnostics_with_source(&diagnostics, &source);
+    println!("{}", output);
 
-        Ok(!has_errors)
+    !diagnostics
+        .iter()
+        .any(|d| d.level == diagnostics::DiagnosticLevel::Error)
+}
+
+/// Print the baselined diagnostics of the checked files that no longer occur
+fn report_fixed(baseline: &Baseline, files: &[PathBuf], config: &Config) {
+    let fixed: Vec<&BaselineEntry> = files
+        .iter()
+        .flat_map(|file_path| baseline.unmatched(&config.relative_path(file_path)))
+        .collect();
+    if fixed.is_empty() {
+        return;
+    }
+
+    let count: usize = fixed.iter().map(|entry| entry.count).sum();
+    println!(
+        "{} baselined diagnostics no longer occur (run with --generate-baseline to remove them):",
+        count
+    );
+    for entry in fixed {
+        let times = if entry.count > 1 {
+            format!(" (x{})", entry.count)
+        } else {
+            String::new()
+        };
+        println!(
+            "   {}: {} [{}]{}",
+            entry.file, entry.message, entry.code, times
+        );
     }
 }
 
-/// Check all Ruby files in the project
-pub fn check_project(_verbose: bool) -> Result<()> {
-    println!("Project-wide checking not yet implemented");
-    println!("Use: methodray check <file> to check a single file");
-    Ok(())
+/// Files under the project root that the configuration includes, skipping
+/// hidden and excluded directories
+fn find_ruby_files(config: &Config) -> Vec<PathBuf> {
+    let relative = |path: &Path| {
+        path.strip_prefix(&config.root)
+            .unwrap_or(path)
+            .to_path_buf()
+    };
+    let mut files: Vec<PathBuf> = walkdir::WalkDir::new(&config.root)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !(name.starts_with('.')
+                    || entry.file_type().is_dir()
+                        && config.is_excluded_dir(&relative(entry.path())))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| config.is_included(&relative(path)))
+        .collect();
+    files.sort();
+    files
 }
 
 /// Watch a file for changes and re-check on modifications
-pub fn watch_file(file_path: &PathBuf) -> Result<()> {
+pub fn watch_file(file_path: &PathBuf, config: &Config) -> Result<()> {
     use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
     use std::sync::mpsc::channel;
     use std::time::Duration;
@@ -53,9 +261,11 @@ pub fn watch_file(file_path: &PathBuf) -> Result<()> {
     );
     println!();
 
+    let baseline_path = default_baseline_path(config);
+
     // Initial check
     println!("Initial check:");
-    let mut had_errors = match check_single_file(file_path, true) {
+    let mut had_errors = match check_single_file(file_path, true, config, &baseline_path) {
         Ok(success) => !success,
         Err(e) => {
             eprintln!("Error during initial check: {}", e);
@@ -87,7 +297,7 @@ pub fn watch_file(file_path: &PathBuf) -> Result<()> {
 
                     std::thread::sleep(Duration::from_millis(100));
 
-                    match check_single_file(file_path, true) {
+                    match check_single_file(file_path, true, config, &baseline_path) {
                         Ok(success) => {
                             if success && had_errors {
                                 println!("✓ All errors fixed!");
@@ -133,6 +343,28 @@ pub fn clear_cache() -> Result<()> {
     Ok(())
 }
 
+/// Print the long-form description of a diagnostic code, or list every
+/// code when none is given
+pub fn explain(code: Option<&str>) -> Result<()> {
+    match code {
+        Some(code) => {
+            let rule = catalog::find_rule(code).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown diagnostic code: {} (run `methodray explain` to list codes)",
+                    code
+                )
+            })?;
+            println!("{}", rule.explain());
+        }
+        None => {
+            for rule in catalog::RULES {
+                println!("{}  {:<7}  {}", rule.code, rule.level.as_str(), rule.title);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Print version information
 pub fn print_version() {
     println!("MethodRay {}", env!("CARGO_PKG_VERSION"));
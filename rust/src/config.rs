@@ -0,0 +1,371 @@
+//! Project configuration (`.methodray.yml`)
+//!
+//! The configuration file is looked up from the working directory (or the
+//! checked document, in the editor) upwards. Its directory is the project
+//! root: globs and directories in it are relative to that root. Every
+//! setting is optional:
+//!
+//! ```yaml
+//! include: ["app/**/*.rb", "lib/**/*.rb"]  # files checked by `methodray check`
+//! exclude: ["vendor/**", "db/schema.rb"]
+//! signature_dirs: ["sig"]                  # RBS files declaring methods
+//! load_paths: ["app/lib"]                  # searched by `require`, after lib
+//! severity:
+//!   MR003: error                           # error, warning or off
+//!   MR009: off
+//! ignored_receivers: ["ActiveRecord::Relation"]
+//! ```
+
+use crate::diagnostics::catalog;
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Name of the configuration file
+pub const CONFIG_FILE_NAME: &str = ".methodray.yml";
+
+/// Files checked unless `include` is configured
+const DEFAULT_INCLUDE: &[&str] = &["**/*.rb"];
+
+/// Files skipped unless `exclude` is configured
+const DEFAULT_EXCLUDE: &[&str] = &["**/vendor/**"];
+
+/// Signature directory used when it exists and `signature_dirs` is not
+/// configured
+const DEFAULT_SIGNATURE_DIR: &str = "sig";
+
+/// Load path always searched by `require`
+const DEFAULT_LOAD_PATH: &str = "lib";
+
+/// Severity of a rule's diagnostics, overriding its default level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    /// The rule is not reported
+    Off,
+}
+
+impl FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "error" => Ok(Severity::Error),
+            "warning" => Ok(Severity::Warning),
+            "off" => Ok(Severity::Off),
+            _ => anyhow::bail!("Invalid severity `{}` (expected error, warning or off)", s),
+        }
+    }
+}
+
+/// Contents of the configuration file, as written
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    signature_dirs: Option<Vec<PathBuf>>,
+    #[serde(default)]
+    load_paths: Vec<PathBuf>,
+    #[serde(default)]
+    severity: HashMap<String, Severity>,
+    #[serde(default)]
+    ignored_receivers: Vec<String>,
+}
+
+/// Checker configuration, with paths resolved against the project root
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Directory of the configuration file (where the lookup started
+    /// without one)
+    pub root: PathBuf,
+    /// Configuration file, if one was found
+    pub path: Option<PathBuf>,
+    include: GlobSet,
+    exclude: GlobSet,
+    /// Directories whose whole contents are excluded (`vendor/**`), so the
+    /// project walk skips them
+    excluded_dirs: GlobSet,
+    /// Directories of the RBS files declaring project methods
+    pub signature_dirs: Vec<PathBuf>,
+    /// Directories searched by `require`
+    pub load_paths: Vec<PathBuf>,
+    /// Severity overrides by rule code
+    severity: HashMap<String, Severity>,
+    /// Receiver classes whose diagnostics are not reported
+    pub ignored_receivers: Vec<String>,
+}
+
+impl Default for Config {
+    /// The defaults for the current directory, without signature directories
+    fn default() -> Self {
+        let config_file = ConfigFile {
+            signature_dirs: Some(Vec::new()),
+            ..ConfigFile::default()
+        };
+        Self::from_config_file(Path::new("."), None, config_file)
+            .expect("the default configuration is valid")
+    }
+}
+
+impl Config {
+    /// Configuration of the project containing `start` (a directory)
+    ///
+    /// Looks for the configuration file in `start` and its ancestors, and
+    /// falls back to the defaults when there is none.
+    pub fn discover(start: &Path) -> Result<Self> {
+        let dir = start
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", start.display()))?;
+        for (depth, ancestor) in dir.ancestors().enumerate() {
+            if ancestor.join(CONFIG_FILE_NAME).is_file() {
+                let root = ancestor_path(start, depth);
+                return Self::load(&root.join(CONFIG_FILE_NAME));
+            }
+        }
+        Self::from_config_file(start, None, ConfigFile::default())
+    }
+
+    /// Load a configuration file (its directory is the project root)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read configuration {}", path.display()))?;
+        let root = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        Self::parse(root, path, &content)
+            .with_context(|| format!("Invalid configuration in {}", path.display()))
+    }
+
+    /// Parse and validate the contents of a configuration file
+    fn parse(root: &Path, path: &Path, content: &str) -> Result<Self> {
+        // A file with only comments is an empty configuration
+        let config_file = serde_yaml::from_str::<Option<ConfigFile>>(content)?.unwrap_or_default();
+        Self::from_config_file(root, Some(path.to_path_buf()), config_file)
+    }
+
+    fn from_config_file(root: &Path, path: Option<PathBuf>, file: ConfigFile) -> Result<Self> {
+        let include = file
+            .include
+            .unwrap_or_else(|| DEFAULT_INCLUDE.iter().map(|s| s.to_string()).collect());
+        let exclude = file
+            .exclude
+            .unwrap_or_else(|| DEFAULT_EXCLUDE.iter().map(|s| s.to_string()).collect());
+        let excluded_dirs: Vec<String> = exclude
+            .iter()
+            .filter_map(|pattern| pattern.strip_suffix("/**"))
+            .map(str::to_string)
+            .collect();
+
+        let signature_dirs = match file.signature_dirs {
+            Some(dirs) => existing_dirs(root, dirs, "signature directory")?,
+            None => Some(root.join(DEFAULT_SIGNATURE_DIR))
+                .filter(|dir| dir.is_dir())
+                .into_iter()
+                .collect(),
+        };
+        let mut load_paths = vec![root.join(DEFAULT_LOAD_PATH)];
+        load_paths.extend(existing_dirs(root, file.load_paths, "load path")?);
+
+        let mut config = Self {
+            root: root.to_path_buf(),
+            path,
+            include: glob_set(&include, "include")?,
+            exclude: glob_set(&exclude, "exclude")?,
+            excluded_dirs: glob_set(&excluded_dirs, "exclude")?,
+            signature_dirs,
+            load_paths,
+            severity: HashMap::new(),
+            ignored_receivers: file.ignored_receivers,
+        };
+        for (code, severity) in file.severity {
+            config.set_severity(&code, severity)?;
+        }
+        Ok(config)
+    }
+
+    /// Override the severity of a rule (e.g., from `--severity MR003=off`)
+    pub fn set_severity(&mut self, code: &str, severity: Severity) -> Result<()> {
+        let rule = catalog::find_rule(code).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown diagnostic code `{}` in severity (run `methodray explain` to list codes)",
+                code
+            )
+        })?;
+        self.severity.insert(rule.code.to_string(), severity);
+        Ok(())
+    }
+
+    /// Severity configured for a rule, if it is overridden
+    pub fn severity(&self, code: &str) -> Option<Severity> {
+        self.severity.get(code).copied()
+    }
+
+    /// Whether a project file is checked (`path` is relative to the root)
+    pub fn is_included(&self, path: &Path) -> bool {
+        self.include.is_match(path) && !self.exclude.is_match(path)
+    }
+
+    /// Whether every file under a directory is excluded (`path` is relative
+    /// to the root)
+    pub fn is_excluded_dir(&self, path: &Path) -> bool {
+        self.excluded_dirs.is_match(path)
+    }
+
+    /// Path of a file relative to the root, so it names the file the same
+    /// from any working directory (a file outside the root keeps its path)
+    pub fn relative_path(&self, path: &Path) -> PathBuf {
+        if let Ok(relative) = path.strip_prefix(&self.root) {
+            return relative.to_path_buf();
+        }
+        // `user.rb` checked from `app/models` with the root at `../..`
+        match (path.canonicalize(), self.root.canonicalize()) {
+            (Ok(file), Ok(root)) => match file.strip_prefix(&root) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => path.to_path_buf(),
+            },
+            _ => path.to_path_buf(),
+        }
+    }
+}
+
+/// `start`, or its ancestor `depth` levels up, as a relative path when
+/// `start` is relative
+fn ancestor_path(start: &Path, depth: usize) -> PathBuf {
+    if depth == 0 {
+        return start.to_path_buf();
+    }
+    let parents: PathBuf = (0..depth).map(|_| "..").collect();
+    if start == Path::new(".") {
+        parents
+    } else {
+        start.join(parents)
+    }
+}
+
+/// Configured directories resolved against the root, which must exist
+fn existing_dirs(root: &Path, dirs: Vec<PathBuf>, setting: &str) -> Result<Vec<PathBuf>> {
+    dirs.into_iter()
+        .map(|dir| {
+            let resolved = root.join(&dir);
+            if resolved.is_dir() {
+                Ok(resolved)
+            } else {
+                anyhow::bail!("{} `{}` is not a directory", setting, dir.display())
+            }
+        })
+        .collect()
+}
+
+/// Compile glob patterns (`*` does not match `/`, `**` matches directories)
+fn glob_set(patterns: &[String], setting: &str) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .with_context(|| format!("Invalid {} pattern `{}`", setting, pattern))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Result<Config> {
+        Config::parse(Path::new("."), Path::new(CONFIG_FILE_NAME), content)
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = parse("# nothing configured yet\n").unwrap();
+
+        assert!(config.is_included(Path::new("app/models/user.rb")));
+        assert!(!config.is_included(Path::new("app/models/user.rbs")));
+        assert!(!config.is_included(Path::new("vendor/bundle/gem/lib/gem.rb")));
+        assert!(config.is_excluded_dir(Path::new("engines/billing/vendor")));
+        assert_eq!(config.load_paths, vec![PathBuf::from("./lib")]);
+        assert_eq!(config.severity("MR001"), None);
+    }
+
+    #[test]
+    fn test_include_and_exclude() {
+        let config = parse(
+            "include: [\"app/**/*.rb\", \"*.rb\"]\nexclude: [\"app/legacy/**\", \"db/schema.rb\"]\n",
+        )
+        .unwrap();
+
+        assert!(config.is_included(Path::new("app/models/user.rb")));
+        assert!(config.is_included(Path::new("Rakefile.rb")));
+        assert!(!config.is_included(Path::new("spec/user_spec.rb")));
+        assert!(!config.is_included(Path::new("app/legacy/report.rb")));
+        assert!(config.is_excluded_dir(Path::new("app/legacy")));
+        assert!(!config.is_excluded_dir(Path::new("app")));
+        // The default exclude is replaced
+        assert!(config.is_included(Path::new("app/vendor/client.rb")));
+    }
+
+    #[test]
+    fn test_severity() {
+        let mut config = parse("severity:\n  MR003: error\n  mr009: off\n").unwrap();
+
+        assert_eq!(config.severity("MR003"), Some(Severity::Error));
+        assert_eq!(config.severity("MR009"), Some(Severity::Off));
+
+        config.set_severity("MR003", Severity::Warning).unwrap();
+        assert_eq!(config.severity("MR003"), Some(Severity::Warning));
+        assert_eq!("off".parse::<Severity>().unwrap(), Severity::Off);
+        assert!("fatal".parse::<Severity>().is_err());
+    }
+
+    #[test]
+    fn test_invalid_configuration() {
+        let error = |content| format!("{:#}", parse(content).unwrap_err());
+
+        assert!(error("exlude: [\"vendor/**\"]\n").contains("unknown field `exlude`"));
+        assert!(error("severity:\n  MR001: fatal\n").contains("unknown variant `fatal`"));
+        assert!(error("severity:\n  MR099: off\n").contains("Unknown diagnostic code `MR099`"));
+        assert!(error("include: [\"app/[*.rb\"]\n").contains("Invalid include pattern"));
+        assert!(error("load_paths: [\"no/such/dir\"]\n")
+            .contains("load path `no/such/dir` is not a directory"));
+        assert!(error("include: app/**/*.rb\n").contains("invalid type"));
+    }
+
+    #[test]
+    fn test_discover_from_subdirectory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("app/models")).unwrap();
+        std::fs::create_dir_all(dir.path().join("sig")).unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            "ignored_receivers: [\"ActiveRecord::Relation\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::discover(&dir.path().join("app/models")).unwrap();
+
+        assert_eq!(config.root, dir.path().join("app/models/../.."));
+        assert_eq!(config.ignored_receivers, vec!["ActiveRecord::Relation"]);
+        assert_eq!(config.signature_dirs, vec![config.root.join("sig")]);
+        assert!(config.path.is_some());
+
+        std::fs::write(dir.path().join("app/models/user.rb"), "").unwrap();
+        let user = dir.path().join("app/models/user.rb");
+        assert_eq!(config.relative_path(&user), Path::new("app/models/user.rb"));
+        let outside = Path::new("/nonexistent/user.rb");
+        assert_eq!(config.relative_path(outside), outside);
+
+        let empty = tempfile::tempdir().unwrap();
+        let config = Config::discover(empty.path()).unwrap();
+        assert_eq!(config.path, None);
+        assert!(config.signature_dirs.is_empty());
+    }
+}
@@ -0,0 +1,274 @@
+//! Baseline: known diagnostics that are not reported
+//!
+//! `methodray check --generate-baseline` records the current diagnostics,
+//! so a project can adopt MethodRay and be held to "no new errors". An entry
+//! is keyed by file, code, message and a fingerprint of the source line,
+//! which still matches after the line moves.
+
+use super::diagnostic::Diagnostic;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Baseline file in the project root, used unless another is given
+pub const DEFAULT_BASELINE_PATH: &str = ".methodray_baseline.json";
+
+/// Version of the baseline file format
+const BASELINE_VERSION: u32 = 1;
+
+/// Diagnostics recorded as known
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+/// A known diagnostic (`count` identical ones in the same file)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub file: String,
+    pub code: String,
+    pub message: String,
+    /// Hash of the source line, ignoring indentation
+    pub fingerprint: String,
+    pub count: usize,
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Load a baseline file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        let baseline: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse baseline {}", path.display()))?;
+        if baseline.version != BASELINE_VERSION {
+            anyhow::bail!(
+                "Unsupported baseline version {} in {} (regenerate it with --generate-baseline)",
+                baseline.version,
+                path.display()
+            );
+        }
+        Ok(baseline)
+    }
+
+    /// Write the baseline file, with entries sorted so it diffs well
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut baseline = self.clone();
+        baseline.entries.sort_by(|a, b| {
+            (&a.file, &a.code, &a.message, &a.fingerprint).cmp(&(
+                &b.file,
+                &b.code,
+                &b.message,
+                &b.fingerprint,
+            ))
+        });
+        let content = serde_json::to_string_pretty(&baseline)?;
+        std::fs::write(path, content + "\n")
+            .with_context(|| format!("Failed to write baseline {}", path.display()))
+    }
+
+    /// Number of diagnostics recorded
+    pub fn diagnostic_count(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+
+    /// Forget the diagnostics of a file
+    pub fn remove_file(&mut self, file_path: &Path) {
+        let file = baseline_file_name(file_path);
+        self.entries.retain(|entry| entry.file != file);
+    }
+
+    /// Record the diagnostics of a file
+    pub fn add(&mut self, file_path: &Path, source: &str, diagnostics: &[Diagnostic]) {
+        for diagnostic in diagnostics {
+            let key = BaselineEntry::new(file_path, source, diagnostic);
+            match self.entries.iter_mut().find(|entry| entry.same_key(&key)) {
+                Some(entry) => entry.count += 1,
+                None => self.entries.push(key),
+            }
+        }
+    }
+
+    /// Diagnostics of a file that are not in the baseline
+    ///
+    /// Matched entries are used up, so a second identical diagnostic is new
+    /// and the entries left for a checked file are fixed.
+    pub fn filter_new(
+        &mut self,
+        file_path: &Path,
+        source: &str,
+        diagnostics: Vec<Diagnostic>,
+    ) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter(|diagnostic| {
+                let key = BaselineEntry::new(file_path, source, diagnostic);
+                match self
+                    .entries
+                    .iter_mut()
+                    .find(|entry| entry.count > 0 && entry.same_key(&key))
+                {
+                    Some(entry) => {
+                        entry.count -= 1;
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect()
+    }
+
+    /// Entries of a file not matched by `filter_new`: diagnostics that no
+    /// longer occur
+    pub fn unmatched(&self, file_path: &Path) -> impl Iterator<Item = &BaselineEntry> {
+        let file = baseline_file_name(file_path);
+        self.entries
+            .iter()
+            .filter(move |entry| entry.count > 0 && entry.file == file)
+    }
+}
+
+impl BaselineEntry {
+    fn new(file_path: &Path, source: &str, diagnostic: &Diagnostic) -> Self {
+        let line = diagnostic
+            .location
+            .line
+            .checked_sub(1)
+            .and_then(|index| source.lines().nth(index))
+            .unwrap_or_default();
+        Self {
+            file: baseline_file_name(file_path),
+            code: diagnostic.code.clone().unwrap_or_default(),
+            message: diagnostic.message.clone(),
+            fingerprint: fingerprint(line),
+            count: 1,
+        }
+    }
+
+    fn same_key(&self, other: &BaselineEntry) -> bool {
+        self.file == other.file
+            && self.code == other.code
+            && self.message == other.message
+            && self.fingerprint == other.fingerprint
+    }
+}
+
+/// File name as recorded: relative paths without `./`, with `/` separators
+fn baseline_file_name(file_path: &Path) -> String {
+    let name = file_path.to_string_lossy().replace('\\', "/");
+    name.strip_prefix("./").unwrap_or(&name).to_string()
+}
+
+/// Stable hash (FNV-1a) of a source line with whitespace normalized
+fn fingerprint(line: &str) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let normalized = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    let hash = normalized.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Location;
+    use std::path::PathBuf;
+
+    fn undefined_method(line: usize, method_name: &str) -> Diagnostic {
+        let location = Location {
+            file: PathBuf::from("app/user.rb"),
+            line,
+            column: 6,
+            length: Some(method_name.len()),
+        };
+        Diagnostic::undefined_method(location, "String", method_name)
+    }
+
+    #[test]
+    fn test_baseline_matches_moved_lines() {
+        let source = "name = \"Alice\"\nname.abs\n";
+        let mut baseline = Baseline::default();
+        baseline.add(
+            Path::new("./app/user.rb"),
+            source,
+            &[undefined_method(2, "abs")],
+        );
+        assert_eq!(baseline.diagnostic_count(), 1);
+
+        // Two lines were inserted above and the line was re-indented
+        let source = "# User\n\nname = \"Alice\"\n  name.abs\nname.abs\n";
+        let diagnostics = vec![undefined_method(4, "abs"), undefined_method(5, "abs")];
+
+        let new = baseline.filter_new(Path::new("app/user.rb"), source, diagnostics);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].location.line, 5);
+        assert_eq!(baseline.unmatched(Path::new("app/user.rb")).count(), 0);
+    }
+
+    #[test]
+    fn test_baseline_reports_fixed_entries() {
+        let source = "name.abs\nname.lenght\n";
+        let mut baseline = Baseline::default();
+        baseline.add(
+            Path::new("app/user.rb"),
+            source,
+            &[undefined_method(1, "abs"), undefined_method(2, "lenght")],
+        );
+
+        let source = "name.abs\nname.length\n";
+        let new = baseline.filter_new(
+            Path::new("app/user.rb"),
+            source,
+            vec![undefined_method(1, "abs")],
+        );
+        assert!(new.is_empty());
+
+        let fixed: Vec<&str> = baseline
+            .unmatched(Path::new("app/user.rb"))
+            .map(|entry| entry.message.as_str())
+            .collect();
+        assert_eq!(fixed, vec!["undefined method `lenght` for String"]);
+        assert_eq!(baseline.unmatched(Path::new("app/other.rb")).count(), 0);
+    }
+
+    #[test]
+    fn test_baseline_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_BASELINE_PATH);
+        let mut baseline = Baseline::default();
+        baseline.add(
+            Path::new("app/user.rb"),
+            "name.abs\nname.abs\n",
+            &[undefined_method(1, "abs"), undefined_method(2, "abs")],
+        );
+
+        baseline.save(&path).unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+
+        assert_eq!(loaded, baseline);
+        assert_eq!(loaded.entries[0].count, 2);
+        assert_eq!(loaded.entries[0].code, "MR001");
+
+        std::fs::write(&path, r#"{"version": 99, "entries": []}"#).unwrap();
+        assert!(Baseline::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_indentation() {
+        assert_eq!(fingerprint("  name.abs"), fingerprint("name.abs  "));
+        assert_ne!(fingerprint("name.abs"), fingerprint("other.abs"));
+        assert_eq!(fingerprint(""), "cbf29ce484222325");
+    }
+}
@@ -0,0 +1,259 @@
+//! Diagnostic catalog: the checks MethodRay performs
+//!
+//! Every diagnostic carries the stable code of its rule (`MR001`), so
+//! specific checks can be filtered, documented and suppressed.
+//! `methodray explain MR001` prints the rule's long-form description.
+
+use super::diagnostic::DiagnosticLevel;
+
+/// A kind of diagnostic, identified by a stable code
+#[derive(Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// Stable code, e.g. `MR001` (never reused once published)
+    pub code: &'static str,
+    pub title: &'static str,
+    /// Severity of the diagnostics reported by this rule
+    pub level: DiagnosticLevel,
+    /// Long-form description with an example
+    pub description: &'static str,
+}
+
+impl Rule {
+    /// Long-form explanation, as printed by `methodray explain`
+    pub fn explain(&self) -> String {
+        format!(
+            "{}: {} ({})\n\n{}",
+            self.code,
+            self.title,
+            self.level.as_str(),
+            self.description
+        )
+    }
+}
+
+pub static UNDEFINED_METHOD: Rule = Rule {
+    code: "MR001",
+    title: "undefined method",
+    level: DiagnosticLevel::Error,
+    description: "\
+A method is called on a receiver whose type does not define it, which
+raises NoMethodError at runtime.
+
+    name = \"Alice\"
+    name.abs      # undefined method `abs` for String
+
+Similar method names are suggested when the name looks misspelled.",
+};
+
+pub static NIL_RECEIVER: Rule = Rule {
+    code: "MR002",
+    title: "receiver may be nil",
+    level: DiagnosticLevel::Error,
+    description: "\
+The receiver may be nil on some path, and NilClass does not define the
+method called.
+
+    user = users.find { |u| u.admin? }
+    user.name     # receiver may be nil: undefined method `name` for nil
+
+The related location points at where the nil was introduced. Check for
+nil first (`user&.name`, `return unless user`).",
+};
+
+pub static UNKNOWN_RECORD_KEY: Rule = Rule {
+    code: "MR003",
+    title: "unknown record key",
+    level: DiagnosticLevel::Warning,
+    description: "\
+A hash literal with symbol keys is read with a key it does not have, so
+the lookup always returns nil.
+
+    options = { timeout: 5 }
+    options[:retries]   # unknown key `:retries` for { timeout: Integer }",
+};
+
+pub static NO_SUPER_METHOD: Rule = Rule {
+    code: "MR004",
+    title: "no superclass method",
+    level: DiagnosticLevel::Error,
+    description: "\
+`super` is called in a method that no ancestor of the class defines.
+
+    class Admin < User
+      def save
+        super     # super: no superclass method `save` for Admin
+      end
+    end",
+};
+
+pub static VISIBILITY_VIOLATION: Rule = Rule {
+    code: "MR005",
+    title: "private or protected method called",
+    level: DiagnosticLevel::Error,
+    description: "\
+A private method is called with an explicit receiver, or a protected
+method from outside the class hierarchy.
+
+    class User
+      private
+
+      def secret; end
+    end
+
+    User.new.secret   # private method `secret` called for User",
+};
+
+pub static UNRESOLVED_REQUIRE: Rule = Rule {
+    code: "MR006",
+    title: "unresolvable require",
+    level: DiagnosticLevel::Warning,
+    description: "\
+A `require_relative` path does not exist relative to the file, or a
+`require` of a project file is not found in the load paths (`lib` and
+`-I DIR`). Its declarations are unknown to the check.
+
+    require_relative \"user/validatoins\"   # cannot resolve require_relative \"user/validatoins\"
+
+A `require` is of a project file when the first segment of its path is
+in a load path (`billing/invoise` with `lib/billing/`). Gems and standard
+libraries (`require \"json\"`) are not reported.",
+};
+
+pub static UNINITIALIZED_CONSTANT: Rule = Rule {
+    code: "MR007",
+    title: "uninitialized constant",
+    level: DiagnosticLevel::Error,
+    description: "\
+A constant is not defined in the enclosing namespaces, the ancestors of
+the enclosing class or the top level, which raises NameError at runtime.
+
+    class Config
+      TIMEOUT = 30
+    end
+
+    Config::TIMOUT   # uninitialized constant Config::TIMOUT
+
+Constants of a class whose superclass is outside the project (e.g., from
+a gem), or of a class or module with mixins, are not reported. Top-level
+constants (`Usr`) are only reported when checking the whole project, and
+only if it has no Gemfile and requires no gems or standard libraries.",
+};
+
+pub static UNDEFINED_LOCAL: Rule = Rule {
+    code: "MR008",
+    title: "undefined local variable or method",
+    level: DiagnosticLevel::Error,
+    description: "\
+A bare identifier is neither a local variable nor a method of self, which
+raises NameError at runtime.
+
+    class User
+      attr_reader :name
+
+      def greeting
+        \"Hello, #{nmae}\"   # undefined local variable or method `nmae` for User
+      end
+    end",
+};
+
+pub static UNION_PARTIAL: Rule = Rule {
+    code: "MR009",
+    title: "method not defined for every type of a union",
+    level: DiagnosticLevel::Warning,
+    description: "\
+The receiver has several possible types and only some of them define the
+method called.
+
+    value = flag ? \"text\" : 42
+    value.upcase   # method `upcase` is defined for String but not for Integer",
+};
+
+pub static SYNTAX_ERROR: Rule = Rule {
+    code: "MR010",
+    title: "syntax error",
+    level: DiagnosticLevel::Error,
+    description: "\
+The file cannot be parsed, so it is not type checked.
+
+    def greeting
+      puts \"Hello\"
+    # syntax error: expected an `end` to close the `def` statement",
+};
+
+pub static UNUSED_SUPPRESSION: Rule = Rule {
+    code: "MR011",
+    title: "unused suppression comment",
+    level: DiagnosticLevel::Warning,
+    description: "\
+A suppression comment no longer silences any diagnostic, e.g. because the
+code was fixed. Remove it, so it does not hide a future problem.
+
+    name.upcase   # methodray:disable-line MR001
+
+Diagnostics are suppressed on one line (`disable-line`), on the next line
+(`disable-next-line`), or from `# methodray:disable` up to
+`# methodray:enable`. Without codes, every diagnostic is suppressed.",
+};
+
+pub static WRONG_ARITY: Rule = Rule {
+    code: "MR012",
+    title: "wrong number of arguments",
+    level: DiagnosticLevel::Error,
+    description: "\
+A method is called with more positional arguments than it accepts, which
+raises ArgumentError at runtime. Checked for the constructors of classes
+defined with `Struct.new` and `Data.define`.
+
+    Point = Struct.new(:x, :y)
+    Point.new(1, 2, 3)   # wrong number of arguments (given 3, expected at most 2) for `new` of singleton(Point)",
+};
+
+/// Every rule, in code order
+pub static RULES: [&Rule; 12] = [
+    &UNDEFINED_METHOD,
+    &NIL_RECEIVER,
+    &UNKNOWN_RECORD_KEY,
+    &NO_SUPER_METHOD,
+    &VISIBILITY_VIOLATION,
+    &UNRESOLVED_REQUIRE,
+    &UNINITIALIZED_CONSTANT,
+    &UNDEFINED_LOCAL,
+    &UNION_PARTIAL,
+    &SYNTAX_ERROR,
+    &UNUSED_SUPPRESSION,
+    &WRONG_ARITY,
+];
+
+/// Find a rule by code (case-insensitive: `mr001` finds `MR001`)
+pub fn find_rule(code: &str) -> Option<&'static Rule> {
+    RULES
+        .iter()
+        .copied()
+        .find(|rule| rule.code.eq_ignore_ascii_case(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique_and_ordered() {
+        for (i, rule) in RULES.iter().enumerate() {
+            assert_eq!(rule.code, format!("MR{:03}", i + 1));
+        }
+    }
+
+    #[test]
+    fn test_find_rule() {
+        assert_eq!(find_rule("MR002"), Some(&NIL_RECEIVER));
+        assert_eq!(find_rule("mr007"), Some(&UNINITIALIZED_CONSTANT));
+        assert_eq!(find_rule("E001"), None);
+    }
+
+    #[test]
+    fn test_explain() {
+        let explanation = UNKNOWN_RECORD_KEY.explain();
+        assert!(explanation.starts_with("MR003: unknown record key (warning)\n\n"));
+        assert!(explanation.contains("options[:retries]"));
+    }
+}
@@ -1,3 +1,4 @@
+use super::catalog::{self, Rule};
 use std::path::PathBuf;
 
 /// Diagnostic severity level (LSP compatible)
@@ -26,6 +27,13 @@ pub struct Location {
     pub length: Option<usize>, // Character length of the error span
 }
 
+/// Additional location related to a diagnostic (e.g., where a nil came from)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedInformation {
+    pub location: Location,
+    pub message: String,
+}
+
 /// Type checking diagnostic
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -33,7 +41,10 @@ pub struct Diagnostic {
     pub location: Location,
     pub level: DiagnosticLevel,
     pub message: String,
-    pub code: Option<String>, // e.g., "E001"
+    pub code: Option<String>, // e.g., "MR001" (see catalog)
+    pub related: Vec<RelatedInformation>,
+    /// Names the misspelled one was likely meant to be, best match first
+    pub suggestions: Vec<String>,
 }
 
 #[allow(dead_code)]
@@ -45,27 +56,172 @@ impl Diagnostic {
             level: DiagnosticLevel::Error,
             message,
             code: None,
+            related: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
-    /// Create a warning diagnostic (internal use only)
-    fn warning(location: Location, message: String) -> Self {
+    /// Create a diagnostic of a catalog rule, with the rule's severity and code
+    pub fn for_rule(rule: &Rule, location: Location, message: String) -> Self {
         Self {
             location,
-            level: DiagnosticLevel::Warning,
+            level: rule.level,
             message,
-            code: None,
+            code: Some(rule.code.to_string()),
+            related: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
+    /// Attach related information
+    pub fn with_related(mut self, location: Location, message: &str) -> Self {
+        self.related.push(RelatedInformation {
+            location,
+            message: message.to_string(),
+        });
+        self
+    }
+
+    /// Attach "Did you mean?" suggestions
+    pub fn with_suggestions(mut self, suggestions: Vec<String>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    /// "did you mean `length`?" for the attached suggestions
+    pub fn suggestion_message(&self) -> Option<String> {
+        let (last, rest) = self.suggestions.split_last()?;
+        let quoted = |name: &String| format!("`{}`", name);
+        let names = if rest.is_empty() {
+            quoted(last)
+        } else {
+            format!(
+                "{} or {}",
+                rest.iter().map(quoted).collect::<Vec<_>>().join(", "),
+                quoted(last)
+            )
+        };
+        Some(format!("did you mean {}?", names))
+    }
+
     /// Create undefined method error
     pub fn undefined_method(location: Location, receiver_type: &str, method_name: &str) -> Self {
-        Self::error(
+        Self::for_rule(
+            &catalog::UNDEFINED_METHOD,
             location,
             format!("undefined method `{}` for {}", method_name, receiver_type),
         )
     }
 
+    /// Create nil receiver error (possible NoMethodError on nil)
+    ///
+    /// `nil_origin` points at the nil literal or call site that introduced nil.
+    pub fn nil_receiver(
+        location: Location,
+        method_name: &str,
+        nil_origin: Option<Location>,
+    ) -> Self {
+        let diag = Self::for_rule(
+            &catalog::NIL_RECEIVER,
+            location,
+            format!(
+                "receiver may be nil: undefined method `{}` for nil",
+                method_name
+            ),
+        );
+
+        match nil_origin {
+            Some(origin) => diag.with_related(origin, "nil introduced here"),
+            None => diag,
+        }
+    }
+
+    /// Create unknown record key warning (`opts[:key]` always returns nil)
+    pub fn unknown_record_key(location: Location, record_type: &str, key: &str) -> Self {
+        Self::for_rule(
+            &catalog::UNKNOWN_RECORD_KEY,
+            location,
+            format!("unknown key `:{}` for {}", key, record_type),
+        )
+    }
+
+    /// Create missing super method error
+    pub fn no_super_method(location: Location, class_name: &str, method_name: &str) -> Self {
+        Self::for_rule(
+            &catalog::NO_SUPER_METHOD,
+            location,
+            format!(
+                "super: no superclass method `{}` for {}",
+                method_name, class_name
+            ),
+        )
+    }
+
+    /// Create wrong number of arguments error
+    pub fn wrong_arity(
+        location: Location,
+        receiver_type: &str,
+        method_name: &str,
+        given: usize,
+        max: usize,
+    ) -> Self {
+        Self::for_rule(
+            &catalog::WRONG_ARITY,
+            location,
+            format!(
+                "wrong number of arguments (given {}, expected at most {}) for `{}` of {}",
+                given, max, method_name, receiver_type
+            ),
+        )
+    }
+
+    /// Create private/protected method call error
+    pub fn visibility_violation(
+        location: Location,
+        visibility: &str,
+        receiver_type: &str,
+        method_name: &str,
+    ) -> Self {
+        Self::for_rule(
+            &catalog::VISIBILITY_VIOLATION,
+            location,
+            format!(
+                "{} method `{}` called for {}",
+                visibility, method_name, receiver_type
+            ),
+        )
+    }
+
+    /// Create unresolvable `require` / `require_relative` warning
+    pub fn unresolved_require(location: Location, method: &str, path: &str) -> Self {
+        Self::for_rule(
+            &catalog::UNRESOLVED_REQUIRE,
+            location,
+            format!("cannot resolve {} \"{}\"", method, path),
+        )
+    }
+
+    /// Create uninitialized constant error (NameError)
+    pub fn uninitialized_constant(location: Location, name: &str) -> Self {
+        Self::for_rule(
+            &catalog::UNINITIALIZED_CONSTANT,
+            location,
+            format!("uninitialized constant {}", name),
+        )
+    }
+
+    /// Create undefined local variable or method error (NameError)
+    pub fn undefined_local(location: Location, self_type: &str, name: &str) -> Self {
+        Self::for_rule(
+            &catalog::UNDEFINED_LOCAL,
+            location,
+            format!(
+                "undefined local variable or method `{}` for {}",
+                name, self_type
+            ),
+        )
+    }
+
     /// Create Union type partial error (warning)
     pub fn union_partial_error(
         location: Location,
@@ -79,7 +235,23 @@ impl Diagnostic {
             valid_types.join(", "),
             invalid_types.join(", ")
         );
-        Self::warning(location, message)
+        Self::for_rule(&catalog::UNION_PARTIAL, location, message)
+    }
+
+    /// Create unused suppression comment warning
+    ///
+    /// `comment` is the directive, e.g. `methodray:disable-line MR001`.
+    pub fn unused_suppression(location: Location, comment: &str) -> Self {
+        Self::for_rule(
+            &catalog::UNUSED_SUPPRESSION,
+            location,
+            format!("unused suppression comment `{}`", comment),
+        )
+    }
+
+    /// Create syntax error (the file is not type checked)
+    pub fn syntax_error(location: Location, message: &str) -> Self {
+        Self::for_rule(&catalog::SYNTAX_ERROR, location, message.to_string())
     }
 }
 
@@ -99,6 +271,162 @@ mod tests {
         let diag = Diagnostic::undefined_method(loc.clone(), "Integer", "upcase");
         assert_eq!(diag.level, DiagnosticLevel::Error);
         assert_eq!(diag.message, "undefined method `upcase` for Integer");
+        assert_eq!(diag.code.as_deref(), Some("MR001"));
+    }
+
+    #[test]
+    fn test_nil_receiver() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 3,
+            column: 1,
+            length: Some(8),
+        };
+        let origin = Location {
+            file: PathBuf::from("test.rb"),
+            line: 2,
+            column: 5,
+            length: Some(3),
+        };
+
+        let diag = Diagnostic::nil_receiver(loc, "upcase", Some(origin.clone()));
+        assert_eq!(diag.level, DiagnosticLevel::Error);
+        assert_eq!(diag.code.as_deref(), Some("MR002"));
+        assert!(diag.message.contains("may be nil"));
+        assert_eq!(diag.related.len(), 1);
+        assert_eq!(diag.related[0].location, origin);
+    }
+
+    #[test]
+    fn test_unknown_record_key() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 4,
+            column: 1,
+            length: Some(15),
+        };
+
+        let diag = Diagnostic::unknown_record_key(loc, "{ timeout: Integer }", "retries");
+        assert_eq!(diag.level, DiagnosticLevel::Warning);
+        assert_eq!(
+            diag.message,
+            "unknown key `:retries` for { timeout: Integer }"
+        );
+        assert_eq!(diag.code.as_deref(), Some("MR003"));
+    }
+
+    #[test]
+    fn test_no_super_method() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 5,
+            column: 5,
+            length: Some(5),
+        };
+
+        let diag = Diagnostic::no_super_method(loc, "Admin", "save");
+        assert_eq!(diag.level, DiagnosticLevel::Error);
+        assert_eq!(diag.message, "super: no superclass method `save` for Admin");
+        assert_eq!(diag.code.as_deref(), Some("MR004"));
+    }
+
+    #[test]
+    fn test_wrong_arity() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 3,
+            column: 1,
+            length: Some(18),
+        };
+
+        let diag = Diagnostic::wrong_arity(loc, "singleton(Point)", "new", 3, 2);
+        assert_eq!(diag.level, DiagnosticLevel::Error);
+        assert_eq!(
+            diag.message,
+            "wrong number of arguments (given 3, expected at most 2) for `new` of singleton(Point)"
+        );
+        assert_eq!(diag.code.as_deref(), Some("MR012"));
+    }
+
+    #[test]
+    fn test_visibility_violation() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 8,
+            column: 1,
+            length: Some(11),
+        };
+
+        let diag = Diagnostic::visibility_violation(loc, "private", "User", "secret");
+        assert_eq!(diag.level, DiagnosticLevel::Error);
+        assert_eq!(diag.message, "private method `secret` called for User");
+        assert_eq!(diag.code.as_deref(), Some("MR005"));
+    }
+
+    #[test]
+    fn test_unresolved_require() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 1,
+            column: 1,
+            length: Some(22),
+        };
+
+        let diag = Diagnostic::unresolved_require(loc, "require_relative", "missing");
+        assert_eq!(diag.level, DiagnosticLevel::Warning);
+        assert_eq!(diag.message, "cannot resolve require_relative \"missing\"");
+        assert_eq!(diag.code.as_deref(), Some("MR006"));
+    }
+
+    #[test]
+    fn test_name_errors() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 2,
+            column: 1,
+            length: Some(3),
+        };
+
+        let diag = Diagnostic::uninitialized_constant(loc.clone(), "Config::TIMOUT");
+        assert_eq!(diag.level, DiagnosticLevel::Error);
+        assert_eq!(diag.message, "uninitialized constant Config::TIMOUT");
+        assert_eq!(diag.code.as_deref(), Some("MR007"));
+
+        let diag = Diagnostic::undefined_local(loc, "User", "nmae");
+        assert_eq!(
+            diag.message,
+            "undefined local variable or method `nmae` for User"
+        );
+        assert_eq!(diag.code.as_deref(), Some("MR008"));
+    }
+
+    #[test]
+    fn test_suggestion_message() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 1,
+            column: 3,
+            length: Some(6),
+        };
+
+        let diag = Diagnostic::undefined_method(loc, "String", "lenght");
+        assert_eq!(diag.suggestion_message(), None);
+
+        let diag = diag.with_suggestions(vec!["length".to_string()]);
+        assert_eq!(
+            diag.suggestion_message().as_deref(),
+            Some("did you mean `length`?")
+        );
+
+        let diag = diag.with_suggestions(vec![
+            "size".to_string(),
+            "length".to_string(),
+            "lines".to_string(),
+        ]);
+        assert_eq!(
+            diag.suggestion_message().as_deref(),
+            Some("did you mean `size`, `length` or `lines`?")
+        );
     }
 
     #[test]
@@ -121,5 +449,39 @@ mod tests {
         assert!(diag
             .message
             .contains("method `upcase` is defined for String but not for Integer"));
+        assert_eq!(diag.code.as_deref(), Some("MR009"));
+    }
+
+    #[test]
+    fn test_unused_suppression() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 7,
+            column: 13,
+            length: Some(36),
+        };
+
+        let diag = Diagnostic::unused_suppression(loc, "methodray:disable-line MR001");
+        assert_eq!(diag.level, DiagnosticLevel::Warning);
+        assert_eq!(
+            diag.message,
+            "unused suppression comment `methodray:disable-line MR001`"
+        );
+        assert_eq!(diag.code.as_deref(), Some("MR011"));
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let loc = Location {
+            file: PathBuf::from("test.rb"),
+            line: 3,
+            column: 1,
+            length: Some(1),
+        };
+
+        let diag = Diagnostic::syntax_error(loc, "unexpected end-of-input");
+        assert_eq!(diag.level, DiagnosticLevel::Error);
+        assert_eq!(diag.message, "unexpected end-of-input");
+        assert_eq!(diag.code.as_deref(), Some("MR010"));
     }
 }
@@ -0,0 +1,159 @@
+//! "Did you mean?" suggestions for misspelled names
+//!
+//! Follows Ruby's `did_you_mean` spell checker: candidates close to the
+//! input by Jaro-Winkler distance (which favors a shared prefix and
+//! tolerates transposed letters) are kept, then narrowed down by
+//! Levenshtein distance.
+
+/// Names from `candidates` the misspelled `input` was likely meant to be,
+/// best match first
+pub fn suggest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let input = input.to_lowercase();
+    let input_len = input.chars().count();
+    let threshold = if input_len > 3 { 0.834 } else { 0.77 };
+
+    let mut words: Vec<(&str, f64)> = candidates
+        .into_iter()
+        .filter(|word| word.to_lowercase() != input)
+        .map(|word| (word, jaro_winkler(&word.to_lowercase(), &input)))
+        .filter(|(_, distance)| *distance >= threshold)
+        .collect();
+    words.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    words.dedup_by(|a, b| a.0 == b.0);
+
+    // Mistypes: a few characters off
+    let max_edits = (input_len as f64 * 0.25).ceil() as usize;
+    let mistypes: Vec<String> = words
+        .iter()
+        .filter(|(word, _)| levenshtein(&word.to_lowercase(), &input) <= max_edits)
+        .map(|(word, _)| word.to_string())
+        .collect();
+    if !mistypes.is_empty() {
+        return mistypes;
+    }
+
+    // Misspellings: the closest word, unless it shares almost nothing
+    words
+        .iter()
+        .find(|(word, _)| {
+            let word = word.to_lowercase();
+            levenshtein(&word, &input) < word.chars().count().min(input_len)
+        })
+        .map(|(word, _)| vec![word.to_string()])
+        .unwrap_or_default()
+}
+
+/// Jaro-Winkler similarity (1.0 for identical strings)
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    const PREFIX_WEIGHT: f64 = 0.1;
+    const BOOST_THRESHOLD: f64 = 0.7;
+
+    let distance = jaro(a, b);
+    if distance <= BOOST_THRESHOLD {
+        return distance;
+    }
+    let prefix = a
+        .chars()
+        .zip(b.chars())
+        .take(4)
+        .take_while(|(x, y)| x == y)
+        .count();
+    distance + prefix as f64 * PREFIX_WEIGHT * (1.0 - distance)
+}
+
+/// Jaro similarity
+fn jaro(a: &str, b: &str) -> f64 {
+    let (mut a, mut b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    if a.len() > b.len() {
+        std::mem::swap(&mut a, &mut b);
+    }
+    if a.is_empty() {
+        return 0.0;
+    }
+
+    let range = (b.len() / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+    for (i, ch) in a.iter().enumerate() {
+        let start = i.saturating_sub(range);
+        let end = (i + range + 1).min(b.len());
+        for j in start..end {
+            if !b_matched[j] && b[j] == *ch {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_matches = a.iter().zip(&a_matched).filter(|(_, m)| **m);
+    let b_matches = b.iter().zip(&b_matched).filter(|(_, m)| **m);
+    let transpositions = a_matches
+        .zip(b_matches)
+        .filter(|((x, _), (y, _))| x != y)
+        .count()
+        / 2;
+
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0
+}
+
+/// Levenshtein edit distance
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, y) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(x != *y);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRING_METHODS: [&str; 8] = [
+        "length",
+        "upcase",
+        "downcase",
+        "empty?",
+        "strip",
+        "size",
+        "start_with?",
+        "lines",
+    ];
+
+    #[test]
+    fn test_suggest_mistypes() {
+        assert_eq!(suggest("lenght", STRING_METHODS), vec!["length"]);
+        assert_eq!(suggest("upcse", STRING_METHODS), vec!["upcase"]);
+        assert_eq!(suggest("emtpy?", STRING_METHODS), vec!["empty?"]);
+        assert_eq!(suggest("strat_with?", STRING_METHODS), vec!["start_with?"]);
+        assert_eq!(suggest("TIMOUT", ["TIMEOUT", "RETRIES"]), vec!["TIMEOUT"]);
+    }
+
+    #[test]
+    fn test_suggest_nothing_for_unrelated_or_exact_names() {
+        assert!(suggest("frobnicate", STRING_METHODS).is_empty());
+        assert!(suggest("length", ["length"]).is_empty());
+        assert!(suggest("x", STRING_METHODS).is_empty());
+    }
+
+    #[test]
+    fn test_distances() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert!((jaro("martha", "marhta") - 0.944).abs() < 0.001);
+        assert!((jaro_winkler("martha", "marhta") - 0.961).abs() < 0.001);
+    }
+}
@@ -1,26 +1,20 @@
 use super::diagnostic::Diagnostic;
-use std::fs;
-use std::path::Path;
 
 /// Format diagnostics in LSP-compatible format
 ///
 /// Example output:
 /// ```text
-/// app/models/user.rb:10:5: error: undefined method `upcase` for Integer
+/// app/models/user.rb:10:5: error: undefined method `upcase` for Integer [MR001]
 /// ```
 #[allow(dead_code)]
 pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
     diagnostics
         .iter()
         .map(|diag| {
-            format!(
-                "{}:{}:{}: {}: {}",
-                diag.location.file.display(),
-                diag.location.line,
-                diag.location.column,
-                diag.level.as_str(),
-                diag.message
-            )
+            let mut output = format_header(diag);
+            output.push_str(&format_related(diag));
+            output.push_str(&format_suggestions(diag));
+            output
         })
         .collect::<Vec<_>>()
         .join("\n")
@@ -30,7 +24,7 @@ pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
 ///
 /// Example output:
 /// ```text
-/// app/models/user.rb:10:5: error: undefined method `upcase` for Integer
+/// app/models/user.rb:10:5: error: undefined method `upcase` for Integer [MR001]
 ///    x.upcase
 ///      ^^^^^^
 /// ```
@@ -40,14 +34,7 @@ pub fn format_diagnostics_with_source(diagnostics: &[Diagnostic], source_code: &
     diagnostics
         .iter()
         .map(|diag| {
-            let mut output = format!(
-                "{}:{}:{}: {}: {}",
-                diag.location.file.display(),
-                diag.location.line,
-                diag.location.column,
-                diag.level.as_str(),
-                diag.message
-            );
+            let mut output = format_header(diag);
 
             // Add code snippet
             if diag.location.line > 0 && diag.location.line <= lines.len() {
@@ -66,18 +53,62 @@ pub fn format_diagnostics_with_source(diagnostics: &[Diagnostic], source_code: &
                 output.push('^');
             }
 
+            output.push_str(&format_related(diag));
+            output.push_str(&format_suggestions(diag));
+
             output
         })
         .collect::<Vec<_>>()
         .join("\n\n")
 }
 
-/// Read source file and format diagnostics with code snippet
-pub fn format_diagnostics_with_file(diagnostics: &[Diagnostic], file_path: &Path) -> String {
-    match fs::read_to_string(file_path) {
-        Ok(source) => format_diagnostics_with_source(diagnostics, &source),
-        Err(_) => format_diagnostics(diagnostics), // Fallback to simple format
+/// Format the location, severity, message and code of a diagnostic
+fn format_header(diag: &Diagnostic) -> String {
+    let mut header = format!(
+        "{}:{}:{}: {}: {}",
+        diag.location.file.display(),
+        diag.location.line,
+        diag.location.column,
+        diag.level.as_str(),
+        diag.message
+    );
+    if let Some(code) = &diag.code {
+        header.push_str(&format!(" [{}]", code));
     }
+    header
+}
+
+/// Format related information as note lines
+///
+/// Example output:
+/// ```text
+///    note: nil introduced here (app/models/user.rb:8:12)
+/// ```
+fn format_related(diag: &Diagnostic) -> String {
+    diag.related
+        .iter()
+        .map(|related| {
+            format!(
+                "\n   note: {} ({}:{}:{})",
+                related.message,
+                related.location.file.display(),
+                related.location.line,
+                related.location.column
+            )
+        })
+        .collect()
+}
+
+/// Format "Did you mean?" suggestions as a help line
+///
+/// Example output:
+/// ```text
+///    help: did you mean `length`?
+/// ```
+fn format_suggestions(diag: &Diagnostic) -> String {
+    diag.suggestion_message()
+        .map(|message| format!("\n   help: {}", message))
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -114,6 +145,45 @@ mod tests {
 
         let output = format_diagnostics(&diagnostics);
         assert!(output.contains("test.rb:10:5: error:"));
+        assert!(output.contains("for Integer [MR001]"));
         assert!(output.contains("test.rb:15:3: warning:"));
     }
+
+    #[test]
+    fn test_format_related_information() {
+        let location = |line, column| Location {
+            file: PathBuf::from("test.rb"),
+            line,
+            column,
+            length: None,
+        };
+        let diagnostics = vec![Diagnostic::nil_receiver(
+            location(2, 1),
+            "upcase",
+            Some(location(1, 5)),
+        )];
+
+        let output = format_diagnostics_with_source(&diagnostics, "x = nil\nx.upcase");
+        assert!(output.contains("test.rb:2:1: error:"));
+        assert!(output.contains("note: nil introduced here (test.rb:1:5)"));
+    }
+
+    #[test]
+    fn test_format_suggestions() {
+        let diagnostics = vec![Diagnostic::undefined_method(
+            Location {
+                file: PathBuf::from("test.rb"),
+                line: 1,
+                column: 6,
+                length: Some(6),
+            },
+            "String",
+            "lenght",
+        )
+        .with_suggestions(vec!["length".to_string()])];
+
+        let output = format_diagnostics_with_source(&diagnostics, "name.lenght");
+        assert!(output.ends_with("^\n   help: did you mean `length`?"));
+        assert!(format_diagnostics(&diagnostics).contains("help: did you mean `length`?"));
+    }
 }
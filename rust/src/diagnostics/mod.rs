@@ -1,5 +1,8 @@
+pub mod baseline;
+pub mod catalog;
 pub mod diagnostic;
+pub mod did_you_mean;
 pub mod formatter;
 
 pub use diagnostic::{Diagnostic, DiagnosticLevel, Location};
-pub use formatter::format_diagnostics_with_file;
+pub use formatter::format_diagnostics_with_source;
@@ -4,13 +4,15 @@
 //! methods, type errors, and scopes during type inference.
 
 use crate::env::box_manager::BoxManager;
-use crate::env::method_registry::{MethodInfo, MethodRegistry};
+use crate::env::method_registry::{MethodInfo, MethodRegistry, Visibility};
+use crate::env::project_index::{IndexedInstanceVar, IndexedMethod, ProjectIndex};
 use crate::env::scope::{Scope, ScopeId, ScopeKind, ScopeManager};
 use crate::env::type_error::TypeError;
 use crate::env::vertex_manager::VertexManager;
 use crate::graph::{BoxId, BoxTrait, ChangeSet, EdgeUpdate, Source, Vertex, VertexId};
 use crate::source_map::SourceLocation;
 use crate::types::Type;
+use std::collections::{HashMap, HashSet};
 
 /// Global environment: core of the type inference engine
 ///
@@ -35,6 +37,79 @@ pub struct GlobalEnv {
 
     /// Scope management
     pub scope_manager: ScopeManager,
+
+    /// Constant vertices by qualified name (`MAX`, `Config::DEFAULTS`)
+    constants: HashMap<String, VertexId>,
+
+    /// Global variable vertices by name (`$stdout`)
+    globals: HashMap<String, VertexId>,
+
+    /// Instance variables by (class or module, name, singleton). Class-level
+    /// variables (assigned in the class body or a `def self.name`) belong to
+    /// the class object and are kept apart from the instances' variables
+    instance_vars: HashMap<(String, String, bool), InstanceVar>,
+
+    /// Modules defined in user code
+    modules: HashSet<String>,
+
+    /// Classes, modules and constants declared in RBS (`String`, `File::SEPARATOR`)
+    builtin_constants: HashSet<String>,
+
+    /// Every top-level constant is defined in user code or RBS: the whole
+    /// project is known and loads no gems or standard libraries
+    top_level_constants_known: bool,
+
+    /// Constant references and bare identifiers, checked once every
+    /// definition is known
+    references: Vec<Reference>,
+
+    /// Element reads (`pair[1]`) of each container vertex, run again when the
+    /// container is written
+    element_readers: HashMap<VertexId, Vec<BoxId>>,
+}
+
+/// A reference to a name that may be defined after it is used
+#[derive(Debug, Clone)]
+enum Reference {
+    /// `Usr`, `Config::TIMOUT`
+    Constant {
+        name: String,
+        /// Enclosing classes and modules, innermost first
+        nesting: Vec<String>,
+        location: Option<SourceLocation>,
+    },
+    /// Bare identifier (`nmae`), a method call on self
+    Identifier {
+        name: String,
+        self_type: Type,
+        location: Option<SourceLocation>,
+    },
+}
+
+/// Instance variable of a class or module, shared by all of its methods
+#[derive(Debug, Clone, Copy)]
+struct InstanceVar {
+    vtx: VertexId,
+    /// Assigned in `initialize` (the class body for a class-level variable),
+    /// so set before any other method runs
+    assigned_in_initialize: bool,
+    /// Read in a method before any assignment in that method
+    read_before_assignment: bool,
+    /// Already connected to its ancestors' variables and given its implicit nil
+    finished: bool,
+}
+
+/// Type of a built-in global variable
+fn builtin_global_type(name: &str) -> Option<Type> {
+    let io = || Type::Instance {
+        class_name: "IO".to_string(),
+    };
+    match name {
+        "$stdout" | "$stderr" | "$stdin" | "$>" => Some(io()),
+        "$PROGRAM_NAME" | "$0" => Some(Type::string()),
+        "$LOAD_PATH" | "$:" | "$LOADED_FEATURES" | "$\"" => Some(Type::array_of(Type::string())),
+        _ => None,
+    }
 }
 
 #[allow(dead_code)]
@@ -46,6 +121,14 @@ impl GlobalEnv {
             method_registry: MethodRegistry::new(),
             type_errors: Vec::new(),
             scope_manager: ScopeManager::new(),
+            constants: HashMap::new(),
+            globals: HashMap::new(),
+            instance_vars: HashMap::new(),
+            modules: HashSet::new(),
+            builtin_constants: HashSet::new(),
+            top_level_constants_known: false,
+            references: Vec::new(),
+            element_readers: HashMap::new(),
         }
     }
 
@@ -61,6 +144,11 @@ impl GlobalEnv {
         self.vertex_manager.new_source(ty)
     }
 
+    /// Create new Source (fixed type) that remembers where it was introduced
+    pub fn new_source_with_location(&mut self, ty: Type, location: SourceLocation) -> VertexId {
+        self.vertex_manager.new_source_with_location(ty, location)
+    }
+
     /// Get Vertex
     pub fn get_vertex(&self, id: VertexId) -> Option<&Vertex> {
         self.vertex_manager.get_vertex(id)
@@ -76,6 +164,72 @@ impl GlobalEnv {
         self.vertex_manager.add_edge(src, dst);
     }
 
+    /// Find the location that introduced `ty` into the given vertex
+    pub fn find_type_origin(&self, id: VertexId, ty: &Type) -> Option<SourceLocation> {
+        self.vertex_manager.find_type_origin(id, ty)
+    }
+
+    /// Get the element vertex of a container (values written with `[]=`)
+    pub fn element_vertex(&mut self, container: VertexId) -> VertexId {
+        self.vertex_manager.element_vertex(container)
+    }
+
+    /// Remember a `container[key] = value` write (with its symbol key, if static)
+    ///
+    /// Element reads of the container that already ran are run again.
+    pub fn record_element_write(
+        &mut self,
+        changes: &mut ChangeSet,
+        container: VertexId,
+        key: Option<String>,
+    ) {
+        let written = self.vertex_manager.record_element_write(container, key);
+        self.rerun_element_readers(changes, &written);
+    }
+
+    /// Pass an argument to a method parameter: the elements the method adds
+    /// to the parameter (`list << value`) are added to the argument
+    pub fn pass_elements(&mut self, changes: &mut ChangeSet, arg: VertexId, param: VertexId) {
+        let param_elem = self.element_vertex(param);
+        let arg_elem = self.element_vertex(arg);
+        changes.add_edge(param_elem, arg_elem);
+
+        let written = self.vertex_manager.forward_element_writes(param, arg);
+        self.rerun_element_readers(changes, &written);
+    }
+
+    /// Run the element reads of newly written containers again
+    fn rerun_element_readers(&self, changes: &mut ChangeSet, containers: &[VertexId]) {
+        for container in containers {
+            for &box_id in self.element_readers.get(container).into_iter().flatten() {
+                changes.reschedule(box_id);
+            }
+        }
+    }
+
+    /// Register a box reading the elements of a container
+    pub fn add_element_reader(&mut self, container: VertexId, box_id: BoxId) {
+        self.element_readers
+            .entry(container)
+            .or_default()
+            .push(box_id);
+    }
+
+    /// Check if a container was written with `[]=`
+    pub fn is_element_written(&self, container: VertexId) -> bool {
+        self.vertex_manager.is_element_written(container)
+    }
+
+    /// Check if a container was written at a key or position not known statically
+    pub fn is_written_at_any_key(&self, container: VertexId) -> bool {
+        self.vertex_manager.is_written_at_any_key(container)
+    }
+
+    /// Check if a symbol key was written to a container
+    pub fn has_written_key(&self, container: VertexId, key: &str) -> bool {
+        self.vertex_manager.has_written_key(container, key)
+    }
+
     /// For debugging: display types of all Vertices
     pub fn show_all(&self) -> String {
         self.vertex_manager.show_all()
@@ -162,6 +316,631 @@ impl GlobalEnv {
             .register_with_block(recv_ty, method_name, ret_ty, block_param_types);
     }
 
+    /// Register user-defined method with its return and yielded value vertices
+    pub fn register_user_method(
+        &mut self,
+        recv_ty: Type,
+        method_name: &str,
+        return_vtx: Option<VertexId>,
+        yield_vtxs: Vec<VertexId>,
+        param_vtxs: Vec<VertexId>,
+        visibility: Visibility,
+    ) {
+        self.method_registry.register_user_method(
+            recv_ty,
+            method_name,
+            return_vtx,
+            yield_vtxs,
+            param_vtxs,
+            visibility,
+        );
+    }
+
+    /// Change the visibility of a registered method (`private :foo`)
+    pub fn set_method_visibility(
+        &mut self,
+        recv_ty: &Type,
+        method_name: &str,
+        visibility: Visibility,
+    ) -> bool {
+        self.method_registry
+            .set_visibility(recv_ty, method_name, visibility)
+    }
+
+    /// Limit the positional arguments of a registered method
+    pub fn set_max_args(&mut self, recv_ty: &Type, method_name: &str, max_args: usize) {
+        self.method_registry
+            .set_max_args(recv_ty, method_name, max_args);
+    }
+
+    /// Register `new_name` as an alias of `old_name` (`alias`, `alias_method`)
+    pub fn alias_method(&mut self, recv_ty: Type, new_name: &str, old_name: &str) {
+        self.method_registry
+            .alias_method(recv_ty, new_name, old_name);
+    }
+
+    /// Check if a user class handles undefined methods with `method_missing`
+    pub fn has_method_missing(&self, class_name: &str) -> bool {
+        self.method_registry.has_method_missing(class_name)
+    }
+
+    /// Class that defines the method resolved for an instance of `class_name`
+    pub fn method_owner(&self, class_name: &str, method_name: &str) -> Option<String> {
+        self.method_registry.method_owner(class_name, method_name)
+    }
+
+    /// Check if `class_name` is `ancestor` or inherits from it
+    pub fn is_subclass_of(&self, class_name: &str, ancestor: &str) -> bool {
+        self.method_registry.is_subclass_of(class_name, ancestor)
+    }
+
+    /// Register a class defined in user code with its declared superclass
+    pub fn register_class(&mut self, class_name: &str, superclass: Option<String>) {
+        self.method_registry.register_class(class_name, superclass);
+    }
+
+    /// Resolve the method `super` calls from a method of `class_name`
+    pub fn resolve_super_method(
+        &self,
+        class_name: &str,
+        singleton: bool,
+        method_name: &str,
+    ) -> Option<&MethodInfo> {
+        self.method_registry
+            .resolve_super(class_name, singleton, method_name)
+    }
+
+    /// Mark the enclosing class or module (Object at the top level) as having
+    /// ancestors that cannot be followed: a mixin, or an expression superclass
+    pub fn mark_open_ancestry(&mut self) {
+        let name = self
+            .scope_manager
+            .current_namespace()
+            .unwrap_or_else(|| "Object".to_string());
+        self.method_registry.mark_open_ancestry(&name);
+    }
+
+    /// Check if every ancestor of a class is known
+    pub fn has_known_ancestry(&self, class_name: &str) -> bool {
+        self.method_registry.has_known_ancestry(class_name)
+    }
+
+    /// Check if every class method of a class is known
+    pub fn has_loaded_singleton_methods(&self, class_name: &str) -> bool {
+        self.method_registry
+            .has_loaded_singleton_methods(class_name)
+    }
+
+    /// Names of the methods resolvable for a receiver type
+    pub fn method_names(&self, recv_ty: &Type) -> Vec<String> {
+        self.method_registry.method_names(recv_ty)
+    }
+
+    // ===== Constants =====
+
+    /// Get the vertex of a constant, creating it on first use
+    pub fn constant_vertex(&mut self, name: &str) -> VertexId {
+        if let Some(&vtx) = self.constants.get(name) {
+            return vtx;
+        }
+        let vtx = self.vertex_manager.new_vertex();
+        self.constants.insert(name.to_string(), vtx);
+        vtx
+    }
+
+    /// Look up the vertex of a constant that has been assigned
+    pub fn lookup_constant(&self, name: &str) -> Option<VertexId> {
+        self.constants.get(name).copied()
+    }
+
+    // ===== Instance Variables =====
+
+    /// Get the instance variable of the enclosing class or module, creating
+    /// it on first use
+    ///
+    /// In the class body and in `def self.name`, self is the class, so the
+    /// class-level variable is returned. Returns None outside a class or module.
+    fn instance_var_entry(&mut self, name: &str) -> Option<&mut InstanceVar> {
+        let owner = self.scope_manager.current_namespace()?;
+        let singleton = self.scope_manager.in_singleton_context();
+        let vertex_manager = &mut self.vertex_manager;
+        Some(
+            self.instance_vars
+                .entry((owner, name.to_string(), singleton))
+                .or_insert_with(|| InstanceVar {
+                    vtx: vertex_manager.new_vertex(),
+                    assigned_in_initialize: false,
+                    read_before_assignment: false,
+                    finished: false,
+                }),
+        )
+    }
+
+    /// Record an instance variable assignment and return the variable's vertex
+    ///
+    /// Every assignment in any method of the class flows into one vertex.
+    pub fn write_instance_var(&mut self, name: &str, value_vtx: VertexId) -> Option<VertexId> {
+        let in_initialize = match self.scope_manager.current_method_name() {
+            Some(method_name) => method_name == "initialize",
+            // The class body runs before any class method
+            None => self.scope_manager.in_singleton_context(),
+        };
+        let ivar = self.instance_var_entry(name)?;
+        ivar.assigned_in_initialize |= in_initialize;
+        let ivar_vtx = ivar.vtx;
+
+        self.add_edge(value_vtx, ivar_vtx);
+        self.scope_manager
+            .set_instance_var_in_method(name.to_string(), ivar_vtx);
+        Some(ivar_vtx)
+    }
+
+    /// Record an instance variable read and return the variable's vertex
+    pub fn read_instance_var(&mut self, name: &str) -> Option<VertexId> {
+        let in_method = self.scope_manager.current_method_name().is_some();
+        let assigned_before = self
+            .scope_manager
+            .lookup_instance_var_in_method(name)
+            .is_some();
+        let ivar = self.instance_var_entry(name)?;
+        if in_method && !assigned_before {
+            ivar.read_before_assignment = true;
+        }
+        Some(ivar.vtx)
+    }
+
+    /// Complete instance variable types once every method has been installed
+    ///
+    /// A class sees the variables its superclasses assign to their
+    /// instances; class-level variables are not inherited. A variable read
+    /// before assignment that no `initialize` (own or inherited) assigns
+    /// may still be nil.
+    pub fn finish_instance_vars(&mut self) {
+        let keys: Vec<(String, String, bool)> = self
+            .instance_vars
+            .iter()
+            .filter(|(_, ivar)| !ivar.finished)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in keys {
+            let (owner, name, singleton) = &key;
+            let ivar = self.instance_vars[&key];
+            let mut initialized = ivar.assigned_in_initialize;
+
+            let ancestors = if *singleton {
+                Vec::new()
+            } else {
+                self.method_registry.ancestors(owner)
+            };
+            for ancestor in ancestors {
+                let inherited_key = (ancestor, name.clone(), false);
+                if let Some(inherited) = self.instance_vars.get(&inherited_key).copied() {
+                    initialized |= inherited.assigned_in_initialize;
+                    self.add_edge(inherited.vtx, ivar.vtx);
+                }
+            }
+
+            if ivar.read_before_assignment && !initialized {
+                let nil_vtx = self.new_source(Type::Nil);
+                self.add_edge(nil_vtx, ivar.vtx);
+            }
+
+            if let Some(ivar) = self.instance_vars.get_mut(&key) {
+                ivar.finished = true;
+            }
+        }
+    }
+
+    // ===== Constant and Identifier References =====
+
+    /// Register a module defined in user code
+    pub fn register_module(&mut self, module_name: &str) {
+        self.modules.insert(module_name.to_string());
+    }
+
+    /// Register a class, module or constant declared in RBS
+    pub fn register_builtin_constant(&mut self, name: &str) {
+        self.builtin_constants.insert(name.to_string());
+    }
+
+    /// Report top-level constants nothing defines, not only those missing
+    /// from a namespace of the project
+    pub fn mark_top_level_constants_known(&mut self) {
+        self.top_level_constants_known = true;
+    }
+
+    /// Record a constant reference in the current scope
+    pub fn record_constant_reference(&mut self, name: &str, location: Option<SourceLocation>) {
+        let nesting = self.scope_manager.namespace_nesting();
+        self.references.push(Reference::Constant {
+            name: name.to_string(),
+            nesting,
+            location,
+        });
+    }
+
+    /// Record a bare identifier that is not a local variable (`nmae`)
+    pub fn record_identifier(
+        &mut self,
+        name: &str,
+        self_type: Type,
+        location: Option<SourceLocation>,
+    ) {
+        self.references.push(Reference::Identifier {
+            name: name.to_string(),
+            self_type,
+            location,
+        });
+    }
+
+    /// Report the recorded constants and identifiers nothing defines
+    ///
+    /// Without the RBS declarations every built-in constant would look
+    /// undefined, so nothing is reported then.
+    pub fn finish_references(&mut self) {
+        let references = std::mem::take(&mut self.references);
+        if self.builtin_constants.is_empty() {
+            return;
+        }
+
+        for reference in references {
+            match reference {
+                Reference::Constant {
+                    name,
+                    nesting,
+                    location,
+                } => {
+                    if let Some(missing) = self.missing_constant(&name, &nesting) {
+                        self.type_errors
+                            .push(TypeError::uninitialized_constant(missing, location));
+                    }
+                }
+                Reference::Identifier {
+                    name,
+                    self_type,
+                    location,
+                } => {
+                    if self.is_undefined_identifier(&self_type, &name) {
+                        self.type_errors
+                            .push(TypeError::undefined_local(self_type, name, location));
+                    }
+                }
+            }
+        }
+    }
+
+    /// The prefix of a constant path that is certainly not defined, if any
+    ///
+    /// The first segment is looked up like Ruby does: in the enclosing
+    /// namespaces, the ancestors of the innermost class, then the top level.
+    /// Later segments are looked up in the namespace resolved so far. Unless
+    /// every top-level constant is known, a gem, a standard library or another
+    /// project file may define the first segment, so it is never reported.
+    fn missing_constant(&self, name: &str, nesting: &[String]) -> Option<String> {
+        let mut segments = name.split("::");
+        let first = segments.next()?;
+
+        let mut candidates: Vec<String> = nesting
+            .iter()
+            .map(|namespace| format!("{}::{}", namespace, first))
+            .collect();
+        if let Some(innermost) = nesting.first() {
+            candidates.extend(
+                self.method_registry
+                    .ancestors(innermost)
+                    .iter()
+                    .map(|ancestor| format!("{}::{}", ancestor, first)),
+            );
+        }
+        candidates.push(first.to_string());
+
+        let Some(mut resolved) = candidates
+            .into_iter()
+            .find(|candidate| self.is_constant_defined(candidate))
+        else {
+            if !self.top_level_constants_known {
+                return None;
+            }
+            // A superclass or mixin outside the project (e.g., from a gem) may define it
+            let inherits_unknown = nesting.iter().any(|namespace| {
+                self.method_registry.has_open_ancestry(namespace)
+                    || (self.method_registry.is_user_class(namespace)
+                        && !self.has_known_ancestry(namespace))
+            });
+            return (!inherits_unknown).then(|| first.to_string());
+        };
+
+        for segment in segments {
+            let qualified = format!("{}::{}", resolved, segment);
+            let inherited = self
+                .method_registry
+                .ancestors(&resolved)
+                .into_iter()
+                .map(|ancestor| format!("{}::{}", ancestor, segment))
+                .find(|candidate| self.is_constant_defined(candidate));
+
+            resolved = if self.is_constant_defined(&qualified) {
+                qualified
+            } else if let Some(inherited) = inherited {
+                inherited
+            } else if self.is_namespace(segment) {
+                // Classes and modules are known by their own name only
+                segment.to_string()
+            } else if self.has_known_constants(&resolved) {
+                return Some(qualified);
+            } else {
+                return None;
+            };
+        }
+
+        None
+    }
+
+    /// Short names of the constants, classes and modules defined directly in
+    /// a namespace (`None` for the top level), sorted
+    pub fn constant_names_in(&self, namespace: Option<&str>) -> Vec<String> {
+        let user_classes = self.method_registry.user_classes().map(|(name, _)| name);
+        let mut names: Vec<String> = self
+            .constants
+            .keys()
+            .chain(&self.builtin_constants)
+            .chain(&self.modules)
+            .chain(user_classes)
+            .filter_map(|name| match (namespace, name.rsplit_once("::")) {
+                (Some(namespace), Some((parent, short_name))) if parent == namespace => {
+                    Some(short_name.to_string())
+                }
+                (None, None) => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Check if a (qualified) constant, class or module is defined
+    fn is_constant_defined(&self, name: &str) -> bool {
+        self.constants.contains_key(name)
+            || self.is_namespace(name)
+            || self.builtin_constants.contains(name)
+    }
+
+    /// Check if a user class or module has this name
+    fn is_namespace(&self, name: &str) -> bool {
+        self.method_registry.is_user_class(name) || self.modules.contains(name)
+    }
+
+    /// Check if every constant of a namespace is known: a user module
+    /// without mixins, or a user class whose ancestors are all known
+    ///
+    /// Built-in classes are excluded, as libraries add constants to them.
+    fn has_known_constants(&self, namespace: &str) -> bool {
+        (self.modules.contains(namespace) && !self.method_registry.has_open_ancestry(namespace))
+            || (self.method_registry.is_user_class(namespace) && self.has_known_ancestry(namespace))
+    }
+
+    /// Check if a bare identifier is not a method of self
+    fn is_undefined_identifier(&self, self_type: &Type, name: &str) -> bool {
+        let Type::Instance { class_name } = self_type else {
+            return false;
+        };
+        self.has_known_ancestry(class_name)
+            && !self.has_method_missing(class_name)
+            && self.resolve_method(self_type, name).is_none()
+    }
+
+    // ===== Project Index =====
+
+    /// Collect the declarations of every installed file
+    ///
+    /// Call after the boxes have run, so the recorded types are complete.
+    pub fn project_index(&self) -> ProjectIndex {
+        let classes = self
+            .method_registry
+            .user_classes()
+            .map(|(name, superclass)| (name.clone(), superclass.clone()))
+            .collect();
+
+        let modules = self.modules.iter().cloned().collect();
+        let open_ancestry = self.method_registry.open_ancestry().cloned().collect();
+        let aliases = self
+            .method_registry
+            .aliases()
+            .map(|(recv_ty, new_name, old_name)| {
+                (recv_ty.clone(), new_name.clone(), old_name.clone())
+            })
+            .collect();
+
+        let methods = self
+            .method_registry
+            .user_methods()
+            .map(|(recv_ty, name, info)| IndexedMethod {
+                recv_ty: recv_ty.clone(),
+                name: name.clone(),
+                return_types: match info.return_vtx {
+                    Some(vtx) => self.vertex_types(vtx),
+                    None if info.return_type == Type::Bot => Vec::new(),
+                    None => vec![info.return_type.clone()],
+                },
+                yield_types: info
+                    .yield_vtxs
+                    .iter()
+                    .map(|&vtx| self.vertex_types(vtx))
+                    .collect(),
+                param_count: info.param_vtxs.len(),
+                written_params: info
+                    .param_vtxs
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &vtx)| self.is_element_written(vtx))
+                    .map(|(position, &vtx)| (position, self.element_types(vtx)))
+                    .collect(),
+                max_args: info.max_args,
+                visibility: info.visibility,
+            })
+            .collect();
+
+        let instance_vars = self
+            .instance_vars
+            .iter()
+            .map(|((owner, name, singleton), ivar)| IndexedInstanceVar {
+                owner: owner.clone(),
+                name: name.clone(),
+                singleton: *singleton,
+                types: self.vertex_types(ivar.vtx),
+                assigned_in_initialize: ivar.assigned_in_initialize,
+            })
+            .collect();
+
+        let constants = self
+            .constants
+            .iter()
+            .map(|(name, &vtx)| (name.clone(), self.vertex_types(vtx)))
+            .collect();
+
+        ProjectIndex {
+            classes,
+            modules,
+            open_ancestry,
+            aliases,
+            methods,
+            instance_vars,
+            constants,
+        }
+    }
+
+    /// Load the declarations of a project before installing one of its files
+    ///
+    /// Declarations the file makes itself are installed on top of these.
+    /// Instance variables are loaded finished: their types already include
+    /// the inherited ones and the implicit nil.
+    pub fn load_project_index(&mut self, index: &ProjectIndex) {
+        for (name, superclass) in &index.classes {
+            self.register_class(name, superclass.clone());
+        }
+        for module_name in &index.modules {
+            self.register_module(module_name);
+        }
+        for name in &index.open_ancestry {
+            self.method_registry.mark_open_ancestry(name);
+        }
+        for (recv_ty, new_name, old_name) in &index.aliases {
+            self.alias_method(recv_ty.clone(), new_name, old_name);
+        }
+
+        for method in &index.methods {
+            let return_vtx = self.typed_vertex(&method.return_types);
+            let yield_vtxs = method
+                .yield_types
+                .iter()
+                .map(|types| self.typed_vertex(types))
+                .collect();
+            let param_vtxs: Vec<VertexId> =
+                (0..method.param_count).map(|_| self.new_vertex()).collect();
+            // Arguments are widened as they were by the method's own writes
+            for (position, types) in &method.written_params {
+                let param_vtx = param_vtxs[*position];
+                self.vertex_manager.record_element_write(param_vtx, None);
+                let written_vtx = self.typed_vertex(types);
+                let elem_vtx = self.element_vertex(param_vtx);
+                self.add_edge(written_vtx, elem_vtx);
+            }
+            self.register_user_method(
+                method.recv_ty.clone(),
+                &method.name,
+                Some(return_vtx),
+                yield_vtxs,
+                param_vtxs,
+                method.visibility,
+            );
+            if let Some(max_args) = method.max_args {
+                self.set_max_args(&method.recv_ty, &method.name, max_args);
+            }
+        }
+
+        for ivar in &index.instance_vars {
+            let vtx = self.typed_vertex(&ivar.types);
+            self.instance_vars.insert(
+                (ivar.owner.clone(), ivar.name.clone(), ivar.singleton),
+                InstanceVar {
+                    vtx,
+                    assigned_in_initialize: ivar.assigned_in_initialize,
+                    read_before_assignment: false,
+                    finished: true,
+                },
+            );
+        }
+
+        for (name, types) in &index.constants {
+            let value_vtx = self.typed_vertex(types);
+            let constant_vtx = self.constant_vertex(name);
+            self.add_edge(value_vtx, constant_vtx);
+        }
+    }
+
+    /// Types written to the elements of a container
+    fn element_types(&self, container: VertexId) -> Vec<Type> {
+        self.vertex_manager
+            .find_element_vertex(container)
+            .map(|elem_vtx| self.vertex_types(elem_vtx))
+            .unwrap_or_default()
+    }
+
+    /// Types that have reached a vertex or source
+    fn vertex_types(&self, vtx: VertexId) -> Vec<Type> {
+        if let Some(source) = self.get_source(vtx) {
+            return vec![source.ty.clone()];
+        }
+        self.get_vertex(vtx)
+            .map(|vertex| vertex.types.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// New vertex holding the given types
+    fn typed_vertex(&mut self, types: &[Type]) -> VertexId {
+        let vtx = self.new_vertex();
+        for ty in types {
+            let src = self.new_source(ty.clone());
+            self.add_edge(src, vtx);
+        }
+        vtx
+    }
+
+    // ===== Class and Global Variables =====
+
+    /// Get the vertex of a class variable of the enclosing class or module,
+    /// creating it on first use
+    ///
+    /// All reads and writes of `@@name` in the class share the vertex. Returns
+    /// None outside a class or module.
+    pub fn class_var_vertex(&mut self, name: &str) -> Option<VertexId> {
+        if let Some(vtx) = self.scope_manager.lookup_class_var(name) {
+            return Some(vtx);
+        }
+        let vtx = self.vertex_manager.new_vertex();
+        self.scope_manager
+            .set_class_var(name.to_string(), vtx)
+            .then_some(vtx)
+    }
+
+    /// Get the vertex of a global variable, creating it on first use
+    ///
+    /// Built-in globals (`$stdout`, `$PROGRAM_NAME`, ...) start with their type.
+    pub fn global_var_vertex(&mut self, name: &str) -> VertexId {
+        if let Some(&vtx) = self.globals.get(name) {
+            return vtx;
+        }
+        let vtx = self.vertex_manager.new_vertex();
+        if let Some(ty) = builtin_global_type(name) {
+            let src = self.vertex_manager.new_source(ty);
+            self.add_edge(src, vtx);
+        }
+        self.globals.insert(name.to_string(), vtx);
+        vtx
+    }
+
     // ===== Type Errors =====
 
     /// Record a type error (undefined method)
@@ -175,6 +954,76 @@ impl GlobalEnv {
             .push(TypeError::new(receiver_type, method_name, location));
     }
 
+    /// Record a nil receiver error (method not defined on NilClass)
+    pub fn record_nil_receiver_error(
+        &mut self,
+        method_name: String,
+        location: Option<SourceLocation>,
+        nil_origin: Option<SourceLocation>,
+    ) {
+        self.type_errors
+            .push(TypeError::nil_receiver(method_name, location, nil_origin));
+    }
+
+    /// Record an unknown record key error (`record[:key]` with an unknown key)
+    pub fn record_unknown_record_key(
+        &mut self,
+        record_type: Type,
+        key: String,
+        location: Option<SourceLocation>,
+    ) {
+        self.type_errors
+            .push(TypeError::unknown_record_key(record_type, key, location));
+    }
+
+    /// Record a private/protected method call error
+    pub fn record_visibility_violation(
+        &mut self,
+        receiver_type: Type,
+        method_name: String,
+        visibility: Visibility,
+        location: Option<SourceLocation>,
+    ) {
+        self.type_errors.push(TypeError::visibility_violation(
+            receiver_type,
+            method_name,
+            visibility,
+            location,
+        ));
+    }
+
+    /// Record a call with more positional arguments than the method accepts
+    pub fn record_wrong_arity(
+        &mut self,
+        receiver_type: Type,
+        method_name: String,
+        given: usize,
+        max: usize,
+        location: Option<SourceLocation>,
+    ) {
+        self.type_errors.push(TypeError::wrong_arity(
+            receiver_type,
+            method_name,
+            given,
+            max,
+            location,
+        ));
+    }
+
+    /// Record a missing super method error
+    pub fn record_no_super_method(
+        &mut self,
+        class_type: Type,
+        method_name: String,
+        location: Option<SourceLocation>,
+    ) {
+        self.type_errors.push(TypeError::no_super_method(
+            class_type,
+            method_name,
+            location,
+        ));
+    }
+
     // ===== Scope Management =====
 
     /// Enter a class scope
@@ -195,7 +1044,7 @@ impl GlobalEnv {
     }
 
     /// Enter a method scope
-    pub fn enter_method(&mut self, name: String) -> ScopeId {
+    pub fn enter_method(&mut self, name: String, singleton: bool) -> ScopeId {
         // Look for class or module context
         let receiver_type = self
             .scope_manager
@@ -204,6 +1053,7 @@ impl GlobalEnv {
         let scope_id = self.scope_manager.new_scope(ScopeKind::Method {
             name,
             receiver_type,
+            singleton,
         });
         self.scope_manager.enter_scope(scope_id);
         scope_id
@@ -1,19 +1,87 @@
 //! Method registration and resolution
 
+use crate::graph::VertexId;
 use crate::types::Type;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Maximum superclass chain length followed during resolution (guards against cycles)
+const MAX_ANCESTOR_DEPTH: usize = 64;
+
+/// Methods that Ruby always makes private
+const IMPLICITLY_PRIVATE_METHODS: &[&str] = &[
+    "initialize",
+    "initialize_copy",
+    "initialize_clone",
+    "initialize_dup",
+    "respond_to_missing?",
+];
+
+/// Methods that make a class respond to calls of undefined methods
+const DYNAMIC_DISPATCH_METHODS: &[&str] = &["method_missing", "respond_to_missing?"];
+
+/// Method visibility (`public`, `protected`, `private`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Protected,
+    Private,
+}
+
+impl Visibility {
+    /// Parse a visibility keyword (`public`, `protected`, `private`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "public" => Some(Visibility::Public),
+            "protected" => Some(Visibility::Protected),
+            "private" => Some(Visibility::Private),
+            _ => None,
+        }
+    }
+
+    /// Visibility keyword as written in Ruby
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Protected => "protected",
+            Visibility::Private => "private",
+        }
+    }
+}
 
 /// Method information
 #[derive(Debug, Clone)]
 pub struct MethodInfo {
     pub return_type: Type,
     pub block_param_types: Option<Vec<Type>>,
+    /// Return value vertex (user-defined methods; takes precedence over return_type)
+    pub return_vtx: Option<VertexId>,
+    /// Yielded value vertices, one per block parameter (user-defined methods)
+    pub yield_vtxs: Vec<VertexId>,
+    /// Positional parameter vertices (user-defined methods)
+    pub param_vtxs: Vec<VertexId>,
+    /// Maximum number of positional arguments, when it is fixed
+    /// (Struct and Data constructors)
+    pub max_args: Option<usize>,
+    pub visibility: Visibility,
+    /// Defined in user code (rather than loaded from RBS)
+    pub user_defined: bool,
 }
 
 /// Registry for method definitions
 #[derive(Debug, Default)]
 pub struct MethodRegistry {
     methods: HashMap<(Type, String), MethodInfo>,
+    /// Classes defined in user code, with their declared superclass
+    classes: HashMap<String, Option<String>>,
+    /// Classes and modules whose ancestors cannot be followed: they include,
+    /// extend or prepend a module, or inherit from an expression
+    open_ancestry: HashSet<String>,
+    /// Aliases by receiver type and new name, with the original name.
+    /// Resolved on lookup, so the original may be defined later or elsewhere
+    aliases: HashMap<(Type, String), String>,
 }
 
 impl MethodRegistry {
@@ -21,7 +89,127 @@ impl MethodRegistry {
     pub fn new() -> Self {
         Self {
             methods: HashMap::new(),
+            classes: HashMap::new(),
+            open_ancestry: HashSet::new(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// Register a class defined in user code
+    ///
+    /// The first declared superclass wins (`class Admin` may be reopened without one).
+    pub fn register_class(&mut self, class_name: &str, superclass: Option<String>) {
+        let entry = self.classes.entry(class_name.to_string()).or_insert(None);
+        if entry.is_none() {
+            *entry = superclass;
+        }
+    }
+
+    /// Mark a class or module whose ancestors cannot all be followed
+    pub fn mark_open_ancestry(&mut self, name: &str) {
+        self.open_ancestry.insert(name.to_string());
+    }
+
+    /// Check if a class or module includes, extends or prepends a module,
+    /// or inherits from an expression
+    pub fn has_open_ancestry(&self, name: &str) -> bool {
+        self.open_ancestry.contains(name)
+    }
+
+    /// Classes and modules whose ancestors cannot all be followed
+    pub fn open_ancestry(&self) -> impl Iterator<Item = &String> {
+        self.open_ancestry.iter()
+    }
+
+    /// Classes defined in user code, with their declared superclass
+    pub fn user_classes(&self) -> impl Iterator<Item = (&String, &Option<String>)> {
+        self.classes.iter()
+    }
+
+    /// Check if a class is defined in user code
+    pub fn is_user_class(&self, class_name: &str) -> bool {
+        self.classes.contains_key(class_name)
+    }
+
+    /// Methods defined in user code, by receiver type and name
+    pub fn user_methods(&self) -> impl Iterator<Item = (&Type, &String, &MethodInfo)> {
+        self.methods
+            .iter()
+            .filter(|(_, info)| info.user_defined)
+            .map(|((recv_ty, name), info)| (recv_ty, name, info))
+    }
+
+    /// Superclass of a class: the declared one, Object for user classes
+    /// without one, and BasicObject for Object
+    pub fn superclass_of(&self, class_name: &str) -> Option<String> {
+        match self.classes.get(class_name) {
+            Some(Some(superclass)) => Some(superclass.clone()),
+            Some(None) if class_name != "Object" && class_name != "BasicObject" => {
+                Some("Object".to_string())
+            }
+            _ if class_name == "Object" => Some("BasicObject".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Superclasses of a class, nearest first
+    pub fn ancestors(&self, class_name: &str) -> Vec<String> {
+        let mut ancestors = Vec::new();
+        let mut current = self.superclass_of(class_name);
+        while let Some(class_name) = current {
+            if ancestors.len() >= MAX_ANCESTOR_DEPTH {
+                break;
+            }
+            current = self.superclass_of(&class_name);
+            ancestors.push(class_name);
+        }
+        ancestors
+    }
+
+    /// Check if every ancestor of a class is known (user classes up to Object)
+    ///
+    /// A missing method can only be reported when no unknown (e.g., gem)
+    /// superclass or mixin could define it.
+    pub fn has_known_ancestry(&self, class_name: &str) -> bool {
+        let mut current = class_name.to_string();
+        for _ in 0..MAX_ANCESTOR_DEPTH {
+            if self.open_ancestry.contains(&current) {
+                return false;
+            }
+            if current == "Object" || current == "BasicObject" {
+                return true;
+            }
+            if !self.classes.contains_key(&current) {
+                return false;
+            }
+            match self.superclass_of(&current) {
+                Some(superclass) => current = superclass,
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// Check if every class method of a class is known: the class and each
+    /// of its ancestors is a user class, or has class methods loaded
+    ///
+    /// RBS class methods are not loaded, so e.g. `Point.members` for a
+    /// Struct subclass may be defined.
+    pub fn has_loaded_singleton_methods(&self, class_name: &str) -> bool {
+        if !self.has_known_ancestry(class_name) {
+            return false;
         }
+        std::iter::once(class_name.to_string())
+            .chain(self.ancestors(class_name))
+            .all(|name| self.is_user_class(&name) || self.has_builtin_singleton_methods(&name))
+    }
+
+    /// Check if class methods of a class were loaded (rather than defined in user code)
+    fn has_builtin_singleton_methods(&self, class_name: &str) -> bool {
+        self.methods.iter().any(|((recv_ty, _), info)| {
+            !info.user_defined
+                && matches!(recv_ty, Type::Singleton { class_name: name } if name == class_name)
+        })
     }
 
     /// Register a method for a receiver type
@@ -42,23 +230,202 @@ impl MethodRegistry {
             MethodInfo {
                 return_type: ret_ty,
                 block_param_types,
+                return_vtx: None,
+                yield_vtxs: Vec::new(),
+                param_vtxs: Vec::new(),
+                max_args: None,
+                visibility: Visibility::Public,
+                user_defined: false,
+            },
+        );
+    }
+
+    /// Register a user-defined method
+    ///
+    /// Its return value, yielded values and parameters are vertices of the
+    /// method body, so callers see every type that flows into them.
+    /// `initialize` and the other hook methods are always private.
+    pub fn register_user_method(
+        &mut self,
+        recv_ty: Type,
+        method_name: &str,
+        return_vtx: Option<VertexId>,
+        yield_vtxs: Vec<VertexId>,
+        param_vtxs: Vec<VertexId>,
+        visibility: Visibility,
+    ) {
+        let visibility = if IMPLICITLY_PRIVATE_METHODS.contains(&method_name) {
+            Visibility::Private
+        } else {
+            visibility
+        };
+        self.methods.insert(
+            (recv_ty, method_name.to_string()),
+            MethodInfo {
+                return_type: Type::Bot,
+                block_param_types: None,
+                return_vtx,
+                yield_vtxs,
+                param_vtxs,
+                max_args: None,
+                visibility,
+                user_defined: true,
             },
         );
     }
 
+    /// Change the visibility of a method registered for exactly this receiver type
+    ///
+    /// An alias is resolved now and becomes a method of its own. Returns false
+    /// when no such method is registered (e.g., `private :foo` before
+    /// `def foo`, or a method inherited from a superclass).
+    pub fn set_visibility(
+        &mut self,
+        recv_ty: &Type,
+        method_name: &str,
+        visibility: Visibility,
+    ) -> bool {
+        let key = (recv_ty.clone(), method_name.to_string());
+        if !self.methods.contains_key(&key) && self.aliases.contains_key(&key) {
+            let Some(info) = self.resolve(recv_ty, method_name).cloned() else {
+                return false;
+            };
+            self.aliases.remove(&key);
+            self.methods.insert(
+                key.clone(),
+                MethodInfo {
+                    user_defined: true,
+                    ..info
+                },
+            );
+        }
+        match self.methods.get_mut(&key) {
+            Some(info) => {
+                info.visibility = visibility;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Limit the positional arguments of a method registered for exactly
+    /// this receiver type
+    pub fn set_max_args(&mut self, recv_ty: &Type, method_name: &str, max_args: usize) {
+        if let Some(info) = self
+            .methods
+            .get_mut(&(recv_ty.clone(), method_name.to_string()))
+        {
+            info.max_args = Some(max_args);
+        }
+    }
+
+    /// Register `new_name` as an alias of `old_name` for a receiver type
+    ///
+    /// The alias resolves to whatever `old_name` resolves to when it is looked
+    /// up, keeping the original's return value, parameters and visibility.
+    pub fn alias_method(&mut self, recv_ty: Type, new_name: &str, old_name: &str) {
+        self.aliases
+            .insert((recv_ty, new_name.to_string()), old_name.to_string());
+    }
+
+    /// Aliases: receiver type, new name and original name
+    pub fn aliases(&self) -> impl Iterator<Item = (&Type, &String, &String)> {
+        self.aliases
+            .iter()
+            .map(|((recv_ty, new_name), old_name)| (recv_ty, new_name, old_name))
+    }
+
+    /// Check if a user class or one of its user-defined ancestors defines
+    /// `method_missing` or `respond_to_missing?`
+    ///
+    /// Built-in classes are not considered: BasicObject#method_missing from
+    /// RBS is inherited by every class.
+    pub fn has_method_missing(&self, class_name: &str) -> bool {
+        let mut current = Some(class_name.to_string());
+        for _ in 0..MAX_ANCESTOR_DEPTH {
+            let Some(class_name) = current else {
+                return false;
+            };
+            if !self.classes.contains_key(&class_name) {
+                return false;
+            }
+            let ty = Type::Instance {
+                class_name: class_name.clone(),
+            };
+            if DYNAMIC_DISPATCH_METHODS
+                .iter()
+                .any(|name| self.methods.contains_key(&(ty.clone(), name.to_string())))
+            {
+                return true;
+            }
+            current = self.superclass_of(&class_name);
+        }
+        false
+    }
+
+    /// Class that defines the method resolved for an instance of `class_name`
+    pub fn method_owner(&self, class_name: &str, method_name: &str) -> Option<String> {
+        let mut current = Some(class_name.to_string());
+        for _ in 0..MAX_ANCESTOR_DEPTH {
+            let class_name = current?;
+            let ty = Type::Instance {
+                class_name: class_name.clone(),
+            };
+            let key = (ty, method_name.to_string());
+            if self.methods.contains_key(&key) || self.aliases.contains_key(&key) {
+                return Some(class_name);
+            }
+            current = self.superclass_of(&class_name);
+        }
+        None
+    }
+
+    /// Check if `class_name` is `ancestor` or inherits from it
+    pub fn is_subclass_of(&self, class_name: &str, ancestor: &str) -> bool {
+        let mut current = Some(class_name.to_string());
+        for _ in 0..MAX_ANCESTOR_DEPTH {
+            match current {
+                Some(class_name) if class_name == ancestor => return true,
+                Some(class_name) => current = self.superclass_of(&class_name),
+                None => return false,
+            }
+        }
+        false
+    }
+
     /// Resolve a method for a receiver type
     ///
     /// For generic types like `Array[Integer]`, first tries exact match,
     /// then falls back to base class match (`Array`).
     pub fn resolve(&self, recv_ty: &Type, method_name: &str) -> Option<&MethodInfo> {
+        self.resolve_at_depth(recv_ty, method_name, 0)
+    }
+
+    /// `resolve`, `depth` aliases deep
+    fn resolve_at_depth(
+        &self,
+        recv_ty: &Type,
+        method_name: &str,
+        depth: usize,
+    ) -> Option<&MethodInfo> {
         // First, try exact match
-        if let Some(info) = self
-            .methods
-            .get(&(recv_ty.clone(), method_name.to_string()))
-        {
+        if let Some(info) = self.own_method(recv_ty, method_name, depth) {
             return Some(info);
         }
 
+        // nil is the singleton instance of NilClass
+        if let Type::Nil = recv_ty {
+            let nil_class = Type::Instance {
+                class_name: "NilClass".to_string(),
+            };
+            return self.methods.get(&(nil_class, method_name.to_string()));
+        }
+
+        // Records and tuples are Hashes and Arrays with a known shape
+        if let Type::Record { .. } | Type::Tuple(_) = recv_ty {
+            return self.resolve_at_depth(&recv_ty.widen(), method_name, depth);
+        }
+
         // For generic types, fall back to base class
         if let Type::Generic { class_name, .. } = recv_ty {
             let base_type = Type::Instance {
@@ -67,6 +434,126 @@ impl MethodRegistry {
             return self.methods.get(&(base_type, method_name.to_string()));
         }
 
+        // Inherited methods: walk the superclass chain
+        match recv_ty {
+            Type::Instance { class_name } | Type::Singleton { class_name } => {
+                self.resolve_in_ancestors(recv_ty, class_name, method_name, depth)
+            }
+            _ => None,
+        }
+    }
+
+    /// Method registered, or aliased, for exactly this receiver type
+    fn own_method(&self, recv_ty: &Type, method_name: &str, depth: usize) -> Option<&MethodInfo> {
+        let key = (recv_ty.clone(), method_name.to_string());
+        if let Some(info) = self.methods.get(&key) {
+            return Some(info);
+        }
+        let old_name = self.aliases.get(&key)?;
+        // Guards against alias cycles (`alias a b` and `alias b a`)
+        if depth >= MAX_ANCESTOR_DEPTH {
+            return None;
+        }
+        self.resolve_at_depth(recv_ty, old_name, depth + 1)
+    }
+
+    /// Resolve the method `super` calls from a method of `class_name`
+    ///
+    /// Lookup starts at the superclass; `singleton` is true for `def self.foo`.
+    pub fn resolve_super(
+        &self,
+        class_name: &str,
+        singleton: bool,
+        method_name: &str,
+    ) -> Option<&MethodInfo> {
+        let recv_ty = if singleton {
+            Type::Singleton {
+                class_name: class_name.to_string(),
+            }
+        } else {
+            Type::Instance {
+                class_name: class_name.to_string(),
+            }
+        };
+        self.resolve_in_ancestors(&recv_ty, class_name, method_name, 0)
+    }
+
+    /// Names of the methods `resolve` finds for a receiver type, sorted
+    ///
+    /// Used for "Did you mean?" suggestions.
+    pub fn method_names(&self, recv_ty: &Type) -> Vec<String> {
+        let lookup_types = self.lookup_types(recv_ty);
+        let mut names: Vec<String> = self
+            .methods
+            .keys()
+            .chain(self.aliases.keys())
+            .filter(|(ty, _)| lookup_types.contains(ty))
+            .map(|(_, name)| name.clone())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Receiver types `resolve` looks methods up in, in order
+    fn lookup_types(&self, recv_ty: &Type) -> Vec<Type> {
+        match recv_ty {
+            Type::Nil => vec![
+                Type::Nil,
+                Type::Instance {
+                    class_name: "NilClass".to_string(),
+                },
+            ],
+            Type::Record { .. } | Type::Tuple(_) => self.lookup_types(&recv_ty.widen()),
+            Type::Generic { class_name, .. } => vec![
+                recv_ty.clone(),
+                Type::Instance {
+                    class_name: class_name.clone(),
+                },
+            ],
+            Type::Instance { class_name } | Type::Singleton { class_name } => {
+                let mut types = vec![recv_ty.clone()];
+                types.extend(self.ancestors(class_name).into_iter().map(
+                    |ancestor| match recv_ty {
+                        Type::Singleton { .. } => Type::Singleton {
+                            class_name: ancestor,
+                        },
+                        _ => Type::Instance {
+                            class_name: ancestor,
+                        },
+                    },
+                ));
+                types
+            }
+            _ => vec![recv_ty.clone()],
+        }
+    }
+
+    /// Look a method up in the superclasses of `class_name`, keeping the
+    /// instance/singleton kind of `recv_ty`
+    fn resolve_in_ancestors(
+        &self,
+        recv_ty: &Type,
+        class_name: &str,
+        method_name: &str,
+        depth: usize,
+    ) -> Option<&MethodInfo> {
+        let mut current = self.superclass_of(class_name);
+        for _ in 0..MAX_ANCESTOR_DEPTH {
+            let class_name = current?;
+            let ancestor_ty = match recv_ty {
+                Type::Singleton { .. } => Type::Singleton {
+                    class_name: class_name.clone(),
+                },
+                _ => Type::Instance {
+                    class_name: class_name.clone(),
+                },
+            };
+            if let Some(info) = self.own_method(&ancestor_ty, method_name, depth) {
+                return Some(info);
+            }
+            current = self.superclass_of(&class_name);
+        }
         None
     }
 }
@@ -86,9 +573,237 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_resolve_nil_via_nil_class() {
+        let mut registry = MethodRegistry::new();
+        let nil_class = Type::Instance {
+            class_name: "NilClass".to_string(),
+        };
+        registry.register(nil_class, "to_s", Type::string());
+
+        assert!(registry.resolve(&Type::Nil, "to_s").is_some());
+        assert!(registry.resolve(&Type::Nil, "upcase").is_none());
+    }
+
     #[test]
     fn test_resolve_not_found() {
         let registry = MethodRegistry::new();
         assert!(registry.resolve(&Type::string(), "unknown").is_none());
     }
+
+    #[test]
+    fn test_register_user_method() {
+        let mut registry = MethodRegistry::new();
+        let user = Type::Instance {
+            class_name: "User".to_string(),
+        };
+        registry.register_user_method(
+            user.clone(),
+            "each_name",
+            Some(VertexId(1)),
+            vec![VertexId(2)],
+            Vec::new(),
+            Visibility::Public,
+        );
+
+        let info = registry.resolve(&user, "each_name").unwrap();
+        assert_eq!(info.return_vtx, Some(VertexId(1)));
+        assert_eq!(info.yield_vtxs, vec![VertexId(2)]);
+        assert!(info.block_param_types.is_none());
+    }
+
+    #[test]
+    fn test_resolve_inherited_method() {
+        let mut registry = MethodRegistry::new();
+        registry.register_class("User", None);
+        registry.register_class("Admin", Some("User".to_string()));
+        let user = Type::Instance {
+            class_name: "User".to_string(),
+        };
+        let admin = Type::Instance {
+            class_name: "Admin".to_string(),
+        };
+        registry.register(user, "name", Type::string());
+        registry.register(admin.clone(), "id", Type::integer());
+
+        assert_eq!(
+            registry.resolve(&admin, "name").unwrap().return_type,
+            Type::string()
+        );
+        assert_eq!(
+            registry
+                .resolve_super("Admin", false, "name")
+                .unwrap()
+                .return_type,
+            Type::string()
+        );
+        assert!(registry.resolve_super("Admin", false, "id").is_none());
+    }
+
+    #[test]
+    fn test_method_visibility() {
+        let mut registry = MethodRegistry::new();
+        let user = Type::Instance {
+            class_name: "User".to_string(),
+        };
+        registry.register_user_method(
+            user.clone(),
+            "initialize",
+            None,
+            Vec::new(),
+            Vec::new(),
+            Visibility::Public,
+        );
+        registry.register(user.clone(), "secret", Type::string());
+
+        assert_eq!(
+            registry.resolve(&user, "initialize").unwrap().visibility,
+            Visibility::Private
+        );
+        assert_eq!(
+            registry.resolve(&user, "secret").unwrap().visibility,
+            Visibility::Public
+        );
+
+        assert!(registry.set_visibility(&user, "secret", Visibility::Protected));
+        assert!(!registry.set_visibility(&user, "missing", Visibility::Private));
+        assert_eq!(
+            registry.resolve(&user, "secret").unwrap().visibility,
+            Visibility::Protected
+        );
+    }
+
+    #[test]
+    fn test_method_owner_and_subclass() {
+        let mut registry = MethodRegistry::new();
+        registry.register_class("User", None);
+        registry.register_class("Admin", Some("User".to_string()));
+        let user = Type::Instance {
+            class_name: "User".to_string(),
+        };
+        registry.register(user, "secret", Type::string());
+
+        assert_eq!(
+            registry.method_owner("Admin", "secret"),
+            Some("User".to_string())
+        );
+        assert_eq!(registry.method_owner("Admin", "missing"), None);
+        assert!(registry.is_subclass_of("Admin", "User"));
+        assert!(registry.is_subclass_of("User", "User"));
+        assert!(!registry.is_subclass_of("User", "Admin"));
+    }
+
+    #[test]
+    fn test_alias_method() {
+        let mut registry = MethodRegistry::new();
+        registry.register_class("User", None);
+        registry.register_class("Admin", Some("User".to_string()));
+        let user = Type::Instance {
+            class_name: "User".to_string(),
+        };
+        let admin = Type::Instance {
+            class_name: "Admin".to_string(),
+        };
+        registry.register(user, "name", Type::string());
+
+        // Aliasing an inherited method defines the alias on the subclass
+        registry.alias_method(admin.clone(), "full_name", "name");
+        registry.alias_method(admin.clone(), "nick", "missing");
+        assert_eq!(
+            registry.resolve(&admin, "full_name").unwrap().return_type,
+            Type::string()
+        );
+        assert!(registry.resolve(&admin, "nick").is_none());
+
+        // Resolved on lookup: the original may be defined after the alias
+        registry.register(admin.clone(), "missing", Type::integer());
+        assert_eq!(
+            registry.resolve(&admin, "nick").unwrap().return_type,
+            Type::integer()
+        );
+
+        // Cycles resolve to nothing
+        registry.alias_method(admin.clone(), "a", "b");
+        registry.alias_method(admin.clone(), "b", "a");
+        assert!(registry.resolve(&admin, "a").is_none());
+    }
+
+    #[test]
+    fn test_has_method_missing() {
+        let mut registry = MethodRegistry::new();
+        registry.register_class("Proxy", None);
+        registry.register_class("RemoteProxy", Some("Proxy".to_string()));
+        registry.register_class("Plain", None);
+        let proxy = Type::Instance {
+            class_name: "Proxy".to_string(),
+        };
+        registry.register(proxy, "method_missing", Type::Bot);
+        registry.register(Type::string(), "method_missing", Type::Bot);
+
+        assert!(registry.has_method_missing("Proxy"));
+        assert!(registry.has_method_missing("RemoteProxy"));
+        assert!(!registry.has_method_missing("Plain"));
+        // Built-in classes inherit BasicObject#method_missing from RBS
+        assert!(!registry.has_method_missing("String"));
+    }
+
+    #[test]
+    fn test_known_ancestry() {
+        let mut registry = MethodRegistry::new();
+        registry.register_class("User", None);
+        registry.register_class("Admin", Some("User".to_string()));
+        registry.register_class("Model", Some("ActiveRecord::Base".to_string()));
+
+        assert_eq!(registry.superclass_of("User"), Some("Object".to_string()));
+        assert_eq!(
+            registry.ancestors("Admin"),
+            vec!["User", "Object", "BasicObject"]
+        );
+        assert!(registry.has_known_ancestry("Admin"));
+        assert!(!registry.has_known_ancestry("Model"));
+        assert!(!registry.has_known_ancestry("String"));
+
+        // include Auditable
+        registry.mark_open_ancestry("User");
+        assert!(!registry.has_known_ancestry("User"));
+        assert!(!registry.has_known_ancestry("Admin"));
+    }
+
+    #[test]
+    fn test_method_names_include_ancestors() {
+        let mut registry = MethodRegistry::new();
+        registry.register_class("User", None);
+        registry.register_class("Admin", Some("User".to_string()));
+        let instance = |class_name: &str| Type::Instance {
+            class_name: class_name.to_string(),
+        };
+        registry.register(instance("Object"), "to_s", Type::string());
+        registry.register(instance("User"), "name", Type::string());
+        registry.register(instance("Admin"), "permissions", Type::array());
+        let user_singleton = Type::Singleton {
+            class_name: "User".to_string(),
+        };
+        registry.register(user_singleton, "find", instance("User"));
+
+        assert_eq!(
+            registry.method_names(&instance("Admin")),
+            vec!["name", "permissions", "to_s"]
+        );
+        assert!(registry.method_names(&Type::string()).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_record_and_tuple_via_base_class() {
+        let mut registry = MethodRegistry::new();
+        registry.register(Type::hash(), "keys", Type::array());
+        registry.register(Type::array(), "first", Type::Bot);
+
+        let record = Type::Record {
+            fields: vec![("name".to_string(), Type::string())],
+        };
+        assert!(registry.resolve(&record, "keys").is_some());
+
+        let tuple = Type::Tuple(vec![Type::integer(), Type::string()]);
+        assert!(registry.resolve(&tuple, "first").is_some());
+    }
 }
@@ -7,6 +7,7 @@ pub mod box_manager;
 pub mod global_env;
 pub mod local_env;
 pub mod method_registry;
+pub mod project_index;
 pub mod scope;
 pub mod type_error;
 pub mod vertex_manager;
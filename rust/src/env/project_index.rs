@@ -0,0 +1,59 @@
+//! Project index: declarations shared by the files of a project
+//!
+//! A project is analyzed in two phases. First every file is installed into
+//! one environment and its declarations (classes, methods, instance
+//! variables, constants) are collected here with the types inferred for
+//! them. Then each file is checked on its own against the index, so a class
+//! reopened in another file, or a monkey patch in `core_ext/`, resolves.
+
+use crate::env::method_registry::Visibility;
+use crate::types::Type;
+
+/// Declarations collected from every file of a project
+#[derive(Debug, Clone, Default)]
+pub struct ProjectIndex {
+    /// User classes with their declared superclass
+    pub classes: Vec<(String, Option<String>)>,
+    /// User modules
+    pub modules: Vec<String>,
+    /// Classes and modules whose ancestors cannot all be followed (mixins,
+    /// expression superclasses)
+    pub open_ancestry: Vec<String>,
+    pub methods: Vec<IndexedMethod>,
+    /// Method aliases: receiver type, new name and original name
+    pub aliases: Vec<(Type, String, String)>,
+    pub instance_vars: Vec<IndexedInstanceVar>,
+    /// Constants by qualified name, with their value types
+    pub constants: Vec<(String, Vec<Type>)>,
+}
+
+/// A user-defined method
+#[derive(Debug, Clone)]
+pub struct IndexedMethod {
+    pub recv_ty: Type,
+    pub name: String,
+    pub return_types: Vec<Type>,
+    /// Yielded types, one entry per block parameter
+    pub yield_types: Vec<Vec<Type>>,
+    /// Number of positional parameters
+    pub param_count: usize,
+    /// Parameters whose elements the method writes (`list << value`), by
+    /// position, with the types written
+    pub written_params: Vec<(usize, Vec<Type>)>,
+    /// Maximum number of positional arguments, when it is fixed
+    pub max_args: Option<usize>,
+    pub visibility: Visibility,
+}
+
+/// An instance variable of a class or module
+#[derive(Debug, Clone)]
+pub struct IndexedInstanceVar {
+    pub owner: String,
+    pub name: String,
+    /// Class-level variable (assigned in the class body or a `def self.name`)
+    pub singleton: bool,
+    pub types: Vec<Type>,
+    /// Assigned in `initialize` (the class body for a class-level variable),
+    /// so never read before assignment
+    pub assigned_in_initialize: bool,
+}
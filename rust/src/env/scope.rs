@@ -1,3 +1,4 @@
+use crate::env::method_registry::Visibility;
 use crate::graph::VertexId;
 use std::collections::HashMap;
 
@@ -20,6 +21,7 @@ pub enum ScopeKind {
     Method {
         name: String,
         receiver_type: Option<String>, // Receiver class/module name
+        singleton: bool,               // `def self.name`: self is the class
     },
     Block,
 }
@@ -35,11 +37,15 @@ pub struct Scope {
     /// Local variables
     pub local_vars: HashMap<String, VertexId>,
 
-    /// Instance variables (class/module scope only)
+    /// Instance variables (class/module scope; in a method scope, the ones
+    /// assigned so far in the method)
     pub instance_vars: HashMap<String, VertexId>,
 
-    /// Class variables (class scope only)
+    /// Class variables (class/module scope only)
     pub class_vars: HashMap<String, VertexId>,
+
+    /// Visibility of methods defined next in this scope (set by a bare `private`)
+    pub default_visibility: Visibility,
 }
 
 #[allow(dead_code)]
@@ -52,6 +58,7 @@ impl Scope {
             local_vars: HashMap::new(),
             instance_vars: HashMap::new(),
             class_vars: HashMap::new(),
+            default_visibility: Visibility::Public,
         }
     }
 
@@ -227,6 +234,140 @@ impl ScopeManager {
         None
     }
 
+    /// Innermost enclosing class or module name (the namespace of constants)
+    pub fn current_namespace(&self) -> Option<String> {
+        self.enclosing_namespace_scope().and_then(|scope_id| {
+            match &self.scopes.get(&scope_id)?.kind {
+                ScopeKind::Class { name, .. } | ScopeKind::Module { name } => Some(name.clone()),
+                _ => None,
+            }
+        })
+    }
+
+    /// Enclosing class and module names, innermost first (`Module.nesting`)
+    pub fn namespace_nesting(&self) -> Vec<String> {
+        let mut nesting = Vec::new();
+        let mut current = Some(self.current_scope);
+
+        while let Some(scope_id) = current {
+            let Some(scope) = self.scopes.get(&scope_id) else {
+                break;
+            };
+            if let ScopeKind::Class { name, .. } | ScopeKind::Module { name } = &scope.kind {
+                nesting.push(name.clone());
+            }
+            current = scope.parent;
+        }
+
+        nesting
+    }
+
+    /// Lookup class variable in enclosing class or module scope
+    pub fn lookup_class_var(&self, name: &str) -> Option<VertexId> {
+        let scope_id = self.enclosing_namespace_scope()?;
+        self.scopes.get(&scope_id)?.class_vars.get(name).copied()
+    }
+
+    /// Set class variable in enclosing class or module scope
+    ///
+    /// Returns false outside a class or module.
+    pub fn set_class_var(&mut self, name: String, vtx: VertexId) -> bool {
+        let Some(scope_id) = self.enclosing_namespace_scope() else {
+            return false;
+        };
+        match self.scopes.get_mut(&scope_id) {
+            Some(scope) => {
+                scope.class_vars.insert(name, vtx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Name of the enclosing method
+    pub fn current_method_name(&self) -> Option<String> {
+        let scope_id = self.enclosing_method_scope()?;
+        match &self.scopes.get(&scope_id)?.kind {
+            ScopeKind::Method { name, .. } => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Check if self is the class or module itself: directly in its body, or
+    /// in a `def self.name`
+    ///
+    /// Blocks in a class body (`define_method`, callbacks) usually run with
+    /// an instance as self.
+    pub fn in_singleton_context(&self) -> bool {
+        let kind = move |scope_id: ScopeId| self.scopes.get(&scope_id).map(|scope| &scope.kind);
+        if let Some(ScopeKind::Class { .. } | ScopeKind::Module { .. }) = kind(self.current_scope) {
+            return true;
+        }
+        match self.enclosing_method_scope().and_then(kind) {
+            Some(ScopeKind::Method { singleton, .. }) => *singleton,
+            _ => false,
+        }
+    }
+
+    /// Record an instance variable assignment in the enclosing method
+    pub fn set_instance_var_in_method(&mut self, name: String, vtx: VertexId) {
+        if let Some(scope_id) = self.enclosing_method_scope() {
+            if let Some(scope) = self.scopes.get_mut(&scope_id) {
+                scope.set_instance_var(name, vtx);
+            }
+        }
+    }
+
+    /// Lookup an instance variable assigned earlier in the enclosing method
+    pub fn lookup_instance_var_in_method(&self, name: &str) -> Option<VertexId> {
+        let scope_id = self.enclosing_method_scope()?;
+        self.scopes.get(&scope_id)?.get_instance_var(name)
+    }
+
+    /// Innermost enclosing method scope (blocks belong to their method)
+    fn enclosing_method_scope(&self) -> Option<ScopeId> {
+        let mut current = Some(self.current_scope);
+
+        while let Some(scope_id) = current {
+            let scope = self.scopes.get(&scope_id)?;
+            match scope.kind {
+                ScopeKind::Method { .. } => return Some(scope_id),
+                ScopeKind::Class { .. } | ScopeKind::Module { .. } => return None,
+                _ => current = scope.parent,
+            }
+        }
+
+        None
+    }
+
+    /// Innermost enclosing class or module scope
+    fn enclosing_namespace_scope(&self) -> Option<ScopeId> {
+        let mut current = Some(self.current_scope);
+
+        while let Some(scope_id) = current {
+            let scope = self.scopes.get(&scope_id)?;
+            if matches!(
+                scope.kind,
+                ScopeKind::Class { .. } | ScopeKind::Module { .. }
+            ) {
+                return Some(scope_id);
+            }
+            current = scope.parent;
+        }
+
+        None
+    }
+
+    /// Set the visibility of methods defined next in the current scope
+    pub fn set_default_visibility(&mut self, visibility: Visibility) {
+        self.current_scope_mut().default_visibility = visibility;
+    }
+
+    /// Visibility of methods defined next in the current scope
+    pub fn default_visibility(&self) -> Visibility {
+        self.current_scope().default_visibility
+    }
+
     /// Get current module name
     pub fn current_module_name(&self) -> Option<String> {
         let mut current = Some(self.current_scope);
@@ -384,6 +525,7 @@ mod tests {
         let method_id = sm.new_scope(ScopeKind::Method {
             name: "test".to_string(),
             receiver_type: None,
+            singleton: false,
         });
         sm.enter_scope(method_id);
 
@@ -391,6 +533,77 @@ mod tests {
         assert_eq!(sm.current_class_name(), Some("User".to_string()));
     }
 
+    #[test]
+    fn test_scope_manager_class_var_and_namespace() {
+        let mut sm = ScopeManager::new();
+        assert!(!sm.set_class_var("@@count".to_string(), VertexId(1)));
+        assert_eq!(sm.current_namespace(), None);
+
+        let module_id = sm.new_scope(ScopeKind::Module {
+            name: "Billing".to_string(),
+        });
+        sm.enter_scope(module_id);
+        assert!(sm.set_class_var("@@count".to_string(), VertexId(1)));
+
+        // Visible from methods of the module
+        let method_id = sm.new_scope(ScopeKind::Method {
+            name: "count".to_string(),
+            receiver_type: None,
+            singleton: false,
+        });
+        sm.enter_scope(method_id);
+        assert_eq!(sm.lookup_class_var("@@count"), Some(VertexId(1)));
+        assert_eq!(sm.current_namespace(), Some("Billing".to_string()));
+    }
+
+    #[test]
+    fn test_scope_manager_namespace_nesting() {
+        let mut sm = ScopeManager::new();
+        assert!(sm.namespace_nesting().is_empty());
+
+        let module_id = sm.new_scope(ScopeKind::Module {
+            name: "Billing".to_string(),
+        });
+        sm.enter_scope(module_id);
+        let class_id = sm.new_scope(ScopeKind::Class {
+            name: "Invoice".to_string(),
+            superclass: None,
+        });
+        sm.enter_scope(class_id);
+        let method_id = sm.new_scope(ScopeKind::Method {
+            name: "total".to_string(),
+            receiver_type: None,
+            singleton: false,
+        });
+        sm.enter_scope(method_id);
+
+        assert_eq!(sm.namespace_nesting(), vec!["Invoice", "Billing"]);
+    }
+
+    #[test]
+    fn test_scope_manager_default_visibility() {
+        let mut sm = ScopeManager::new();
+
+        let class_id = sm.new_scope(ScopeKind::Class {
+            name: "User".to_string(),
+            superclass: None,
+        });
+        sm.enter_scope(class_id);
+        assert_eq!(sm.default_visibility(), Visibility::Public);
+
+        sm.set_default_visibility(Visibility::Private);
+        assert_eq!(sm.default_visibility(), Visibility::Private);
+
+        // Reopening the class starts public again
+        sm.exit_scope();
+        let reopened_id = sm.new_scope(ScopeKind::Class {
+            name: "User".to_string(),
+            superclass: None,
+        });
+        sm.enter_scope(reopened_id);
+        assert_eq!(sm.default_visibility(), Visibility::Public);
+    }
+
     #[test]
     fn test_scope_manager_module_scope() {
         let mut sm = ScopeManager::new();
@@ -408,6 +621,7 @@ mod tests {
         let method_id = sm.new_scope(ScopeKind::Method {
             name: "helper".to_string(),
             receiver_type: Some("Utils".to_string()),
+            singleton: false,
         });
         sm.enter_scope(method_id);
 
@@ -436,6 +650,7 @@ mod tests {
         let method_id = sm.new_scope(ScopeKind::Method {
             name: "get_setting".to_string(),
             receiver_type: Some("Config".to_string()),
+            singleton: false,
         });
         sm.enter_scope(method_id);
 
@@ -445,4 +660,70 @@ mod tests {
             Some(VertexId(100))
         );
     }
+
+    #[test]
+    fn test_scope_manager_method_instance_var() {
+        let mut sm = ScopeManager::new();
+
+        let class_id = sm.new_scope(ScopeKind::Class {
+            name: "User".to_string(),
+            superclass: None,
+        });
+        sm.enter_scope(class_id);
+        assert_eq!(sm.current_method_name(), None);
+
+        let method_id = sm.new_scope(ScopeKind::Method {
+            name: "rename".to_string(),
+            receiver_type: None,
+            singleton: false,
+        });
+        sm.enter_scope(method_id);
+        sm.set_instance_var_in_method("@name".to_string(), VertexId(7));
+
+        // Blocks belong to their method
+        let block_id = sm.new_scope(ScopeKind::Block);
+        sm.enter_scope(block_id);
+        assert_eq!(sm.current_method_name(), Some("rename".to_string()));
+        assert_eq!(sm.lookup_instance_var_in_method("@name"), Some(VertexId(7)));
+        sm.exit_scope();
+        sm.exit_scope();
+
+        // A new method starts without assignments
+        let other_id = sm.new_scope(ScopeKind::Method {
+            name: "name".to_string(),
+            receiver_type: None,
+            singleton: false,
+        });
+        sm.enter_scope(other_id);
+        assert_eq!(sm.lookup_instance_var_in_method("@name"), None);
+    }
+
+    #[test]
+    fn test_scope_manager_singleton_context() {
+        let mut sm = ScopeManager::new();
+        assert!(!sm.in_singleton_context());
+
+        let class_id = sm.new_scope(ScopeKind::Class {
+            name: "User".to_string(),
+            superclass: None,
+        });
+        sm.enter_scope(class_id);
+        assert!(sm.in_singleton_context());
+
+        // define_method and callback blocks run with an instance as self
+        let block_id = sm.new_scope(ScopeKind::Block);
+        sm.enter_scope(block_id);
+        assert!(!sm.in_singleton_context());
+        sm.exit_scope();
+
+        let method_id = sm.new_scope(ScopeKind::Method {
+            name: "find".to_string(),
+            receiver_type: None,
+            singleton: true,
+        });
+        sm.enter_scope(method_id);
+        let block_id = sm.new_scope(ScopeKind::Block);
+        sm.enter_scope(block_id);
+        assert!(sm.in_singleton_context());
+    }
 }
@@ -1,23 +1,149 @@
 //! Type error definitions for diagnostic reporting
 
+use crate::env::method_registry::Visibility;
 use crate::source_map::SourceLocation;
 use crate::types::Type;
 
+/// Kind of type error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeErrorKind {
+    /// Method is not defined for the receiver type
+    UndefinedMethod,
+    /// Receiver may be nil and the method is not defined on NilClass
+    NilReceiver {
+        /// Where the nil was introduced (nil literal or call site)
+        nil_origin: Option<SourceLocation>,
+    },
+    /// Symbol key is not a field of the record receiver
+    UnknownRecordKey { key: String },
+    /// `super` has no method to call in the ancestors of the enclosing class
+    NoSuperMethod,
+    /// Private method called with an explicit receiver, or protected method
+    /// called from outside the class hierarchy
+    VisibilityViolation { visibility: Visibility },
+    /// Constant is not defined in the lexical scope or the ancestors
+    /// (method_name holds the constant, e.g. `Config::TIMOUT`)
+    UninitializedConstant,
+    /// Bare identifier that is neither a local variable nor a method of self
+    UndefinedLocal,
+    /// More positional arguments than the method accepts
+    WrongArity { given: usize, max: usize },
+}
+
 /// Type error information for diagnostic reporting
 #[derive(Debug, Clone)]
 pub struct TypeError {
+    pub kind: TypeErrorKind,
     pub receiver_type: Type,
     pub method_name: String,
     pub location: Option<SourceLocation>,
 }
 
 impl TypeError {
-    /// Create a new type error
+    /// Create a new type error (undefined method)
     pub fn new(receiver_type: Type, method_name: String, location: Option<SourceLocation>) -> Self {
         Self {
+            kind: TypeErrorKind::UndefinedMethod,
+            receiver_type,
+            method_name,
+            location,
+        }
+    }
+
+    /// Create an unknown record key error (`record[:key]`)
+    pub fn unknown_record_key(
+        record_type: Type,
+        key: String,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        Self {
+            kind: TypeErrorKind::UnknownRecordKey { key },
+            receiver_type: record_type,
+            method_name: "[]".to_string(),
+            location,
+        }
+    }
+
+    /// Create a missing super method error (receiver_type is the enclosing class)
+    pub fn no_super_method(
+        class_type: Type,
+        method_name: String,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        Self {
+            kind: TypeErrorKind::NoSuperMethod,
+            receiver_type: class_type,
+            method_name,
+            location,
+        }
+    }
+
+    /// Create a private/protected method call error
+    pub fn visibility_violation(
+        receiver_type: Type,
+        method_name: String,
+        visibility: Visibility,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        Self {
+            kind: TypeErrorKind::VisibilityViolation { visibility },
             receiver_type,
             method_name,
             location,
         }
     }
+
+    /// Create an uninitialized constant error
+    pub fn uninitialized_constant(name: String, location: Option<SourceLocation>) -> Self {
+        Self {
+            kind: TypeErrorKind::UninitializedConstant,
+            receiver_type: Type::Bot,
+            method_name: name,
+            location,
+        }
+    }
+
+    /// Create an undefined local variable or method error (receiver_type is self)
+    pub fn undefined_local(
+        self_type: Type,
+        name: String,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        Self {
+            kind: TypeErrorKind::UndefinedLocal,
+            receiver_type: self_type,
+            method_name: name,
+            location,
+        }
+    }
+
+    /// Create a wrong number of arguments error
+    pub fn wrong_arity(
+        receiver_type: Type,
+        method_name: String,
+        given: usize,
+        max: usize,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        Self {
+            kind: TypeErrorKind::WrongArity { given, max },
+            receiver_type,
+            method_name,
+            location,
+        }
+    }
+
+    /// Create a nil receiver error
+    pub fn nil_receiver(
+        method_name: String,
+        location: Option<SourceLocation>,
+        nil_origin: Option<SourceLocation>,
+    ) -> Self {
+        Self {
+            kind: TypeErrorKind::NilReceiver { nil_origin },
+            receiver_type: Type::Nil,
+            method_name,
+            location,
+        }
+    }
 }
@@ -3,8 +3,9 @@
 //! Handles creation, storage, and type propagation for vertices and sources.
 
 use crate::graph::{Source, Vertex, VertexId};
+use crate::source_map::SourceLocation;
 use crate::types::Type;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Manages vertices and sources in the type graph
 #[derive(Debug, Default)]
@@ -15,6 +16,17 @@ pub struct VertexManager {
     pub sources: HashMap<VertexId, Source>,
     /// Next vertex ID to allocate
     next_vertex_id: usize,
+    /// Element vertex of each container written with `[]=`, keyed by container vertex
+    element_vertices: HashMap<VertexId, VertexId>,
+    /// Symbol keys written with `[]=`, keyed by container vertex
+    /// (an entry exists for every container written with `[]=`)
+    written_keys: HashMap<VertexId, HashSet<String>>,
+    /// Containers written at a key or position not known statically
+    /// (`opts[name] = value`, `opts.merge!(other)`, `pair << value`)
+    written_at_any_key: HashSet<VertexId>,
+    /// Arguments passed to each parameter vertex: element writes through the
+    /// parameter are writes to them
+    element_forwards: HashMap<VertexId, Vec<VertexId>>,
 }
 
 #[allow(dead_code)]
@@ -25,6 +37,10 @@ impl VertexManager {
             vertices: HashMap::new(),
             sources: HashMap::new(),
             next_vertex_id: 0,
+            element_vertices: HashMap::new(),
+            written_keys: HashMap::new(),
+            written_at_any_key: HashSet::new(),
+            element_forwards: HashMap::new(),
         }
     }
 
@@ -44,6 +60,109 @@ impl VertexManager {
         id
     }
 
+    /// Create a new source with a fixed type and the location that introduced it
+    pub fn new_source_with_location(&mut self, ty: Type, location: SourceLocation) -> VertexId {
+        let id = VertexId(self.next_vertex_id);
+        self.next_vertex_id += 1;
+        self.sources.insert(id, Source::with_location(ty, location));
+        id
+    }
+
+    /// Get the element vertex of a container, creating it on first use
+    ///
+    /// Values written with `container[key] = value` flow into this vertex,
+    /// and element reads of the same container read from it.
+    pub fn element_vertex(&mut self, container: VertexId) -> VertexId {
+        if let Some(&elem) = self.element_vertices.get(&container) {
+            return elem;
+        }
+        let elem = self.new_vertex();
+        self.element_vertices.insert(container, elem);
+        elem
+    }
+
+    /// Get the element vertex of a container, if it has one
+    pub fn find_element_vertex(&self, container: VertexId) -> Option<VertexId> {
+        self.element_vertices.get(&container).copied()
+    }
+
+    /// Remember a `container[key] = value` write (with its symbol key, if
+    /// static; `None` for a write at any key or position)
+    ///
+    /// Returns the containers not written this way before: the container,
+    /// and the arguments it was passed as.
+    pub fn record_element_write(
+        &mut self,
+        container: VertexId,
+        key: Option<String>,
+    ) -> Vec<VertexId> {
+        let any_key = key.is_none();
+        self.write_elements(container, key.into_iter().collect(), any_key)
+    }
+
+    /// Forward the element writes of a parameter to an argument passed to it
+    ///
+    /// Returns the containers newly written by the writes already made.
+    pub fn forward_element_writes(&mut self, param: VertexId, arg: VertexId) -> Vec<VertexId> {
+        let forwards = self.element_forwards.entry(param).or_default();
+        if forwards.contains(&arg) {
+            return Vec::new();
+        }
+        forwards.push(arg);
+
+        match self.written_keys.get(&param) {
+            Some(keys) => {
+                let any_key = self.written_at_any_key.contains(&param);
+                self.write_elements(arg, keys.clone(), any_key)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Record a write of the keys to a container and the arguments it was
+    /// passed as, returning the containers that changed
+    fn write_elements(
+        &mut self,
+        container: VertexId,
+        keys: HashSet<String>,
+        any_key: bool,
+    ) -> Vec<VertexId> {
+        let mut changed = Vec::new();
+        let mut pending = vec![container];
+        while let Some(container) = pending.pop() {
+            let first_write = !self.written_keys.contains_key(&container);
+            let written = self.written_keys.entry(container).or_default();
+            let new_keys: Vec<String> = keys.difference(written).cloned().collect();
+            written.extend(new_keys.iter().cloned());
+            let new_any_key = any_key && self.written_at_any_key.insert(container);
+            if !first_write && new_keys.is_empty() && !new_any_key {
+                continue;
+            }
+            changed.push(container);
+            if let Some(args) = self.element_forwards.get(&container) {
+                pending.extend(args);
+            }
+        }
+        changed
+    }
+
+    /// Check if a container was written with `[]=`
+    pub fn is_element_written(&self, container: VertexId) -> bool {
+        self.written_keys.contains_key(&container)
+    }
+
+    /// Check if a container was written at a key or position not known statically
+    pub fn is_written_at_any_key(&self, container: VertexId) -> bool {
+        self.written_at_any_key.contains(&container)
+    }
+
+    /// Check if a symbol key was written to a container
+    pub fn has_written_key(&self, container: VertexId, key: &str) -> bool {
+        self.written_keys
+            .get(&container)
+            .is_some_and(|keys| keys.contains(key))
+    }
+
     /// Get a vertex by ID
     pub fn get_vertex(&self, id: VertexId) -> Option<&Vertex> {
         self.vertices.get(&id)
@@ -105,6 +224,33 @@ impl VertexManager {
         }
     }
 
+    /// Find where a type flowing into `id` was introduced
+    ///
+    /// Walks back along the sources recorded for `ty` until a Source with a
+    /// known location is reached.
+    pub fn find_type_origin(&self, id: VertexId, ty: &Type) -> Option<SourceLocation> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![id];
+
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+
+            if let Some(src) = self.sources.get(&current) {
+                if &src.ty == ty && src.location.is_some() {
+                    return src.location.clone();
+                }
+            } else if let Some(vtx) = self.vertices.get(&current) {
+                if let Some(preds) = vtx.types.get(ty) {
+                    stack.extend(preds.iter().copied());
+                }
+            }
+        }
+
+        None
+    }
+
     /// Display all vertices and sources for debugging
     pub fn show_all(&self) -> String {
         let mut lines = Vec::new();
@@ -176,6 +322,27 @@ mod tests {
         assert_eq!(manager.get_vertex(v2).unwrap().show(), "String");
     }
 
+    #[test]
+    fn test_find_type_origin() {
+        let mut manager = VertexManager::new();
+
+        let nil_src = manager.new_source_with_location(Type::Nil, SourceLocation::new(3, 5, 3));
+        let str_src = manager.new_source(Type::string());
+        let v1 = manager.new_vertex();
+        let v2 = manager.new_vertex();
+
+        manager.add_edge(nil_src, v1);
+        manager.add_edge(str_src, v1);
+        manager.add_edge(v1, v2);
+
+        let origin = manager.find_type_origin(v2, &Type::Nil).unwrap();
+        assert_eq!(origin.line, 3);
+        assert_eq!(origin.column, 5);
+
+        // String source has no location
+        assert!(manager.find_type_origin(v2, &Type::string()).is_none());
+    }
+
     #[test]
     fn test_union_propagation() {
         let mut manager = VertexManager::new();
@@ -192,4 +359,53 @@ mod tests {
             "(Integer | String)"
         );
     }
+
+    #[test]
+    fn test_element_vertex_is_shared_per_container() {
+        let mut vm = VertexManager::new();
+
+        let container = vm.new_vertex();
+        let other = vm.new_vertex();
+
+        let elem = vm.element_vertex(container);
+        assert_eq!(vm.element_vertex(container), elem);
+        assert_ne!(vm.element_vertex(other), elem);
+
+        let retries = || Some("retries".to_string());
+        assert_eq!(
+            vm.record_element_write(container, retries()),
+            vec![container]
+        );
+        assert!(vm.record_element_write(container, retries()).is_empty());
+        assert_eq!(vm.record_element_write(other, None), vec![other]);
+        assert!(vm.record_element_write(other, None).is_empty());
+        assert!(vm.has_written_key(container, "retries"));
+        assert!(!vm.has_written_key(other, "retries"));
+        assert!(vm.is_element_written(other));
+        assert!(!vm.is_element_written(elem));
+        assert!(vm.is_written_at_any_key(other));
+        assert!(!vm.is_written_at_any_key(container));
+    }
+
+    #[test]
+    fn test_element_writes_are_forwarded_to_arguments() {
+        let mut vm = VertexManager::new();
+
+        let param = vm.new_vertex();
+        let early_arg = vm.new_vertex();
+        let late_arg = vm.new_vertex();
+
+        // Written after the argument is passed
+        assert!(vm.forward_element_writes(param, early_arg).is_empty());
+        assert_eq!(
+            vm.record_element_write(param, Some("id".to_string())),
+            vec![param, early_arg]
+        );
+        // Written before
+        assert_eq!(vm.forward_element_writes(param, late_arg), vec![late_arg]);
+        assert!(vm.forward_element_writes(param, late_arg).is_empty());
+
+        assert!(vm.has_written_key(early_arg, "id"));
+        assert!(vm.has_written_key(late_arg, "id"));
+    }
 }
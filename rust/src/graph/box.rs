@@ -1,8 +1,10 @@
+use crate::env::method_registry::Visibility;
 use crate::env::GlobalEnv;
 use crate::graph::change_set::ChangeSet;
 use crate::graph::vertex::VertexId;
 use crate::source_map::SourceLocation;
 use crate::types::Type;
+use std::collections::HashSet;
 
 /// Unique ID for Box
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -26,6 +28,14 @@ pub struct MethodCallBox {
     location: Option<SourceLocation>, // Source code location
     /// Number of times this box has been rescheduled
     reschedule_count: u8,
+    /// Safe navigation call (`x&.upcase`): nil receivers short-circuit to nil
+    safe_navigation: bool,
+    /// Positional argument vertices, passed to user-defined method parameters
+    arg_vtxs: Vec<VertexId>,
+    /// Class enclosing the call (protected methods are callable from its hierarchy)
+    caller_class: Option<String>,
+    /// Explicit `self` receiver (`self.foo`): private methods are callable
+    self_receiver: bool,
 }
 
 /// Maximum number of reschedules before giving up
@@ -46,8 +56,31 @@ impl MethodCallBox {
             ret,
             location,
             reschedule_count: 0,
+            safe_navigation: false,
+            arg_vtxs: Vec::new(),
+            caller_class: None,
+            self_receiver: false,
         }
     }
+
+    /// Mark this call as a safe navigation call (`x&.method`)
+    pub fn with_safe_navigation(mut self) -> Self {
+        self.safe_navigation = true;
+        self
+    }
+
+    /// Set the positional arguments of the call
+    pub fn with_arguments(mut self, arg_vtxs: Vec<VertexId>) -> Self {
+        self.arg_vtxs = arg_vtxs;
+        self
+    }
+
+    /// Record where the call is made, for private/protected method checks
+    pub fn called_from(mut self, caller_class: Option<String>, self_receiver: bool) -> Self {
+        self.caller_class = caller_class;
+        self.self_receiver = self_receiver;
+        self
+    }
 }
 
 impl BoxTrait for MethodCallBox {
@@ -83,22 +116,603 @@ impl BoxTrait for MethodCallBox {
             return;
         }
 
+        // `x&.method` never calls the method on nil and evaluates to nil instead
+        let recv_types = if self.safe_navigation && recv_types.contains(&Type::Nil) {
+            let nil_src = match &self.location {
+                Some(location) => genv.new_source_with_location(Type::Nil, location.clone()),
+                None => genv.new_source(Type::Nil),
+            };
+            changes.add_edge(nil_src, self.ret);
+            recv_types
+                .into_iter()
+                .filter(|ty| *ty != Type::Nil)
+                .collect()
+        } else {
+            recv_types
+        };
+
         for recv_ty in recv_types {
-            // Resolve method
-            if let Some(method_info) = genv.resolve_method(&recv_ty, &self.method_name) {
-                // Create return type as Source
-                let ret_src_id = genv.new_source(method_info.return_type.clone());
-
-                // Add edge to return value
-                changes.add_edge(ret_src_id, self.ret);
-            } else {
-                // Record type error for diagnostic reporting
-                genv.record_type_error(
+            self.call_on_type(genv, changes, &recv_ty);
+        }
+    }
+}
+
+impl MethodCallBox {
+    /// Resolve the call for one receiver type and connect its return type
+    fn call_on_type(&self, genv: &mut GlobalEnv, changes: &mut ChangeSet, recv_ty: &Type) {
+        // An untyped receiver may have any method
+        if *recv_ty == Type::Bot {
+            return;
+        }
+
+        // Resolve method
+        if let Some(method_info) = genv.resolve_method(recv_ty, &self.method_name) {
+            let return_vtx = method_info.return_vtx;
+            let return_type = method_info.return_type.clone();
+            let visibility = method_info.visibility;
+            let max_args = method_info.max_args;
+
+            // Arguments flow into the parameters of user-defined methods, and
+            // what the method adds to a parameter's elements back into them
+            let param_vtxs = method_info.param_vtxs.clone();
+            for (&arg_vtx, &param_vtx) in self.arg_vtxs.iter().zip(&param_vtxs) {
+                changes.add_edge(arg_vtx, param_vtx);
+                genv.pass_elements(changes, arg_vtx, param_vtx);
+            }
+
+            if let Some(max_args) = max_args.filter(|&max| self.arg_vtxs.len() > max) {
+                genv.record_wrong_arity(
+                    recv_ty.clone(),
+                    self.method_name.clone(),
+                    self.arg_vtxs.len(),
+                    max_args,
+                    self.location.clone(),
+                );
+            }
+
+            if !self.is_callable(genv, recv_ty, visibility) {
+                genv.record_visibility_violation(
                     recv_ty.clone(),
                     self.method_name.clone(),
+                    visibility,
+                    self.location.clone(),
+                );
+            }
+
+            // User-defined method: its return value flows into the call
+            if let Some(return_vtx) = return_vtx {
+                changes.add_edge(return_vtx, self.ret);
+                return;
+            }
+
+            // Create a Source per union member so each type flows separately
+            // (nil keeps the call site so nil receiver errors can point here)
+            for ty in return_type.union_members() {
+                self.add_return_type(genv, changes, ty);
+            }
+        } else if *recv_ty == Type::Nil {
+            // Receiver may be nil: report where the nil came from
+            let nil_origin = genv.find_type_origin(self.recv, &Type::Nil);
+            genv.record_nil_receiver_error(
+                self.method_name.clone(),
+                self.location.clone(),
+                nil_origin,
+            );
+        } else if Self::has_method_missing(genv, recv_ty) {
+            // Handled dynamically by the class's method_missing
+        } else if !Self::has_loaded_singleton_methods(genv, recv_ty) {
+            // Class methods inherited from a class not loaded from RBS
+            // (`Point.members` for a Struct) are unknown
+        } else {
+            // Record type error for diagnostic reporting
+            genv.record_type_error(
+                recv_ty.clone(),
+                self.method_name.clone(),
+                self.location.clone(),
+            );
+        }
+    }
+
+    /// Check if the receiver's class handles undefined methods with method_missing
+    fn has_method_missing(genv: &GlobalEnv, recv_ty: &Type) -> bool {
+        match recv_ty {
+            Type::Instance { class_name } => genv.has_method_missing(class_name),
+            _ => false,
+        }
+    }
+
+    /// Check if every class method of a class receiver is known
+    ///
+    /// Instance receivers are always checked.
+    fn has_loaded_singleton_methods(genv: &GlobalEnv, recv_ty: &Type) -> bool {
+        match recv_ty {
+            Type::Singleton { class_name } => genv.has_loaded_singleton_methods(class_name),
+            _ => true,
+        }
+    }
+
+    /// Check if a method of this visibility may be called with this receiver
+    ///
+    /// Private methods need an implicit or `self` receiver; protected methods
+    /// are callable on `self` or from the method owner's class hierarchy.
+    fn is_callable(&self, genv: &GlobalEnv, recv_ty: &Type, visibility: Visibility) -> bool {
+        match visibility {
+            Visibility::Public => true,
+            Visibility::Private => self.self_receiver,
+            Visibility::Protected => {
+                if self.self_receiver {
+                    return true;
+                }
+                let Some(caller_class) = &self.caller_class else {
+                    return false;
+                };
+                let Type::Instance { class_name } = recv_ty else {
+                    return true;
+                };
+                match genv.method_owner(class_name, &self.method_name) {
+                    Some(owner) => genv.is_subclass_of(caller_class, &owner),
+                    None => true,
+                }
+            }
+        }
+    }
+
+    /// Add a type to the return value
+    fn add_return_type(&self, genv: &mut GlobalEnv, changes: &mut ChangeSet, ty: Type) {
+        let ret_src_id = match (&ty, &self.location) {
+            (Type::Nil, Some(location)) => genv.new_source_with_location(ty, location.clone()),
+            _ => genv.new_source(ty),
+        };
+
+        // Add edge to return value
+        changes.add_edge(ret_src_id, self.ret);
+    }
+}
+
+/// Box for `super` calls inside a method of a class
+///
+/// Resolves the method of the same name in the superclass chain (user code
+/// or RBS), passes the arguments to its parameters and connects its return
+/// value. When the chain is fully known and has no such method, the call is
+/// reported.
+pub struct SuperCallBox {
+    id: BoxId,
+    /// Class enclosing the method that calls super
+    class_name: String,
+    /// Whether the enclosing method is a singleton method (`def self.foo`)
+    singleton: bool,
+    /// Name of the enclosing method
+    method_name: String,
+    /// Argument vertices (the method's own parameters for a bare `super`)
+    arg_vtxs: Vec<VertexId>,
+    ret: VertexId,
+    location: Option<SourceLocation>,
+}
+
+impl SuperCallBox {
+    pub fn new(
+        id: BoxId,
+        class_name: String,
+        singleton: bool,
+        method_name: String,
+        arg_vtxs: Vec<VertexId>,
+        ret: VertexId,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        Self {
+            id,
+            class_name,
+            singleton,
+            method_name,
+            arg_vtxs,
+            ret,
+            location,
+        }
+    }
+}
+
+impl BoxTrait for SuperCallBox {
+    fn id(&self) -> BoxId {
+        self.id
+    }
+
+    fn ret(&self) -> VertexId {
+        self.ret
+    }
+
+    fn run(&mut self, genv: &mut GlobalEnv, changes: &mut ChangeSet) {
+        let Some(method_info) =
+            genv.resolve_super_method(&self.class_name, self.singleton, &self.method_name)
+        else {
+            if genv.has_known_ancestry(&self.class_name) {
+                let class_type = if self.singleton {
+                    Type::Singleton {
+                        class_name: self.class_name.clone(),
+                    }
+                } else {
+                    Type::Instance {
+                        class_name: self.class_name.clone(),
+                    }
+                };
+                genv.record_no_super_method(
+                    class_type,
+                    self.method_name.clone(),
                     self.location.clone(),
                 );
             }
+            return;
+        };
+
+        let return_vtx = method_info.return_vtx;
+        let return_type = method_info.return_type.clone();
+        let param_vtxs = method_info.param_vtxs.clone();
+
+        for (&arg_vtx, &param_vtx) in self.arg_vtxs.iter().zip(&param_vtxs) {
+            changes.add_edge(arg_vtx, param_vtx);
+            genv.pass_elements(changes, arg_vtx, param_vtx);
+        }
+
+        match return_vtx {
+            Some(return_vtx) => changes.add_edge(return_vtx, self.ret),
+            None => {
+                for ty in return_type.union_members() {
+                    let src_id = genv.new_source(ty);
+                    changes.add_edge(src_id, self.ret);
+                }
+            }
+        }
+    }
+}
+
+/// Statically known index of an element access
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IndexKey {
+    /// Symbol literal: `opts[:timeout]`
+    Symbol(String),
+    /// Integer literal: `pair[1]`
+    Integer(i64),
+    /// Range literal: `list[1..]` (slice)
+    Range,
+}
+
+/// Box for element access: `recv[index]`
+///
+/// Element types come from the receiver's type arguments:
+/// - `Array[T]` gives `T?` (`Array[T]?` for a range slice)
+/// - `Hash[K, V]` gives `V?`
+/// - Tuples with an integer literal give the type at that position, until
+///   the tuple is mutated (then it is read as `Array[union]`)
+/// - Records with a symbol key give the field type; unknown keys are
+///   reported and evaluate to nil unless the key was written with `[]=`.
+///   A record written at any key (`opts.merge!(other)`, `opts.delete(:k)`)
+///   is read as `Hash[Symbol, union]`
+///
+/// Everything else is an ordinary `[]` method call.
+pub struct IndexAccessBox {
+    key: Option<IndexKey>,
+    /// Ordinary `recv.[](index)` call, used when element types are unknown
+    call: MethodCallBox,
+    /// Receiver types already read, and whether they were read by position
+    /// (run again once the receiver is written, only widened reads are new)
+    read: HashSet<(Type, bool)>,
+}
+
+impl IndexAccessBox {
+    pub fn new(
+        id: BoxId,
+        recv: VertexId,
+        key: Option<IndexKey>,
+        ret: VertexId,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        Self {
+            key,
+            call: MethodCallBox::new(id, recv, "[]".to_string(), ret, location),
+            read: HashSet::new(),
+        }
+    }
+
+    /// Check if a record or tuple receiver is read at the field or position
+    /// of the key (a tuple only until it is mutated, a record until it is
+    /// written at any key)
+    fn reads_by_key(&self, genv: &GlobalEnv, recv_ty: &Type) -> bool {
+        match (recv_ty, &self.key) {
+            (Type::Record { .. }, Some(IndexKey::Symbol(_))) => {
+                !genv.is_written_at_any_key(self.call.recv)
+            }
+            (Type::Tuple(_), Some(IndexKey::Integer(_))) => {
+                !genv.is_element_written(self.call.recv)
+            }
+            _ => false,
+        }
+    }
+
+    /// Element type of a generic container read with this box's key
+    fn element_type(&self, recv_ty: &Type) -> Option<Type> {
+        let type_args = recv_ty.type_args()?;
+        match recv_ty.base_class_name()? {
+            "Array" if self.key == Some(IndexKey::Range) => {
+                Some(Type::array_of(type_args.first()?.clone()))
+            }
+            "Array" => type_args.first().cloned(),
+            "Hash" => type_args.get(1).cloned(),
+            _ => None,
+        }
+    }
+}
+
+impl BoxTrait for IndexAccessBox {
+    fn id(&self) -> BoxId {
+        self.call.id
+    }
+
+    fn ret(&self) -> VertexId {
+        self.call.ret
+    }
+
+    fn run(&mut self, genv: &mut GlobalEnv, changes: &mut ChangeSet) {
+        let recv_types: Vec<Type> = if let Some(recv_vertex) = genv.get_vertex(self.call.recv) {
+            recv_vertex.types.keys().cloned().collect()
+        } else if let Some(recv_source) = genv.get_source(self.call.recv) {
+            vec![recv_source.ty.clone()]
+        } else {
+            return;
+        };
+
+        if recv_types.is_empty() {
+            if self.call.reschedule_count < MAX_RESCHEDULE_COUNT {
+                self.call.reschedule_count += 1;
+                changes.reschedule(self.call.id);
+            }
+            return;
+        }
+
+        for recv_ty in recv_types {
+            let by_key = self.reads_by_key(genv, &recv_ty);
+            // Run again after a write to the receiver: only widened reads are new
+            if !self.read.insert((recv_ty.clone(), by_key)) {
+                continue;
+            }
+
+            if by_key {
+                match (&recv_ty, &self.key) {
+                    (Type::Record { .. }, Some(IndexKey::Symbol(key))) => {
+                        match recv_ty.record_field(key) {
+                            Some(field_ty) => {
+                                for ty in field_ty.union_members() {
+                                    self.call.add_return_type(genv, changes, ty);
+                                }
+                            }
+                            // Written with `opts[:key] = value`: typed by the element vertex
+                            None if genv.has_written_key(self.call.recv, key) => {}
+                            None => {
+                                genv.record_unknown_record_key(
+                                    recv_ty.clone(),
+                                    key.clone(),
+                                    self.call.location.clone(),
+                                );
+                                self.call.add_return_type(genv, changes, Type::Nil);
+                            }
+                        }
+                    }
+                    // Unmutated tuple with a literal index: the type at that position
+                    (Type::Tuple(elements), Some(IndexKey::Integer(index))) => {
+                        let position = if *index < 0 {
+                            elements.len() as i64 + index
+                        } else {
+                            *index
+                        };
+                        let elem_ty = usize::try_from(position)
+                            .ok()
+                            .and_then(|i| elements.get(i).cloned())
+                            .unwrap_or(Type::Nil);
+                        for ty in elem_ty.union_members() {
+                            self.call.add_return_type(genv, changes, ty);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            match self.element_type(&recv_ty.widen()) {
+                Some(elem_ty) => {
+                    for ty in elem_ty.union_members() {
+                        self.call.add_return_type(genv, changes, ty);
+                    }
+                    // Out of range index / missing key
+                    self.call.add_return_type(genv, changes, Type::Nil);
+                }
+                None => self.call.call_on_type(genv, changes, &recv_ty),
+            }
+        }
+    }
+}
+
+/// Array methods that change the elements, or their positions, in place
+const MUTATING_ARRAY_METHODS: &[&str] = &[
+    "<<",
+    "push",
+    "append",
+    "unshift",
+    "prepend",
+    "insert",
+    "concat",
+    "replace",
+    "fill",
+    "pop",
+    "shift",
+    "delete",
+    "delete_at",
+    "delete_if",
+    "clear",
+    "compact!",
+    "flatten!",
+    "map!",
+    "collect!",
+    "select!",
+    "filter!",
+    "reject!",
+    "keep_if",
+    "reverse!",
+    "rotate!",
+    "shuffle!",
+    "sort!",
+    "sort_by!",
+    "uniq!",
+    "slice!",
+];
+
+/// Hash methods that change the entries in place
+const MUTATING_HASH_METHODS: &[&str] = &[
+    "store",
+    "merge!",
+    "update",
+    "replace",
+    "default=",
+    "delete",
+    "delete_if",
+    "clear",
+    "shift",
+    "compact!",
+    "select!",
+    "filter!",
+    "reject!",
+    "keep_if",
+    "transform_values!",
+    "transform_keys!",
+];
+
+/// Box for a call that may change the receiver's elements in place
+///
+/// Only Array and Hash receivers (tuples and records included) are mutated:
+/// `list << value` or `opts.merge!(other)` records a write at any element,
+/// so the receiver is no longer read as a tuple or record, and the values
+/// added by `<<`, `insert`, `store`, `merge!`, ... flow into its element
+/// vertex, as with `[]=`. The same names on other receivers
+/// (`str << "x"`, `set.delete(x)`) are left alone.
+pub struct ElementMutationBox {
+    id: BoxId,
+    recv: VertexId,
+    method_name: String,
+    arg_vtxs: Vec<VertexId>,
+    /// Number of times this box has been rescheduled
+    reschedule_count: u8,
+}
+
+impl ElementMutationBox {
+    pub fn new(id: BoxId, recv: VertexId, method_name: String, arg_vtxs: Vec<VertexId>) -> Self {
+        Self {
+            id,
+            recv,
+            method_name,
+            arg_vtxs,
+            reschedule_count: 0,
+        }
+    }
+
+    /// Check if a method may mutate its receiver's elements
+    pub fn is_mutation(method_name: &str) -> bool {
+        MUTATING_ARRAY_METHODS.contains(&method_name)
+            || MUTATING_HASH_METHODS.contains(&method_name)
+    }
+
+    /// Check if the method mutates a receiver of this type
+    fn mutates(&self, recv_ty: &Type) -> bool {
+        let methods = match recv_ty.widen() {
+            Type::Instance { class_name } | Type::Generic { class_name, .. } => {
+                match class_name.as_str() {
+                    "Array" => MUTATING_ARRAY_METHODS,
+                    "Hash" => MUTATING_HASH_METHODS,
+                    _ => return false,
+                }
+            }
+            _ => return false,
+        };
+        methods.contains(&self.method_name.as_str())
+    }
+
+    /// Arguments added to the receiver's elements
+    fn added_values(&self, recv_ty: &Type) -> &[VertexId] {
+        match (recv_ty.base_class_name(), self.method_name.as_str()) {
+            (Some("Array"), "<<" | "push" | "append" | "unshift" | "prepend") => &self.arg_vtxs,
+            // insert(index, *values)
+            (Some("Array"), "insert") => self.arg_vtxs.get(1..).unwrap_or_default(),
+            // store(key, value)
+            (Some("Hash"), "store") => self.arg_vtxs.get(1..2).unwrap_or_default(),
+            // The default value is read for missing keys
+            (Some("Hash"), "default=") => &self.arg_vtxs,
+            _ => &[],
+        }
+    }
+
+    /// Hash arguments whose values are added to the receiver's (`merge!(other)`)
+    fn merged_hashes(&self, recv_ty: &Type) -> &[VertexId] {
+        match (recv_ty.base_class_name(), self.method_name.as_str()) {
+            (Some("Hash"), "merge!" | "update" | "replace") => &self.arg_vtxs,
+            _ => &[],
+        }
+    }
+}
+
+impl BoxTrait for ElementMutationBox {
+    fn id(&self) -> BoxId {
+        self.id
+    }
+
+    fn ret(&self) -> VertexId {
+        // The call's return value is handled by its MethodCallBox
+        self.recv
+    }
+
+    fn run(&mut self, genv: &mut GlobalEnv, changes: &mut ChangeSet) {
+        let recv_types: Vec<Type> = if let Some(recv_vertex) = genv.get_vertex(self.recv) {
+            recv_vertex.types.keys().cloned().collect()
+        } else if let Some(recv_source) = genv.get_source(self.recv) {
+            vec![recv_source.ty.clone()]
+        } else {
+            return;
+        };
+
+        if recv_types.is_empty() {
+            if self.reschedule_count < MAX_RESCHEDULE_COUNT {
+                self.reschedule_count += 1;
+                changes.reschedule(self.id);
+            }
+            return;
+        }
+
+        for recv_ty in recv_types.iter().filter(|recv_ty| self.mutates(recv_ty)) {
+            genv.record_element_write(changes, self.recv, None);
+            let elem_vtx = genv.element_vertex(self.recv);
+
+            for &arg_vtx in self.added_values(recv_ty) {
+                changes.add_edge(arg_vtx, elem_vtx);
+            }
+
+            for &hash_vtx in self.merged_hashes(recv_ty) {
+                // Values written to the other hash, and those of its type
+                let hash_elem_vtx = genv.element_vertex(hash_vtx);
+                changes.add_edge(hash_elem_vtx, elem_vtx);
+
+                let hash_types: Vec<Type> = if let Some(vertex) = genv.get_vertex(hash_vtx) {
+                    vertex.types.keys().cloned().collect()
+                } else if let Some(source) = genv.get_source(hash_vtx) {
+                    vec![source.ty.clone()]
+                } else {
+                    Vec::new()
+                };
+                for hash_ty in hash_types {
+                    let widened = hash_ty.widen();
+                    let Some(value_ty) = widened.type_args().and_then(|args| args.get(1)) else {
+                        continue;
+                    };
+                    for ty in value_ty.union_members() {
+                        let src_id = genv.new_source(ty);
+                        changes.add_edge(src_id, elem_vtx);
+                    }
+                }
+            }
         }
     }
 }
@@ -156,7 +770,8 @@ impl BlockParameterTypeBox {
             _ => return None, // Not a type variable
         };
 
-        // Get type arguments from receiver
+        // Get type arguments from receiver (records and tuples act as Hash/Array)
+        let recv_ty = &recv_ty.widen();
         let type_args = recv_ty.type_args()?;
         let class_name = recv_ty.base_class_name()?;
 
@@ -203,6 +818,15 @@ impl BoxTrait for BlockParameterTypeBox {
         };
 
         for recv_ty in recv_types {
+            // User-defined method: connect what it yields to the block parameters
+            let yield_vtxs = genv
+                .resolve_method(&recv_ty, &self.method_name)
+                .map(|info| info.yield_vtxs.clone())
+                .unwrap_or_default();
+            for (&yield_vtx, &param_vtx) in yield_vtxs.iter().zip(&self.block_param_vtxs) {
+                changes.add_edge(yield_vtx, param_vtx);
+            }
+
             // Resolve method to get block parameter types
             // Clone the block_param_types to avoid borrow issues
             let block_param_types = genv
@@ -243,9 +867,268 @@ impl BoxTrait for BlockParameterTypeBox {
     }
 }
 
+/// Box for binding the keys of a hash pattern (`in {name: String => name, **rest}`)
+///
+/// - `{ name: T }` records give each key its field type (missing keys never match)
+/// - `Hash[K, V]` gives each key `V`
+/// - The `**rest` target gets the remaining record fields, or the Hash itself
+pub struct HashPatternBox {
+    id: BoxId,
+    /// Value being matched
+    value_vtx: VertexId,
+    /// Key targets: `name` in `{name: pattern}`
+    keys: Vec<(String, VertexId)>,
+    /// `**rest` target
+    rest: Option<VertexId>,
+    /// Number of times this box has been rescheduled
+    reschedule_count: u8,
+}
+
+impl HashPatternBox {
+    pub fn new(
+        id: BoxId,
+        value_vtx: VertexId,
+        keys: Vec<(String, VertexId)>,
+        rest: Option<VertexId>,
+    ) -> Self {
+        Self {
+            id,
+            value_vtx,
+            keys,
+            rest,
+            reschedule_count: 0,
+        }
+    }
+
+    /// Type bound to a key when matching a value of `value_ty`
+    fn key_type(value_ty: &Type, key: &str) -> Option<Type> {
+        match value_ty {
+            Type::Record { .. } => value_ty.record_field(key).cloned(),
+            Type::Generic {
+                class_name,
+                type_args,
+            } if class_name == "Hash" => type_args.get(1).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Type bound to `**rest` when matching a value of `value_ty`
+    fn rest_type(&self, value_ty: &Type) -> Option<Type> {
+        match value_ty {
+            Type::Record { fields } => {
+                let remaining: Vec<(String, Type)> = fields
+                    .iter()
+                    .filter(|(name, _)| !self.keys.iter().any(|(key, _)| key == name))
+                    .cloned()
+                    .collect();
+                if remaining.is_empty() {
+                    Some(Type::hash())
+                } else {
+                    Some(Type::Record { fields: remaining })
+                }
+            }
+            _ if value_ty.base_class_name() == Some("Hash") => Some(value_ty.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl BoxTrait for HashPatternBox {
+    fn id(&self) -> BoxId {
+        self.id
+    }
+
+    fn ret(&self) -> VertexId {
+        self.value_vtx
+    }
+
+    fn run(&mut self, genv: &mut GlobalEnv, changes: &mut ChangeSet) {
+        let value_types: Vec<Type> = if let Some(value_vertex) = genv.get_vertex(self.value_vtx) {
+            value_vertex.types.keys().cloned().collect()
+        } else if let Some(value_source) = genv.get_source(self.value_vtx) {
+            vec![value_source.ty.clone()]
+        } else {
+            return;
+        };
+
+        if value_types.is_empty() {
+            if self.reschedule_count < MAX_RESCHEDULE_COUNT {
+                self.reschedule_count += 1;
+                changes.reschedule(self.id);
+            }
+            return;
+        }
+
+        for value_ty in &value_types {
+            for (key, target_vtx) in &self.keys {
+                if let Some(ty) = Self::key_type(value_ty, key) {
+                    let src_id = genv.new_source(ty);
+                    changes.add_edge(src_id, *target_vtx);
+                }
+            }
+            if let Some(rest_vtx) = self.rest {
+                if let Some(ty) = self.rest_type(value_ty) {
+                    let src_id = genv.new_source(ty);
+                    changes.add_edge(src_id, rest_vtx);
+                }
+            }
+        }
+    }
+}
+
+/// Position of a multiple assignment target relative to the splat
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DestructureSlot {
+    /// Target before the splat: `a` in `a, *r, b = ...`
+    Pre(usize),
+    /// Splat target: `r` in `a, *r, b = ...`
+    Rest,
+    /// Target after the splat: `b` in `a, *r, b = ...`
+    Post(usize),
+    /// Element at an unknown position: `x` in `in [*, x, *]`
+    Any,
+}
+
+/// Box for distributing a value's element types to multiple assignment targets
+///
+/// For `a, *rest = value`:
+/// - `[A, B, C]` gives each target the type at its position
+/// - `Array[Elem]` gives each target `Elem` and the splat `Array[Elem]`
+/// - Any other type `T` goes to the first target, the rest become nil
+///   (Ruby wraps non-array values as `[value]`)
+///
+/// Array patterns (`in [a, *rest]`) use the same slots but never match non-Array values.
+pub struct MultiWriteBox {
+    id: BoxId,
+    /// Right-hand side of the assignment
+    value_vtx: VertexId,
+    /// Target vertices with their position
+    targets: Vec<(DestructureSlot, VertexId)>,
+    /// Number of targets before the splat (all targets without a splat)
+    pre_len: usize,
+    /// Number of targets after the splat
+    post_len: usize,
+    /// Whether this destructures an array pattern rather than an assignment
+    pattern: bool,
+    /// Number of times this box has been rescheduled
+    reschedule_count: u8,
+}
+
+impl MultiWriteBox {
+    pub fn new(
+        id: BoxId,
+        value_vtx: VertexId,
+        targets: Vec<(DestructureSlot, VertexId)>,
+        pre_len: usize,
+        post_len: usize,
+    ) -> Self {
+        Self {
+            id,
+            value_vtx,
+            targets,
+            pre_len,
+            post_len,
+            pattern: false,
+            reschedule_count: 0,
+        }
+    }
+
+    /// Mark this as an array pattern (`in [a, b]`), which only matches Arrays
+    pub fn for_pattern(mut self) -> Self {
+        self.pattern = true;
+        self
+    }
+
+    /// Type bound to a target slot when destructuring a value of `value_ty`
+    fn slot_type(&self, value_ty: &Type, slot: DestructureSlot) -> Option<Type> {
+        match value_ty {
+            // Tuples keep per-position types: `a, *r, b = [1, "x", :s]`
+            Type::Tuple(elements) => {
+                let len = elements.len();
+                // Targets after the splat take the last elements not used by `pre`
+                let post_start = self.pre_len + len.saturating_sub(self.pre_len + self.post_len);
+                let element_at = |i: usize| Some(elements.get(i).cloned().unwrap_or(Type::Nil));
+                match slot {
+                    DestructureSlot::Pre(i) => element_at(i),
+                    DestructureSlot::Post(i) => element_at(post_start + i),
+                    DestructureSlot::Rest => match elements.get(self.pre_len..post_start) {
+                        Some(rest) if !rest.is_empty() => Some(Type::Tuple(rest.to_vec()).widen()),
+                        _ => Some(Type::array()),
+                    },
+                    DestructureSlot::Any => self.slot_type(&value_ty.widen(), slot),
+                }
+            }
+            Type::Generic {
+                class_name,
+                type_args,
+            } if class_name == "Array" => {
+                let elem = type_args.first()?.clone();
+                match slot {
+                    DestructureSlot::Rest => Some(Type::array_of(elem)),
+                    _ => Some(elem),
+                }
+            }
+            // Untyped Array: only the splat is known to be an Array
+            Type::Instance { class_name } if class_name == "Array" => match slot {
+                DestructureSlot::Rest => Some(Type::array()),
+                _ => None,
+            },
+            _ if self.pattern => None,
+            _ => match slot {
+                DestructureSlot::Pre(0) => Some(value_ty.clone()),
+                DestructureSlot::Rest => Some(Type::array()),
+                _ => Some(Type::Nil),
+            },
+        }
+    }
+}
+
+impl BoxTrait for MultiWriteBox {
+    fn id(&self) -> BoxId {
+        self.id
+    }
+
+    fn ret(&self) -> VertexId {
+        // Multiple assignment evaluates to its right-hand side
+        self.value_vtx
+    }
+
+    fn run(&mut self, genv: &mut GlobalEnv, changes: &mut ChangeSet) {
+        let value_types: Vec<Type> = if let Some(value_vertex) = genv.get_vertex(self.value_vtx) {
+            value_vertex.types.keys().cloned().collect()
+        } else if let Some(value_source) = genv.get_source(self.value_vtx) {
+            vec![value_source.ty.clone()]
+        } else {
+            return;
+        };
+
+        // Value may be typed later (e.g., method call result, nested destructuring)
+        if value_types.is_empty() {
+            if self.reschedule_count < MAX_RESCHEDULE_COUNT {
+                self.reschedule_count += 1;
+                changes.reschedule(self.id);
+            }
+            return;
+        }
+
+        for value_ty in &value_types {
+            for &(slot, target_vtx) in &self.targets {
+                let Some(slot_ty) = self.slot_type(value_ty, slot) else {
+                    continue;
+                };
+                for ty in slot_ty.union_members() {
+                    let src_id = genv.new_source(ty);
+                    changes.add_edge(src_id, target_vtx);
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::env::type_error::TypeErrorKind;
     use crate::env::GlobalEnv;
     use crate::types::Type;
 
@@ -312,6 +1195,89 @@ mod tests {
         assert_eq!(ret_vertex.show(), "untyped");
     }
 
+    #[test]
+    fn test_method_call_box_untyped_receiver() {
+        let mut genv = GlobalEnv::new();
+
+        // x.anything where x is untyped
+        let x_src = genv.new_source(Type::Bot);
+        let ret_vtx = genv.new_vertex();
+        let mut call_box =
+            MethodCallBox::new(BoxId(0), x_src, "anything".to_string(), ret_vtx, None);
+
+        let mut changes = ChangeSet::new();
+        call_box.run(&mut genv, &mut changes);
+        genv.apply_changes(changes);
+
+        assert!(genv.type_errors.is_empty());
+    }
+
+    #[test]
+    fn test_method_call_box_nil_receiver() {
+        let mut genv = GlobalEnv::new();
+        genv.register_builtin_method(Type::string(), "upcase", Type::string());
+
+        // x = "hello" or nil
+        let x_vtx = genv.new_vertex();
+        let str_src = genv.new_source(Type::string());
+        let nil_src = genv.new_source_with_location(Type::Nil, SourceLocation::new(2, 5, 3));
+        genv.add_edge(str_src, x_vtx);
+        genv.add_edge(nil_src, x_vtx);
+
+        let ret_vtx = genv.new_vertex();
+        let mut call_box = MethodCallBox::new(
+            BoxId(0),
+            x_vtx,
+            "upcase".to_string(),
+            ret_vtx,
+            Some(SourceLocation::new(3, 1, 8)),
+        );
+
+        let mut changes = ChangeSet::new();
+        call_box.run(&mut genv, &mut changes);
+        genv.apply_changes(changes);
+
+        assert_eq!(genv.type_errors.len(), 1);
+        match &genv.type_errors[0].kind {
+            TypeErrorKind::NilReceiver { nil_origin } => {
+                assert_eq!(nil_origin.as_ref().unwrap().line, 2);
+            }
+            kind => panic!("Expected NilReceiver, got {:?}", kind),
+        }
+
+        // String branch still resolves
+        assert_eq!(genv.get_vertex(ret_vtx).unwrap().show(), "String");
+    }
+
+    #[test]
+    fn test_method_call_box_optional_return() {
+        let mut genv = GlobalEnv::new();
+        genv.register_builtin_method(
+            Type::string(),
+            "match",
+            Type::Union(vec![Type::string(), Type::Nil]),
+        );
+
+        let recv_vtx = genv.new_source(Type::string());
+        let ret_vtx = genv.new_vertex();
+        let mut call_box = MethodCallBox::new(
+            BoxId(0),
+            recv_vtx,
+            "match".to_string(),
+            ret_vtx,
+            Some(SourceLocation::new(1, 1, 10)),
+        );
+
+        let mut changes = ChangeSet::new();
+        call_box.run(&mut genv, &mut changes);
+        genv.apply_changes(changes);
+
+        // Union members flow separately; nil remembers the call site
+        assert_eq!(genv.get_vertex(ret_vtx).unwrap().show(), "(String | nil)");
+        let origin = genv.find_type_origin(ret_vtx, &Type::Nil).unwrap();
+        assert_eq!(origin.line, 1);
+    }
+
     #[test]
     fn test_block_param_type_box_simple() {
         let mut genv = GlobalEnv::new();
@@ -497,4 +1463,183 @@ mod tests {
         assert_eq!(genv.get_vertex(key_vtx).unwrap().show(), "String");
         assert_eq!(genv.get_vertex(value_vtx).unwrap().show(), "Integer");
     }
+
+    #[test]
+    fn test_multi_write_box_array_elements() {
+        let mut genv = GlobalEnv::new();
+
+        let value_src = genv.new_source(Type::array_of(Type::integer()));
+        let first_vtx = genv.new_vertex();
+        let rest_vtx = genv.new_vertex();
+
+        let box_id = genv.alloc_box_id();
+        let multi_write_box = MultiWriteBox::new(
+            box_id,
+            value_src,
+            vec![
+                (DestructureSlot::Pre(0), first_vtx),
+                (DestructureSlot::Rest, rest_vtx),
+            ],
+            1,
+            0,
+        );
+        genv.register_box(box_id, Box::new(multi_write_box));
+
+        genv.run_all();
+
+        assert_eq!(genv.get_vertex(first_vtx).unwrap().show(), "Integer");
+        assert_eq!(genv.get_vertex(rest_vtx).unwrap().show(), "Array[Integer]");
+    }
+
+    #[test]
+    fn test_multi_write_box_non_array_value() {
+        let mut genv = GlobalEnv::new();
+
+        let value_src = genv.new_source(Type::string());
+        let a_vtx = genv.new_vertex();
+        let b_vtx = genv.new_vertex();
+
+        let box_id = genv.alloc_box_id();
+        let multi_write_box = MultiWriteBox::new(
+            box_id,
+            value_src,
+            vec![
+                (DestructureSlot::Pre(0), a_vtx),
+                (DestructureSlot::Pre(1), b_vtx),
+            ],
+            2,
+            0,
+        );
+        genv.register_box(box_id, Box::new(multi_write_box));
+
+        genv.run_all();
+
+        // `a, b = "x"` binds a = "x", b = nil
+        assert_eq!(genv.get_vertex(a_vtx).unwrap().show(), "String");
+        assert_eq!(genv.get_vertex(b_vtx).unwrap().show(), "nil");
+    }
+
+    #[test]
+    fn test_hash_pattern_box_record_fields() {
+        let mut genv = GlobalEnv::new();
+
+        let value_src = genv.new_source(Type::Record {
+            fields: vec![
+                ("name".to_string(), Type::string()),
+                ("age".to_string(), Type::integer()),
+            ],
+        });
+        let name_vtx = genv.new_vertex();
+        let missing_vtx = genv.new_vertex();
+        let rest_vtx = genv.new_vertex();
+
+        let box_id = genv.alloc_box_id();
+        let hash_pattern_box = HashPatternBox::new(
+            box_id,
+            value_src,
+            vec![
+                ("name".to_string(), name_vtx),
+                ("email".to_string(), missing_vtx),
+            ],
+            Some(rest_vtx),
+        );
+        genv.register_box(box_id, Box::new(hash_pattern_box));
+
+        genv.run_all();
+
+        // `in {name:, email:, **rest}`: email never matches, rest keeps age
+        assert_eq!(genv.get_vertex(name_vtx).unwrap().show(), "String");
+        assert_eq!(genv.get_vertex(missing_vtx).unwrap().show(), "untyped");
+        assert_eq!(
+            genv.get_vertex(rest_vtx).unwrap().show(),
+            "{ age: Integer }"
+        );
+    }
+
+    #[test]
+    fn test_method_call_box_safe_navigation() {
+        let mut genv = GlobalEnv::new();
+        genv.register_builtin_method(Type::string(), "upcase", Type::string());
+
+        let recv_vtx = genv.new_vertex();
+        let nil_src = genv.new_source(Type::Nil);
+        let str_src = genv.new_source(Type::string());
+        genv.add_edge(nil_src, recv_vtx);
+        genv.add_edge(str_src, recv_vtx);
+
+        let ret_vtx = genv.new_vertex();
+        let box_id = genv.alloc_box_id();
+        let call_box = MethodCallBox::new(box_id, recv_vtx, "upcase".to_string(), ret_vtx, None)
+            .with_safe_navigation();
+        genv.register_box(box_id, Box::new(call_box));
+
+        genv.run_all();
+
+        // nil is skipped instead of reported, and flows to the result
+        assert_eq!(genv.type_errors.len(), 0);
+        assert_eq!(genv.get_vertex(ret_vtx).unwrap().show(), "(String | nil)");
+    }
+
+    #[test]
+    fn test_method_call_box_protected_outside_hierarchy() {
+        let mut genv = GlobalEnv::new();
+        let user = Type::Instance {
+            class_name: "User".to_string(),
+        };
+        genv.register_class("User", None);
+        genv.register_class("Guest", None);
+        genv.register_builtin_method(user.clone(), "token", Type::string());
+        genv.set_method_visibility(&user, "token", Visibility::Protected);
+
+        let recv_vtx = genv.new_source(user);
+        let ret_vtx = genv.new_vertex();
+        let box_id = genv.alloc_box_id();
+        let call_box = MethodCallBox::new(box_id, recv_vtx, "token".to_string(), ret_vtx, None)
+            .called_from(Some("Guest".to_string()), false);
+        genv.register_box(box_id, Box::new(call_box));
+
+        genv.run_all();
+
+        // Reported, but the return type still flows
+        assert_eq!(genv.type_errors.len(), 1);
+        assert_eq!(
+            genv.type_errors[0].kind,
+            crate::env::type_error::TypeErrorKind::VisibilityViolation {
+                visibility: Visibility::Protected
+            }
+        );
+        assert_eq!(genv.get_vertex(ret_vtx).unwrap().show(), "String");
+    }
+
+    #[test]
+    fn test_index_access_box_element_types() {
+        let mut genv = GlobalEnv::new();
+
+        let array_src = genv.new_source(Type::array_of(Type::integer()));
+        let hash_src = genv.new_source(Type::hash_of(Type::symbol(), Type::string()));
+
+        let elem_ret = genv.new_vertex();
+        let slice_ret = genv.new_vertex();
+        let value_ret = genv.new_vertex();
+
+        let boxes = [
+            (array_src, None, elem_ret),
+            (array_src, Some(IndexKey::Range), slice_ret),
+            (hash_src, None, value_ret),
+        ];
+        for (recv, key, ret) in boxes {
+            let box_id = genv.alloc_box_id();
+            let index_box = IndexAccessBox::new(box_id, recv, key, ret, None);
+            genv.register_box(box_id, Box::new(index_box));
+        }
+
+        genv.run_all();
+
+        assert_eq!(genv.get_vertex(elem_ret).unwrap().show(), "(Integer | nil)");
+        assert_eq!(
+            genv.get_vertex(slice_ret).unwrap().show(),
+            "(Array[Integer] | nil)"
+        );
+        assert_eq!(genv.get_vertex(value_ret).unwrap().show(), "(String | nil)");
+    }
 }
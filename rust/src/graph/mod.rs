@@ -3,5 +3,8 @@ pub mod change_set;
 pub mod vertex;
 
 pub use change_set::{ChangeSet, EdgeUpdate};
-pub use r#box::{BlockParameterTypeBox, BoxId, BoxTrait, MethodCallBox};
+pub use r#box::{
+    BlockParameterTypeBox, BoxId, BoxTrait, DestructureSlot, ElementMutationBox, HashPatternBox,
+    IndexAccessBox, IndexKey, MethodCallBox, MultiWriteBox, SuperCallBox,
+};
 pub use vertex::{Source, Vertex, VertexId};
@@ -1,3 +1,4 @@
+use crate::source_map::SourceLocation;
 use crate::types::Type;
 use std::collections::{HashMap, HashSet};
 
@@ -9,11 +10,20 @@ pub struct VertexId(pub usize);
 #[derive(Debug, Clone)]
 pub struct Source {
     pub ty: Type,
+    /// Where the value was introduced (literal or call site), if known
+    pub location: Option<SourceLocation>,
 }
 
 impl Source {
     pub fn new(ty: Type) -> Self {
-        Self { ty }
+        Self { ty, location: None }
+    }
+
+    pub fn with_location(ty: Type, location: SourceLocation) -> Self {
+        Self {
+            ty,
+            location: Some(location),
+        }
     }
 }
 
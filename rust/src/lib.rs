@@ -18,6 +18,9 @@ pub mod rbs;
 #[cfg(any(feature = "cli", feature = "lsp"))]
 pub mod checker;
 
+#[cfg(any(feature = "cli", feature = "lsp"))]
+pub mod config;
+
 #[cfg(feature = "cli")]
 pub mod cli;
 
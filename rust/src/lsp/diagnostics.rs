@@ -1,5 +1,8 @@
-use crate::diagnostics::{Diagnostic as MethodRayDiagnostic, DiagnosticLevel};
-use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+use crate::diagnostics::{Diagnostic as MethodRayDiagnostic, DiagnosticLevel, Location};
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location as LspLocation,
+    NumberOrString, Position, Range, Url,
+};
 
 /// Extract method name length from error message
 /// Supports messages like:
@@ -16,51 +19,82 @@ fn extract_method_name_length(message: &str) -> Option<u32> {
     None
 }
 
-/// Convert MethodRay Diagnostic to LSP Diagnostic
-pub fn to_lsp_diagnostic(diag: &MethodRayDiagnostic) -> Diagnostic {
-    let severity = match diag.level {
-        DiagnosticLevel::Error => DiagnosticSeverity::ERROR,
-        DiagnosticLevel::Warning => DiagnosticSeverity::WARNING,
-    };
-
-    let start_line = if diag.location.line > 0 {
-        (diag.location.line - 1) as u32
+/// Convert a MethodRay location to an LSP range
+fn to_lsp_range(location: &Location, fallback_length: Option<u32>) -> Range {
+    let start_line = if location.line > 0 {
+        (location.line - 1) as u32
     } else {
         0
     };
 
-    let start_char = if diag.location.column > 0 {
-        (diag.location.column - 1) as u32
+    let start_char = if location.column > 0 {
+        (location.column - 1) as u32
     } else {
         0
     };
 
-    // Use actual source length if available, otherwise extract from message
-    let highlight_length = diag
-        .location
+    // Use actual source length if available, otherwise the fallback
+    let highlight_length = location
         .length
         .map(|len| len as u32)
-        .or_else(|| extract_method_name_length(&diag.message))
+        .or(fallback_length)
         .unwrap_or(5);
     let end_char = start_char + highlight_length;
 
-    Diagnostic {
-        range: Range {
-            start: Position {
-                line: start_line,
-                character: start_char,
+    Range {
+        start: Position {
+            line: start_line,
+            character: start_char,
+        },
+        end: Position {
+            line: start_line,
+            character: end_char,
+        },
+    }
+}
+
+/// Convert MethodRay Diagnostic to LSP Diagnostic
+///
+/// Related information always refers to the document being checked, so it
+/// is reported against `uri`. "Did you mean?" suggestions are reported as
+/// related information at the diagnostic's own range.
+pub fn to_lsp_diagnostic(diag: &MethodRayDiagnostic, uri: &Url) -> Diagnostic {
+    let severity = match diag.level {
+        DiagnosticLevel::Error => DiagnosticSeverity::ERROR,
+        DiagnosticLevel::Warning => DiagnosticSeverity::WARNING,
+    };
+
+    let range = to_lsp_range(&diag.location, extract_method_name_length(&diag.message));
+
+    let mut related_information: Vec<DiagnosticRelatedInformation> = diag
+        .related
+        .iter()
+        .map(|related| DiagnosticRelatedInformation {
+            location: LspLocation {
+                uri: uri.clone(),
+                range: to_lsp_range(&related.location, None),
             },
-            end: Position {
-                line: start_line,
-                character: end_char,
+            message: related.message.clone(),
+        })
+        .collect();
+    if let Some(message) = diag.suggestion_message() {
+        related_information.push(DiagnosticRelatedInformation {
+            location: LspLocation {
+                uri: uri.clone(),
+                range,
             },
-        },
+            message,
+        });
+    }
+
+    Diagnostic {
+        range,
         severity: Some(severity),
-        code: None,
+        code: diag.code.clone().map(NumberOrString::String),
         code_description: None,
         source: Some("methodray".to_string()),
         message: diag.message.clone(),
-        related_information: None,
+        related_information: (!related_information.is_empty()).then_some(related_information),
         tags: None,
         data: None,
     }
@@ -69,12 +103,14 @@ pub fn to_lsp_diagnostic(diag: &MethodRayDiagnostic) -> Diagnostic {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::diagnostics::Location;
+    use std::path::PathBuf;
+
+    fn test_uri() -> Url {
+        Url::parse("file:///tmp/test.rb").unwrap()
+    }
 
     #[test]
     fn test_to_lsp_diagnostic() {
-        use std::path::PathBuf;
-
         let methodray_diag = MethodRayDiagnostic {
             level: DiagnosticLevel::Error,
             location: Location {
@@ -85,9 +121,11 @@ mod tests {
             },
             message: "undefined method `upcase` for Integer".to_string(),
             code: None,
+            related: Vec::new(),
+            suggestions: Vec::new(),
         };
 
-        let lsp_diag = to_lsp_diagnostic(&methodray_diag);
+        let lsp_diag = to_lsp_diagnostic(&methodray_diag, &test_uri());
 
         assert_eq!(lsp_diag.range.start.line, 4); // 0-indexed
         assert_eq!(lsp_diag.range.start.character, 9); // 0-indexed
@@ -111,8 +149,6 @@ mod tests {
 
     #[test]
     fn test_highlight_length_for_downcase() {
-        use std::path::PathBuf;
-
         let methodray_diag = MethodRayDiagnostic {
             level: DiagnosticLevel::Error,
             location: Location {
@@ -123,11 +159,61 @@ mod tests {
             },
             message: "undefined method `downcase` for Integer".to_string(),
             code: None,
+            related: Vec::new(),
+            suggestions: Vec::new(),
         };
 
-        let lsp_diag = to_lsp_diagnostic(&methodray_diag);
+        let lsp_diag = to_lsp_diagnostic(&methodray_diag, &test_uri());
 
         assert_eq!(lsp_diag.range.start.character, 4); // column 5 -> 0-indexed = 4
         assert_eq!(lsp_diag.range.end.character, 12); // start(4) + length(8)
     }
+
+    #[test]
+    fn test_nil_receiver_related_information() {
+        let location = |line, column, length| Location {
+            file: PathBuf::from("test.rb"),
+            line,
+            column,
+            length: Some(length),
+        };
+
+        let methodray_diag =
+            MethodRayDiagnostic::nil_receiver(location(3, 1, 8), "upcase", Some(location(2, 5, 3)));
+
+        let lsp_diag = to_lsp_diagnostic(&methodray_diag, &test_uri());
+
+        assert_eq!(
+            lsp_diag.code,
+            Some(NumberOrString::String("MR002".to_string()))
+        );
+        let related = lsp_diag.related_information.unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].location.uri, test_uri());
+        assert_eq!(related[0].location.range.start.line, 1);
+        assert_eq!(related[0].location.range.start.character, 4);
+        assert_eq!(related[0].message, "nil introduced here");
+    }
+
+    #[test]
+    fn test_suggestions_as_related_information() {
+        let methodray_diag = MethodRayDiagnostic::undefined_method(
+            Location {
+                file: PathBuf::from("test.rb"),
+                line: 1,
+                column: 6,
+                length: Some(6),
+            },
+            "String",
+            "lenght",
+        )
+        .with_suggestions(vec!["length".to_string()]);
+
+        let lsp_diag = to_lsp_diagnostic(&methodray_diag, &test_uri());
+
+        let related = lsp_diag.related_information.unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].location.range, lsp_diag.range);
+        assert_eq!(related[0].message, "did you mean `length`?");
+    }
 }
@@ -1,5 +1,6 @@
 use anyhow::Context;
 use std::collections::HashMap;
+use std::path::Path;
 use tokio::sync::RwLock;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
@@ -7,6 +8,7 @@ use tower_lsp::{Client, LanguageServer, LspService, Server};
 
 use super::diagnostics::to_lsp_diagnostic;
 use crate::checker::FileChecker;
+use crate::config::Config;
 
 pub struct MethodRayServer {
     client: Client,
@@ -46,22 +48,22 @@ impl MethodRayServer {
             .to_file_path()
             .map_err(|_| anyhow::anyhow!("Invalid file URI: {}", uri))?;
 
-        // Create a temporary file for checking
-        let temp_dir = tempfile::tempdir()?;
-        let temp_file = temp_dir.path().join("temp.rb");
-        std::fs::write(&temp_file, source)?;
+        // The configuration of the project containing the document
+        let config = Config::discover(file_path.parent().unwrap_or(Path::new(".")))?;
 
-        // Run type check using FileChecker
-        let checker = FileChecker::new().with_context(|| "Failed to create FileChecker")?;
+        // Run type check using FileChecker (requires resolve from the real path)
+        let checker = FileChecker::new()
+            .and_then(|checker| checker.with_config(config))
+            .with_context(|| "Failed to create FileChecker")?;
 
         let methodray_diagnostics = checker
-            .check_file(&temp_file)
+            .check_source(&file_path, source)
             .with_context(|| format!("Failed to check file: {}", file_path.display()))?;
 
         // Convert to LSP diagnostics
         let lsp_diagnostics = methodray_diagnostics
             .iter()
-            .map(to_lsp_diagnostic)
+            .map(|diag| to_lsp_diagnostic(diag, uri))
             .collect();
 
         Ok(lsp_diagnostics)
@@ -7,6 +7,7 @@ mod analyzer;
 mod cache;
 mod checker;
 mod cli;
+mod config;
 mod diagnostics;
 mod env;
 mod graph;
@@ -21,18 +22,37 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Check { file, verbose } => {
-            if let Some(file_path) = file {
-                let success = commands::check_single_file(&file_path, verbose)?;
+        Commands::Check {
+            file,
+            verbose,
+            load_paths,
+            generate_baseline,
+            baseline,
+            config,
+            severities,
+        } => {
+            let config = commands::load_config(config.as_deref(), &load_paths, &severities)?;
+            let baseline = baseline.unwrap_or_else(|| commands::default_baseline_path(&config));
+            if generate_baseline {
+                commands::generate_baseline(file.as_deref(), &config, &baseline)?;
+            } else if let Some(file_path) = file {
+                let success = commands::check_single_file(&file_path, verbose, &config, &baseline)?;
                 if !success {
                     std::process::exit(1);
                 }
             } else {
-                commands::check_project(verbose)?;
+                let success = commands::check_project(verbose, &config, &baseline)?;
+                if !success {
+                    std::process::exit(1);
+                }
             }
         }
-        Commands::Watch { file } => {
-            commands::watch_file(&file)?;
+        Commands::Watch { file, config } => {
+            let config = commands::load_config(config.as_deref(), &[], &[])?;
+            commands::watch_file(&file, &config)?;
+        }
+        Commands::Explain { code } => {
+            commands::explain(code.as_deref())?;
         }
         Commands::Version => {
             commands::print_version();
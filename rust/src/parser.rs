@@ -1,3 +1,4 @@
+use crate::source_map::SourceLocation;
 use anyhow::{Context, Result};
 use ruby_prism::{parse, ParseResult};
 use std::fs;
@@ -43,6 +44,100 @@ pub fn parse_ruby_source(source: &str, file_name: String) -> Result<ParseResult<
     Ok(parse_result)
 }
 
+/// A syntax error found by the parser
+#[derive(Debug, Clone)]
+pub struct SyntaxError {
+    pub message: String,
+    pub location: SourceLocation,
+}
+
+/// Syntax errors of Ruby source code, with their locations
+pub fn syntax_errors(source: &str) -> Vec<SyntaxError> {
+    let parse_result = parse(source.as_bytes());
+    parse_result
+        .errors()
+        .map(|e| SyntaxError {
+            message: e.message().to_string(),
+            location: SourceLocation::from_prism_location_with_source(&e.location(), source),
+        })
+        .collect()
+}
+
+/// Directive of a suppression comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionKind {
+    /// `# methodray:disable-line`: the comment's own line
+    DisableLine,
+    /// `# methodray:disable-next-line`: the line after the comment
+    DisableNextLine,
+    /// `# methodray:disable`: every line until the matching `enable`
+    Disable,
+    /// `# methodray:enable`
+    Enable,
+}
+
+impl SuppressionKind {
+    fn parse(directive: &str) -> Option<Self> {
+        match directive {
+            "disable-line" => Some(Self::DisableLine),
+            "disable-next-line" => Some(Self::DisableNextLine),
+            "disable" => Some(Self::Disable),
+            "enable" => Some(Self::Enable),
+            _ => None,
+        }
+    }
+
+    /// Directive as written in the comment
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DisableLine => "disable-line",
+            Self::DisableNextLine => "disable-next-line",
+            Self::Disable => "disable",
+            Self::Enable => "enable",
+        }
+    }
+}
+
+/// A `# methodray:disable-line MR001` comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuppressionComment {
+    pub kind: SuppressionKind,
+    /// Diagnostic codes the comment applies to (empty: every code)
+    pub codes: Vec<String>,
+    pub location: SourceLocation,
+}
+
+/// Collect the suppression comments of a parsed program
+///
+/// Codes are separated by commas or spaces; text after `--` is a free-form
+/// reason (`# methodray:disable-line MR001 -- defined by method_missing`).
+pub fn suppression_comments(parse_result: &ParseResult, source: &str) -> Vec<SuppressionComment> {
+    parse_result
+        .comments()
+        .filter_map(|comment| {
+            let text = String::from_utf8_lossy(comment.text());
+            let body = text
+                .strip_prefix('#')?
+                .trim_start()
+                .strip_prefix("methodray:")?;
+            let body = body.split("--").next().unwrap_or_default();
+
+            let mut words = body
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|word| !word.is_empty());
+            let kind = SuppressionKind::parse(words.next()?)?;
+            Some(SuppressionComment {
+                kind,
+                codes: words.map(str::to_uppercase).collect(),
+                location: SourceLocation::from_prism_location_with_source(
+                    &comment.location(),
+                    source,
+                ),
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -86,6 +181,45 @@ end"#;
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_suppression_comments() {
+        let source = r#"# frozen_string_literal: true
+user.nmae # methodray:disable-line MR001, mr008 -- defined by method_missing
+# methodray:disable-next-line
+# methodray:disable MR002
+# methodray:enable
+# methodray:ignore MR001
+x = 1 # methodray disable-line"#;
+        let parse_result = parse_ruby_source(source, "test.rb".to_string()).unwrap();
+
+        let comments = suppression_comments(&parse_result, source);
+        let directives: Vec<(SuppressionKind, Vec<&str>, usize)> = comments
+            .iter()
+            .map(|comment| {
+                let codes = comment.codes.iter().map(String::as_str).collect();
+                (comment.kind, codes, comment.location.line)
+            })
+            .collect();
+        assert_eq!(
+            directives,
+            vec![
+                (SuppressionKind::DisableLine, vec!["MR001", "MR008"], 2),
+                (SuppressionKind::DisableNextLine, vec![], 3),
+                (SuppressionKind::Disable, vec!["MR002"], 4),
+                (SuppressionKind::Enable, vec![], 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert!(syntax_errors("x = 1").is_empty());
+
+        let errors = syntax_errors("x = 1\ndef\nend end");
+        assert!(!errors.is_empty());
+        assert_eq!(errors[0].location.line, 3);
+    }
+
     #[test]
     fn test_parse_method_call() {
         let source = r#"user = User.new
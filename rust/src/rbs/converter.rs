@@ -5,9 +5,9 @@ pub struct RbsTypeConverter;
 
 impl RbsTypeConverter {
     pub fn parse(rbs_type: &str) -> Type {
-        // Handle union types
-        if rbs_type.contains(" | ") {
-            let parts: Vec<&str> = rbs_type.split(" | ").collect();
+        // Handle union types (only at the top level, not inside a tuple)
+        let parts = Self::split_top_level(rbs_type, '|');
+        if parts.len() > 1 {
             let types: Vec<Type> = parts.iter().map(|s| Self::parse_single(s.trim())).collect();
             return Type::Union(types);
         }
@@ -15,7 +15,46 @@ impl RbsTypeConverter {
         Self::parse_single(rbs_type)
     }
 
+    /// Split on `separator` outside of brackets and parentheses
+    fn split_top_level(rbs_type: &str, separator: char) -> Vec<&str> {
+        let mut parts = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+
+        for (i, c) in rbs_type.char_indices() {
+            match c {
+                '[' | '(' | '{' => depth += 1,
+                ']' | ')' | '}' => depth = depth.saturating_sub(1),
+                c if c == separator && depth == 0 => {
+                    parts.push(&rbs_type[start..i]);
+                    start = i + c.len_utf8();
+                }
+                _ => {}
+            }
+        }
+        parts.push(&rbs_type[start..]);
+
+        parts
+    }
+
     fn parse_single(rbs_type: &str) -> Type {
+        // Optional type: String? => String | nil
+        if let Some(inner) = rbs_type.strip_suffix('?') {
+            let inner = inner.trim_start_matches('(').trim_end_matches(')');
+            let mut types = Self::parse(inner).union_members();
+            types.push(Type::Nil);
+            return Type::Union(types);
+        }
+
+        // Tuple type: [Integer, String]
+        if let Some(inner) = rbs_type.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            let types = Self::split_top_level(inner, ',')
+                .into_iter()
+                .map(|s| Self::parse(s.trim()))
+                .collect();
+            return Type::Tuple(types);
+        }
+
         let type_name = rbs_type.trim_start_matches("::");
 
         match type_name {
@@ -70,6 +109,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_optional_types() {
+        match RbsTypeConverter::parse("::String?") {
+            Type::Union(types) => {
+                assert_eq!(types, vec![Type::string(), Type::Nil]);
+            }
+            _ => panic!("Expected Union type for optional"),
+        }
+    }
+
     #[test]
     fn test_parse_union_types() {
         match RbsTypeConverter::parse("String | Integer") {
@@ -79,4 +128,26 @@ mod tests {
             _ => panic!("Expected Union type"),
         }
     }
+
+    #[test]
+    fn test_parse_tuple_types() {
+        assert_eq!(
+            RbsTypeConverter::parse("[Integer, String]"),
+            Type::Tuple(vec![Type::integer(), Type::string()])
+        );
+        assert_eq!(
+            RbsTypeConverter::parse("[Integer | nil, ::String]"),
+            Type::Tuple(vec![
+                Type::Union(vec![Type::integer(), Type::Nil]),
+                Type::string()
+            ])
+        );
+        assert_eq!(
+            RbsTypeConverter::parse("[Integer, String] | nil"),
+            Type::Union(vec![
+                Type::Tuple(vec![Type::integer(), Type::string()]),
+                Type::Nil
+            ])
+        );
+    }
 }
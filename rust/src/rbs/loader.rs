@@ -1,3 +1,4 @@
+use crate::env::method_registry::Visibility;
 use crate::env::GlobalEnv;
 use crate::rbs::converter::RbsTypeConverter;
 use crate::rbs::error::RbsError;
@@ -12,6 +13,7 @@ pub struct RbsMethodInfo {
     pub method_name: String,
     pub return_type: Type,
     pub block_param_types: Option<Vec<String>>,
+    pub visibility: Visibility,
 }
 
 /// Loader that calls RBS API via magnus to load method information
@@ -35,15 +37,20 @@ impl<'a> RbsLoader<'a> {
         Ok(Self { ruby })
     }
 
-    /// Load all method definitions from RBS
-    pub fn load_methods(&self) -> Result<Vec<RbsMethodInfo>, RbsError> {
-        // Load method_loader.rb
+    /// Load method_loader.rb
+    fn require_method_loader(&self) -> Result<(), RbsError> {
         let rb_path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/rbs/method_loader.rb");
         let load_code = format!("require '{}'", rb_path);
         let _: Value = self
             .ruby
             .eval(&load_code)
             .map_err(|e| RbsError::LoadError(format!("Failed to load method_loader.rb: {}", e)))?;
+        Ok(())
+    }
+
+    /// Load all method definitions from RBS
+    pub fn load_methods(&self) -> Result<Vec<RbsMethodInfo>, RbsError> {
+        self.require_method_loader()?;
 
         // Instantiate Rbs::MethodLoader class and call method
         let results: Value = self
@@ -59,6 +66,28 @@ impl<'a> RbsLoader<'a> {
         self.parse_results(results)
     }
 
+    /// Load the names of all classes, modules and constants declared in RBS
+    pub fn load_constant_names(&self) -> Result<Vec<String>, RbsError> {
+        self.require_method_loader()?;
+
+        let results: Value = self
+            .ruby
+            .eval("Rbs::MethodLoader.new.load_constant_names")
+            .map_err(|e| {
+                RbsError::LoadError(format!(
+                    "Failed to call Rbs::MethodLoader#load_constant_names: {}",
+                    e
+                ))
+            })?;
+
+        let names = RArray::try_convert(results)
+            .map_err(|e| RbsError::ParseError(format!("Failed to convert to array: {}", e)))?;
+        Ok(names
+            .into_iter()
+            .filter_map(|name: Value| String::try_convert(name).ok())
+            .collect())
+    }
+
     /// Convert Ruby array results to Vec of RbsMethodInfo structs
     fn parse_results(&self, results: Value) -> Result<Vec<RbsMethodInfo>, RbsError> {
         let mut method_infos = Vec::new();
@@ -124,11 +153,19 @@ impl<'a> RbsLoader<'a> {
                     None
                 };
 
+            // Parse visibility (optional, public when missing)
+            let visibility = hash
+                .get(self.ruby.to_symbol("visibility"))
+                .and_then(|value| String::try_convert(value).ok())
+                .and_then(|name| Visibility::parse(&name))
+                .unwrap_or_default();
+
             method_infos.push(RbsMethodInfo {
                 receiver_class,
                 method_name,
                 return_type,
                 block_param_types,
+                visibility,
             });
         }
 
@@ -141,7 +178,7 @@ impl<'a> RbsLoader<'a> {
 pub fn register_rbs_methods(genv: &mut GlobalEnv, ruby: &Ruby) -> Result<usize, Error> {
     use crate::cache::RbsCache;
 
-    let methodray_version = env!("CARGO_PKG_VERSION");
+    let methodray_version = RbsCache::current_version();
 
     // Try to get RBS version
     let rbs_version_value: Value = ruby
@@ -151,19 +188,22 @@ pub fn register_rbs_methods(genv: &mut GlobalEnv, ruby: &Ruby) -> Result<usize,
         String::try_convert(rbs_version_value).unwrap_or_else(|_| "unknown".to_string());
 
     // Try to load from cache
-    let methods = if let Ok(cache) = RbsCache::load() {
-        if cache.is_valid(methodray_version, &rbs_version) {
-            cache.to_method_infos()
+    let (methods, constants) = if let Ok(cache) = RbsCache::load() {
+        if cache.is_valid(&methodray_version, &rbs_version) {
+            (cache.to_method_infos(), cache.constants().to_vec())
         } else {
             eprintln!("Cache invalid, reloading from RBS...");
-            let methods = load_and_cache_rbs_methods(ruby, methodray_version, &rbs_version)?;
-            methods
+            load_and_cache_rbs_methods(ruby, &methodray_version, &rbs_version)?
         }
     } else {
         eprintln!("No cache found, loading from RBS...");
-        load_and_cache_rbs_methods(ruby, methodray_version, &rbs_version)?
+        load_and_cache_rbs_methods(ruby, &methodray_version, &rbs_version)?
     };
 
+    for constant in &constants {
+        genv.register_builtin_constant(constant);
+    }
+
     let count = methods.len();
     for method_info in methods {
         let receiver_type = Type::Instance {
@@ -177,30 +217,39 @@ pub fn register_rbs_methods(genv: &mut GlobalEnv, ruby: &Ruby) -> Result<usize,
                 .collect()
         });
         genv.register_builtin_method_with_block(
-            receiver_type,
+            receiver_type.clone(),
             &method_info.method_name,
             method_info.return_type,
             block_param_types,
         );
+        if method_info.visibility != Visibility::Public {
+            genv.set_method_visibility(
+                &receiver_type,
+                &method_info.method_name,
+                method_info.visibility,
+            );
+        }
     }
 
     Ok(count)
 }
 
-/// Load RBS methods and save to cache
+/// Load RBS methods and constant names and save to cache
 fn load_and_cache_rbs_methods(
     ruby: &Ruby,
     version: &str,
     rbs_version: &str,
-) -> Result<Vec<RbsMethodInfo>, Error> {
+) -> Result<(Vec<RbsMethodInfo>, Vec<String>), Error> {
     use crate::cache::RbsCache;
 
     let loader = RbsLoader::new(ruby)?;
     let methods = loader.load_methods()?;
+    let constants = loader.load_constant_names()?;
 
     // Save to cache
     let cache = RbsCache::from_method_infos(
         methods.clone(),
+        constants.clone(),
         version.to_string(),
         rbs_version.to_string(),
     );
@@ -211,5 +260,5 @@ fn load_and_cache_rbs_methods(
         eprintln!("Saved {} methods to cache", methods.len());
     }
 
-    Ok(methods)
+    Ok((methods, constants))
 }
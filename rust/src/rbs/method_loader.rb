@@ -1,5 +1,6 @@
 # frozen_string_literal: true
 
+require 'pathname'
 require 'rbs'
 
 # TODO: use ruby-rbs crate when available
@@ -7,15 +8,26 @@ require 'rbs'
 module Rbs
   class MethodLoader
     TARGET_CLASSES = %w[
-      String Integer Float Array Hash Symbol
+      Object String Integer Float Array Hash Symbol
       TrueClass FalseClass NilClass
-      Range Regexp Struct Enumerable
+      Range Regexp Struct Data Enumerable IO
     ].freeze
 
-    def initialize
+    # signature_dirs: project signature directories (e.g. "sig"), loaded
+    # on top of the core signatures
+    def initialize(signature_dirs = [])
       loader = ::RBS::EnvironmentLoader.new
-      env = ::RBS::Environment.from_loader(loader).resolve_type_names
-      @builder = ::RBS::DefinitionBuilder.new(env: env)
+      signature_dirs.each { |dir| loader.add(path: Pathname(dir)) }
+      @signature_dirs = signature_dirs.map { |dir| "#{File.expand_path(dir)}/" }
+      @env = ::RBS::Environment.from_loader(loader).resolve_type_names
+      @builder = ::RBS::DefinitionBuilder.new(env: @env)
+    end
+
+    # Names of every class, module and constant declared in RBS
+    # (e.g. "String", "File::SEPARATOR"), without the leading "::"
+    def load_constant_names
+      type_names = @env.class_decls.keys + @env.class_alias_decls.keys + @env.constant_decls.keys
+      type_names.map { |type_name| type_name.to_s.delete_prefix('::') }.uniq
     end
 
     def load_methods
@@ -42,7 +54,8 @@ module Rbs
             receiver_class: class_name,
             method_name: method_name.to_s,
             return_type: return_type,
-            block_param_types: block_param_types
+            block_param_types: block_param_types,
+            visibility: method_def.accessibility.to_s
           }
         end
       rescue StandardError => e
@@ -52,8 +65,68 @@ module Rbs
       results
     end
 
+    # Declarations of the signature directories:
+    # - constants: qualified names of the classes, modules and constants
+    # - methods: instance and singleton methods of the declared classes and
+    #   modules, with receiver and return types named by their last segment
+    #   (the analyzer's short class names, e.g. "Invoice" for Billing::Invoice)
+    def load_signatures
+      { constants: signature_constant_names, methods: signature_methods }
+    end
+
     private
 
+    def signature_constant_names
+      declarations = @env.class_decls.map { |type_name, entry| [type_name, entry.each_decl.to_a] } +
+                     @env.class_alias_decls.map { |type_name, entry| [type_name, [entry.decl]] } +
+                     @env.constant_decls.map { |type_name, entry| [type_name, [entry.decl]] }
+      declarations.filter_map do |type_name, decls|
+        type_name.to_s.delete_prefix('::') if decls.any? { |decl| signature_decl?(decl) }
+      end.uniq
+    end
+
+    def signature_methods
+      type_names = @env.class_decls.filter_map do |type_name, entry|
+        type_name if entry.each_decl.any? { |decl| signature_decl?(decl) }
+      end
+
+      type_names.flat_map do |type_name|
+        [false, true].flat_map do |singleton|
+          definition = singleton ? @builder.build_singleton(type_name) : @builder.build_instance(type_name)
+          # Methods from the core classes are in the RBS cache
+          definition.methods.filter_map do |method_name, method_def|
+            next unless type_names.include?(method_def.implemented_in)
+
+            method_type = method_def.method_types.first
+            next unless method_type
+
+            {
+              receiver_class: type_name.name.to_s,
+              singleton: singleton,
+              method_name: method_name.to_s,
+              return_type: short_type_names(method_type.type.return_type).to_s
+            }
+          end
+        rescue StandardError => e
+          warn "Skipped #{type_name}: #{e.message}" if ENV['DEBUG']
+          []
+        end
+      end
+    end
+
+    # Declared in a file of the signature directories
+    def signature_decl?(decl)
+      path = decl.location&.buffer&.name
+      path && File.expand_path(path.to_s).start_with?(*@signature_dirs)
+    end
+
+    # The type with each class name reduced to its last segment
+    def short_type_names(type)
+      type.map_type_name do |type_name, _location, _kind|
+        ::RBS::TypeName.new(name: type_name.name, namespace: ::RBS::Namespace.empty)
+      end
+    end
+
     # Extract block parameter types from method_type
     # Returns nil if no block, or array of type strings
     def extract_block_param_types(method_type)
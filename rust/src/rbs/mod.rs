@@ -1,7 +1,9 @@
 //! RBS type loading and conversion
 
-// Converter is always available (no Ruby FFI dependency)
+// Converter and the signature reader are always available (the reader runs
+// the RBS loader with `ruby` rather than through Ruby FFI)
 pub mod converter;
+pub mod signature;
 pub use converter::RbsTypeConverter;
 
 // These require Ruby FFI for RBS loading
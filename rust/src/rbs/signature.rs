@@ -0,0 +1,185 @@
+//! Project signatures: the RBS files of the signature directories (`sig/`)
+//!
+//! The RBS cache covers the core classes only. Project signatures are read
+//! by the same RBS loader (`Rbs::MethodLoader`), run with `ruby` since the
+//! checker has no Ruby runtime of its own. Receivers and return types use
+//! the analyzer's short class names (`Invoice` for `Billing::Invoice`);
+//! constants keep their qualified names.
+
+use super::converter::RbsTypeConverter;
+use crate::types::Type;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Ruby script printing the declarations of the directories given as
+/// arguments, as JSON
+const LOAD_SCRIPT: &str = concat!(
+    include_str!("method_loader.rb"),
+    "\nrequire 'json'\nputs JSON.generate(Rbs::MethodLoader.new(ARGV).load_signatures)\n"
+);
+
+/// Declarations read from signature files
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Signatures {
+    /// Qualified names of the classes, modules and constants declared
+    pub constants: Vec<String>,
+    pub methods: Vec<SignatureMethod>,
+}
+
+/// A method declared in a signature file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureMethod {
+    /// `Type::Instance`, or `Type::Singleton` for `def self.name`
+    pub receiver: Type,
+    pub method_name: String,
+    pub return_type: Type,
+}
+
+/// Declarations as printed by the loader
+#[derive(Debug, Deserialize)]
+struct LoadedSignatures {
+    constants: Vec<String>,
+    methods: Vec<LoadedMethod>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LoadedMethod {
+    receiver_class: String,
+    singleton: bool,
+    method_name: String,
+    return_type: String,
+}
+
+impl Signatures {
+    /// Read every `.rbs` file under the directories
+    pub fn load_dirs(dirs: &[PathBuf]) -> Result<Self> {
+        if dirs.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let output = Command::new("ruby")
+            .arg("-e")
+            .arg(LOAD_SCRIPT)
+            .arg("--")
+            .args(dirs)
+            .output()
+            .context("Failed to run ruby to load the signature directories")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to load the signature directories: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Self::from_json(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Read the declarations printed by the loader
+    fn from_json(json: &str) -> Result<Self> {
+        let loaded: LoadedSignatures =
+            serde_json::from_str(json).context("Invalid output of the signature loader")?;
+
+        let methods = loaded
+            .methods
+            .into_iter()
+            .map(|method| {
+                let class_name = method.receiver_class;
+                let return_type =
+                    resolve_self(RbsTypeConverter::parse(&method.return_type), &class_name);
+                SignatureMethod {
+                    receiver: if method.singleton {
+                        Type::Singleton { class_name }
+                    } else {
+                        Type::Instance { class_name }
+                    },
+                    method_name: method.method_name,
+                    return_type,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            constants: loaded.constants,
+            methods,
+        })
+    }
+}
+
+/// `self` and `instance` in a return type are the declaring class
+fn resolve_self(return_type: Type, class_name: &str) -> Type {
+    match return_type {
+        Type::Instance { class_name: name } if name == "self" || name == "instance" => {
+            Type::Instance {
+                class_name: class_name.to_string(),
+            }
+        }
+        Type::Union(types) => Type::Union(
+            types
+                .into_iter()
+                .map(|member| resolve_self(member, class_name))
+                .collect(),
+        ),
+        return_type => return_type,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(class_name: &str) -> Type {
+        Type::Instance {
+            class_name: class_name.to_string(),
+        }
+    }
+
+    fn method<'a>(signatures: &'a Signatures, receiver: &Type, name: &str) -> Option<&'a Type> {
+        signatures
+            .methods
+            .iter()
+            .find(|method| &method.receiver == receiver && method.method_name == name)
+            .map(|method| &method.return_type)
+    }
+
+    #[test]
+    fn test_from_json() {
+        let signatures = Signatures::from_json(
+            r#"{
+  "constants": ["Billing", "Billing::VERSION", "Billing::Invoice"],
+  "methods": [
+    {"receiver_class": "Invoice", "singleton": false, "method_name": "total", "return_type": "Integer"},
+    {"receiver_class": "Invoice", "singleton": true, "method_name": "find", "return_type": "Invoice?"},
+    {"receiver_class": "Invoice", "singleton": false, "method_name": "each_line", "return_type": "self"}
+  ]
+}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            signatures.constants,
+            vec!["Billing", "Billing::VERSION", "Billing::Invoice"]
+        );
+        let invoice = instance("Invoice");
+        assert_eq!(
+            method(&signatures, &invoice, "total"),
+            Some(&Type::integer())
+        );
+        let invoice_class = Type::Singleton {
+            class_name: "Invoice".to_string(),
+        };
+        // Receivers and return types are named alike
+        assert_eq!(
+            method(&signatures, &invoice_class, "find"),
+            Some(&Type::Union(vec![invoice.clone(), Type::Nil]))
+        );
+        assert_eq!(method(&signatures, &invoice, "each_line"), Some(&invoice));
+        assert_eq!(method(&signatures, &invoice, "find"), None);
+    }
+
+    #[test]
+    fn test_load_no_dirs() {
+        assert_eq!(Signatures::load_dirs(&[]).unwrap(), Signatures::default());
+    }
+}
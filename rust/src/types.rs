@@ -15,6 +15,10 @@ pub enum Type {
     Nil,
     /// Union type: sum of multiple types
     Union(Vec<Type>),
+    /// Record type: symbol-keyed Hash with known keys, { name: String, age: Integer }
+    Record { fields: Vec<(String, Type)> },
+    /// Tuple type: Array with known per-position types, [Integer, String]
+    Tuple(Vec<Type>),
     /// Bottom type: no type information
     Bot,
 }
@@ -37,6 +41,17 @@ impl Type {
                 let names: Vec<_> = types.iter().map(|t| t.show()).collect();
                 names.join(" | ")
             }
+            Type::Record { fields } => {
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|(key, ty)| format!("{}: {}", key, ty.show()))
+                    .collect();
+                format!("{{ {} }}", fields.join(", "))
+            }
+            Type::Tuple(elements) => {
+                let elements: Vec<_> = elements.iter().map(|t| t.show()).collect();
+                format!("[{}]", elements.join(", "))
+            }
             Type::Bot => "untyped".to_string(),
         }
     }
@@ -47,6 +62,8 @@ impl Type {
             Type::Instance { class_name } => Some(class_name),
             Type::Generic { class_name, .. } => Some(class_name),
             Type::Singleton { class_name } => Some(class_name),
+            Type::Record { .. } => Some("Hash"),
+            Type::Tuple(_) => Some("Array"),
             _ => None,
         }
     }
@@ -59,6 +76,56 @@ impl Type {
         }
     }
 
+    /// Look up a field type of a record
+    pub fn record_field(&self, key: &str) -> Option<&Type> {
+        match self {
+            Type::Record { fields } => fields.iter().find(|(k, _)| k == key).map(|(_, ty)| ty),
+            _ => None,
+        }
+    }
+
+    /// Widen a record or tuple to the generic type it is an instance of
+    ///
+    /// `{ name: String, age: Integer }` becomes `Hash[Symbol, String | Integer]`
+    /// and `[Integer, String]` becomes `Array[Integer | String]`.
+    /// Other types are returned unchanged.
+    pub fn widen(&self) -> Type {
+        match self {
+            Type::Record { fields } => Type::hash_of(
+                Type::symbol(),
+                Self::union_of_distinct(fields.iter().map(|(_, ty)| ty)),
+            ),
+            Type::Tuple(elements) => Type::array_of(Self::union_of_distinct(elements.iter())),
+            _ => self.clone(),
+        }
+    }
+
+    /// Union of distinct types, in order of first appearance
+    fn union_of_distinct<'a>(types: impl Iterator<Item = &'a Type>) -> Type {
+        let mut distinct: Vec<Type> = Vec::new();
+        for ty in types {
+            if !distinct.contains(ty) {
+                distinct.push(ty.clone());
+            }
+        }
+
+        if distinct.len() == 1 {
+            distinct.pop().unwrap()
+        } else {
+            Type::Union(distinct)
+        }
+    }
+
+    /// Flatten a union into its member types
+    ///
+    /// Non-union types return themselves as the only member.
+    pub fn union_members(&self) -> Vec<Type> {
+        match self {
+            Type::Union(types) => types.iter().flat_map(|t| t.union_members()).collect(),
+            _ => vec![self.clone()],
+        }
+    }
+
     /// Convenience constructors
     pub fn string() -> Self {
         Type::Instance {
@@ -72,6 +139,12 @@ impl Type {
         }
     }
 
+    pub fn symbol() -> Self {
+        Type::Instance {
+            class_name: "Symbol".to_string(),
+        }
+    }
+
     pub fn array() -> Self {
         Type::Instance {
             class_name: "Array".to_string(),
@@ -92,6 +165,14 @@ impl Type {
         }
     }
 
+    /// Create a generic Range type: Range[element_type]
+    pub fn range_of(element_type: Type) -> Self {
+        Type::Generic {
+            class_name: "Range".to_string(),
+            type_args: vec![element_type],
+        }
+    }
+
     /// Create a generic Hash type: Hash[key_type, value_type]
     pub fn hash_of(key_type: Type, value_type: Type) -> Self {
         Type::Generic {
@@ -119,6 +200,49 @@ mod tests {
         assert_eq!(union.show(), "String | Integer");
     }
 
+    #[test]
+    fn test_record_show_and_widen() {
+        let record = Type::Record {
+            fields: vec![
+                ("name".to_string(), Type::string()),
+                ("age".to_string(), Type::integer()),
+            ],
+        };
+
+        assert_eq!(record.show(), "{ name: String, age: Integer }");
+        assert_eq!(record.base_class_name(), Some("Hash"));
+        assert_eq!(record.record_field("age"), Some(&Type::integer()));
+        assert_eq!(record.record_field("email"), None);
+        assert_eq!(record.widen().show(), "Hash[Symbol, String | Integer]");
+    }
+
+    #[test]
+    fn test_tuple_show_and_widen() {
+        let tuple = Type::Tuple(vec![Type::integer(), Type::string(), Type::integer()]);
+
+        assert_eq!(tuple.show(), "[Integer, String, Integer]");
+        assert_eq!(tuple.base_class_name(), Some("Array"));
+        assert_eq!(tuple.widen().show(), "Array[Integer | String]");
+    }
+
+    #[test]
+    fn test_range_of() {
+        assert_eq!(Type::range_of(Type::integer()).show(), "Range[Integer]");
+    }
+
+    #[test]
+    fn test_union_members() {
+        let nested = Type::Union(vec![
+            Type::string(),
+            Type::Union(vec![Type::integer(), Type::Nil]),
+        ]);
+        assert_eq!(
+            nested.union_members(),
+            vec![Type::string(), Type::integer(), Type::Nil]
+        );
+        assert_eq!(Type::string().union_members(), vec![Type::string()]);
+    }
+
     #[test]
     fn test_generic_type_show() {
         let array_int = Type::array_of(Type::integer());